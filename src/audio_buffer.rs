@@ -1,10 +1,20 @@
-use crate::{ AudioBufferDataLength, AudioEffect, AudioGenerator, DurationModifier, StereoShaper, VolumeAmplifier };
-use std::{ error::Error, ops::Add, time::Duration };
+use crate::{ AudioBufferDataLength, AudioEffect, AudioGenerator, AudioWelderError, DurationModifier, LoudnessMeter, Resampler, StereoShaper, VolumeAmplifier, WavFormat };
+use std::{ io::{ Seek, Write }, ops::Add, time::Duration };
 
 
 
 #[derive(Clone, PartialEq)]
-enum ProgressionTracker { Cursor(usize), Drain }
+enum ProgressionTracker { Cursor(usize), Drain(usize) }
+
+
+
+#[derive(Clone, PartialEq, Default)]
+struct EffectOutput {
+	data:Vec<Vec<f32>>,
+	input_len:usize,
+	output_len:usize,
+	flushed_len:usize
+}
 
 
 
@@ -14,7 +24,8 @@ pub struct AudioBuffer {
 	channel_count:usize,
 	sample_rate:u32,
 	effects:Vec<Box<dyn AudioEffect>>,
-	progression_tracker:ProgressionTracker
+	progression_tracker:ProgressionTracker,
+	effect_output:EffectOutput
 }
 impl AudioBuffer {
 
@@ -38,25 +49,39 @@ impl AudioBuffer {
 			channel_count,
 			sample_rate,
 			effects: Vec::new(),
-			progression_tracker: ProgressionTracker::Cursor(0)
+			progression_tracker: ProgressionTracker::Cursor(0),
+			effect_output: EffectOutput::default()
 		}
 	}
 
-	/// Read the wav file at the given filepath and return a buffer.
-	pub fn wav(file_path:&str) -> Result<AudioBuffer, Box<dyn Error>> {
-		use hound::{ WavReader, SampleFormat, WavSpec };
+	/// Read the wav file at the given filepath and return a buffer. Returns an error if any sample in the file could not be decoded.
+	pub fn wav(file_path:&str) -> Result<AudioBuffer, AudioWelderError> {
+		match AudioBuffer::wav_lenient(file_path)? {
+			(buffer, None) => Ok(buffer),
+			(_, Some(decode_error)) => Err(decode_error)
+		}
+	}
+
+	/// Read the wav file at the given filepath and return a buffer. When the file contains corrupt or truncated data, the samples decoded before the bad spot are kept and the decode error is returned alongside the buffer.
+	pub fn wav_lenient(file_path:&str) -> Result<(AudioBuffer, Option<AudioWelderError>), AudioWelderError> {
+		use hound::{ WavReader, WavSpec };
 		use std::{ fs::File, io::BufReader };
 		
 		// Read the WAV file using hound crate.
 		let wav_reader:WavReader<BufReader<File>> = WavReader::open(file_path)?;
 		let spec:WavSpec = wav_reader.spec();
 		let channel_count:usize = spec.channels as usize;
+		let format:WavFormat = match WavFormat::from_spec(&spec) {
+			Some(format) => format,
+			None => return Err(AudioWelderError::UnsupportedFormat(format!("{} bit {:?} wav data.", spec.bits_per_sample, spec.sample_format)))
+		};
 		
-		// Retrieve the audio data.
-		let mut sample_data:Vec<f32> = match spec.sample_format {
-			SampleFormat::Int => wav_reader.into_samples::<i16>().map(|sample| sample.unwrap() as f32 / i16::MAX as f32).collect(),
-			SampleFormat::Float => wav_reader.into_samples::<f32>().map(|s| s.unwrap()).collect(),
+		// Retrieve the audio data, scaling integer samples by the full-scale value of their bit depth.
+		let (mut sample_data, decode_error):(Vec<f32>, Option<(usize, hound::Error)>) = match format.int_scale() {
+			Some(scale) => AudioBuffer::decode_wav_samples(wav_reader.into_samples::<i32>(), |sample| (sample as f64 / scale) as f32),
+			None => AudioBuffer::decode_wav_samples(wav_reader.into_samples::<f32>(), |sample| sample)
 		};
+		let decode_error:Option<AudioWelderError> = decode_error.map(|(sample_index, error)| AudioWelderError::Decode(format!("Could not decode sample {sample_index} of wav file '{file_path}': {error}")));
 		if sample_data.len() % channel_count != 0 {
 			sample_data.extend(vec![0.0; channel_count - (sample_data.len() % channel_count)]);
 		}
@@ -73,7 +98,53 @@ impl AudioBuffer {
 		};
 
 		// Return audio buffer.
-		Ok(AudioBuffer::new(sample_data, spec.sample_rate))
+		Ok((AudioBuffer::new(sample_data, spec.sample_rate), decode_error))
+	}
+
+	/// Decode samples until the first error. Returns the decoded samples and, if decoding stopped early, the index of the failing sample and its error.
+	fn decode_wav_samples<T, U, V>(samples:T, converter:V) -> (Vec<f32>, Option<(usize, hound::Error)>) where T:Iterator<Item = hound::Result<U>>, V:Fn(U) -> f32 {
+		let mut sample_data:Vec<f32> = Vec::with_capacity(samples.size_hint().0);
+		for (sample_index, sample) in samples.enumerate() {
+			match sample {
+				Ok(sample) => sample_data.push(converter(sample)),
+				Err(error) => return (sample_data, Some((sample_index, error)))
+			}
+		}
+		(sample_data, None)
+	}
+
+
+
+	/* EXPORT METHODS */
+
+	/// Apply all scheduled effects and write the buffer to a wav file at the given filepath.
+	pub fn write_wav(&mut self, file_path:&str, format:WavFormat) -> Result<(), AudioWelderError> {
+		use std::{ fs::File, io::BufWriter };
+
+		self.write_wav_to(BufWriter::new(File::create(file_path)?), format)
+	}
+
+	/// Apply all scheduled effects and write the buffer as wav data to the given writer.
+	pub fn write_wav_to<T>(&mut self, writer:T, format:WavFormat) -> Result<(), AudioWelderError> where T:Write + Seek {
+		use hound::WavWriter;
+
+		// Apply effects.
+		self.apply_effects();
+
+		// Write interleaved samples.
+		let mut wav_writer:WavWriter<T> = WavWriter::new(writer, format.spec(self.channel_count, self.sample_rate))?;
+		for sample_index in 0..self.sample_size() {
+			for channel in &self.data {
+				match format {
+					WavFormat::Float32 => wav_writer.write_sample(channel[sample_index])?,
+					_ => wav_writer.write_sample(format.quantize(channel[sample_index]))?
+				}
+			}
+		}
+
+		// Finalize file.
+		wav_writer.finalize()?;
+		Ok(())
 	}
 
 
@@ -82,31 +153,56 @@ impl AudioBuffer {
 
 	/// Return self with a new sample rate and channel count.
 	pub fn resampled(mut self, sample_rate:u32, channel_count:usize) -> Self {
-		let sample_rate_multiplier:f32 = 1.0 / self.sample_rate as f32 * sample_rate as f32;
-		let channel_count_multiplier:f32 = 1.0 / self.channel_count as f32 * channel_count as f32;
-		let mut sample_rate_modifier:DurationModifier = DurationModifier::new_sample_rate_modifier(sample_rate);
-		let mut channel_count_modifier:StereoShaper = StereoShaper::new_channel_count_modifier(channel_count);
-		if sample_rate_multiplier < channel_count_multiplier {
-			sample_rate_modifier.apply_to(&mut self.data, &mut self.sample_rate, &mut self.channel_count);
-			channel_count_modifier.apply_to(&mut self.data, &mut self.sample_rate, &mut self.channel_count);
-		} else {
-			channel_count_modifier.apply_to(&mut self.data, &mut self.sample_rate, &mut self.channel_count);
-			sample_rate_modifier.apply_to(&mut self.data, &mut self.sample_rate, &mut self.channel_count);
-		}
+		AudioBuffer::apply_effect_to(&mut Resampler::new(sample_rate, channel_count), &mut self.data, &mut self.sample_rate, &mut self.channel_count);
 		self
 	}
 
 	/// Return self with draining progression tracker.
 	pub fn drain_progression(mut self) -> Self {
+		let mut drained:usize = 0;
 		if let ProgressionTracker::Cursor(cursor) = self.progression_tracker {
-			self.data.drain(..cursor);
+			self.data.iter_mut().for_each(|channel| { channel.drain(..cursor); });
+			drained = cursor;
 		}
-		self.progression_tracker = ProgressionTracker::Drain;
+		self.progression_tracker = ProgressionTracker::Drain(drained);
 		self
 	}
 
 
 
+	/* PROGRESSION METHODS */
+
+	/// Get the position of the progression tracker in the unprocessed audio.
+	pub fn position(&self) -> Duration {
+		let position:usize = match self.progression_tracker {
+			ProgressionTracker::Cursor(cursor) => cursor,
+			ProgressionTracker::Drain(drained) => drained
+		};
+		Duration::from_nanos(position as u64 * 1_000_000_000 / self.sample_rate as u64)
+	}
+
+	/// Move the progression tracker to the given position in the unprocessed audio. Draining buffers can only move forward.
+	pub fn seek(&mut self, position:Duration) {
+		let sample_size:usize = self.sample_size();
+		let target_position:usize = position.as_buffer_length(self.sample_rate);
+		self.effect_output = EffectOutput::default();
+		self.effects.iter_mut().for_each(|effect| effect.reset());
+		match &mut self.progression_tracker {
+			ProgressionTracker::Cursor(cursor) => {
+				*cursor = target_position.min(sample_size);
+			},
+			ProgressionTracker::Drain(drained) => {
+				if target_position > *drained {
+					let drain_len:usize = (target_position - *drained).min(sample_size);
+					self.data.iter_mut().for_each(|channel| { channel.drain(..drain_len); });
+					*drained += drain_len;
+				}
+			}
+		}
+	}
+
+
+
 	/* EFFECT METHODS */
 
 	/// Add a sample-rate modification. Does not apply it yet. The effect will be applied using the apply_effects method or when the audio is used.
@@ -114,6 +210,11 @@ impl AudioBuffer {
 		self.add_effect(DurationModifier::new_sample_rate_modifier(sample_rate));
 	}
 
+	/// Add a sample-rate and channel modification in a single effect. Does not apply it yet. The effect will be applied using the apply_effects method or when the audio is used.
+	pub fn resample(&mut self, sample_rate:u32, channel_count:usize) {
+		self.add_effect(Resampler::new(sample_rate, channel_count));
+	}
+
 	/// Add a channel modification. Does not apply it yet. The effect will be applied using the apply_effects method or when the audio is used.
 	pub fn resample_channel_count(&mut self, channel_count:usize) {
 		self.add_effect(StereoShaper::new_channel_count_modifier(channel_count));
@@ -146,13 +247,52 @@ impl AudioBuffer {
 				self.effects.remove(right_index);
 			}
 		}
+
+		// Let effects that depend on all data measure it now, so it does not happen while parts of the data are taken.
+		self.analyze_effects();
 	}
 
 	/// Apply all current scheduled effects.
 	pub fn apply_effects(&mut self) {
 		while !self.effects.is_empty() {
 			let mut effect:Box<dyn AudioEffect> = self.effects.remove(0);
-			effect.apply_to(&mut self.data, &mut self.sample_rate, &mut self.channel_count);
+			AudioBuffer::apply_effect_to(&mut *effect, &mut self.data, &mut self.sample_rate, &mut self.channel_count);
+		}
+	}
+
+	/// Let effects that need to measure all data do so, by applying copies of the effects before them to a copy of the data.
+	fn analyze_effects(&mut self) {
+		for effect_index in 0..self.effects.len() {
+			if self.effects[effect_index].needs_analysis() {
+				let mut data:Vec<Vec<f32>> = self.data.clone();
+				let mut sample_rate:u32 = self.sample_rate;
+				let mut channel_count:usize = self.channel_count;
+				for effect in &self.effects[..effect_index] {
+					AudioBuffer::apply_effect_to(&mut *effect.boxed(), &mut data, &mut sample_rate, &mut channel_count);
+				}
+				self.effects[effect_index].analyze(&data, sample_rate, channel_count);
+			}
+		}
+	}
+
+	/// Apply a single effect to the full given data. When the output of the effect lags behind its input or continues after it, silence is processed after the data until all output came out.
+	fn apply_effect_to(effect:&mut dyn AudioEffect, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, channel_count:&mut usize) {
+		let source_sample_rate:u32 = *sample_rate;
+		let source_channel_count:usize = *channel_count;
+		let source_data_channel_count:usize = data.len();
+		let tail_len:usize = effect.tail(source_sample_rate, source_channel_count);
+		let flush_len:usize = effect.latency(source_sample_rate, source_channel_count) + tail_len;
+		let target_len:usize = AudioBuffer::processed_len(data.first().map(|channel| channel.len()).unwrap_or(0), effect.sample_multiplier(source_sample_rate, source_channel_count)) + tail_len;
+		effect.apply_to(data, sample_rate, channel_count);
+
+		// Process silence to get the remaining output.
+		if flush_len > 0 {
+			let mut tail:Vec<Vec<f32>> = vec![vec![0.0; flush_len]; source_data_channel_count];
+			effect.apply_to(&mut tail, &mut source_sample_rate.clone(), &mut source_channel_count.clone());
+			for (channel, tail_channel) in data.iter_mut().zip(tail) {
+				channel.extend(tail_channel);
+				channel.truncate(target_len);
+			}
 		}
 	}
 
@@ -198,6 +338,28 @@ impl AudioBuffer {
 		self.data.iter().map(|channel| channel.len()).min().unwrap_or(0)
 	}
 
+	/// Get the amount of output samples that can be taken from the data that is currently in the buffer, without padding it with silence.
+	pub(crate) fn available_len(&self) -> usize {
+		let output_len:usize = self.effect_output.data.first().map(|channel| channel.len()).unwrap_or(0);
+		output_len + (self.unprocessed_len() as f32 * self.effects_duration_multiplier().abs()) as usize
+	}
+
+	/// Get the amount of samples of data that have not been processed yet.
+	fn unprocessed_len(&self) -> usize {
+		match self.progression_tracker {
+			ProgressionTracker::Cursor(cursor) => self.sample_size().saturating_sub(cursor),
+			ProgressionTracker::Drain(_) => self.sample_size()
+		}
+	}
+
+	/// Check if all unprocessed data has been taken.
+	fn input_depleted(&self) -> bool {
+		match self.progression_tracker {
+			ProgressionTracker::Cursor(cursor) => cursor >= self.sample_size(),
+			ProgressionTracker::Drain(_) => self.sample_size() == 0
+		}
+	}
+
 	/// Get the total duration multiplier created by the effects.
 	fn effects_duration_multiplier(&self) -> f32 {
 		let mut effect_sample_multiplier:f32 = 1.0;
@@ -207,9 +369,40 @@ impl AudioBuffer {
 		effect_sample_multiplier
 	}
 
-	/// Get the duration of the sample.
+	/// Get the amount of unprocessed samples of silence the effects need after the input until all of their output came out.
+	fn effects_flush_len(&self) -> usize {
+		let mut flush_len:f32 = 0.0;
+		let mut effect_sample_multiplier:f32 = 1.0;
+		for effect in &self.effects {
+			flush_len += (effect.latency(self.sample_rate, self.channel_count) + effect.tail(self.sample_rate, self.channel_count)) as f32 / effect_sample_multiplier;
+			effect_sample_multiplier *= effect.sample_multiplier(self.sample_rate, self.channel_count).abs();
+		}
+		flush_len.ceil() as usize
+	}
+
+	/// Get the amount of samples the output of the effects continues after the output of the input ends.
+	fn effects_tail(&self) -> usize {
+		let mut tail:f32 = 0.0;
+		for effect in &self.effects {
+			tail = tail * effect.sample_multiplier(self.sample_rate, self.channel_count).abs() + effect.tail(self.sample_rate, self.channel_count) as f32;
+		}
+		tail.ceil() as usize
+	}
+
+	/// Get the amount of samples effects with the given duration multiplier produce from the given amount of samples. Allows for the rounding error of the multiplier.
+	fn processed_len(sample_len:usize, multiplier:f32) -> usize {
+		(sample_len as f64 * multiplier.abs() as f64 * (1.0 - 1e-6)).ceil() as usize
+	}
+
+	/// Check if the effects produced all output of the data they processed.
+	fn effects_flushed(&self) -> bool {
+		let expected_output_len:usize = AudioBuffer::processed_len(self.effect_output.input_len, self.effects_duration_multiplier()) + self.effects_tail();
+		self.effect_output.flushed_len >= self.effects_flush_len() || self.effect_output.output_len >= expected_output_len
+	}
+
+	/// Get the duration of the sample, including the tail effects add after its end.
 	pub fn duration(&self) -> Duration {
-		Duration::from_secs_f32(self.sample_size() as f32 / self.sample_rate as f32 * self.effects_duration_multiplier())
+		Duration::from_secs_f32((self.sample_size() as f32 * self.effects_duration_multiplier() + self.effects_tail() as f32) / self.sample_rate as f32)
 	}
 
 	/// Get the unprocessed data.
@@ -223,6 +416,14 @@ impl AudioBuffer {
 		self.raw_data()
 	}
 
+	/// Measure the loudness of the processed data.
+	pub fn loudness(&mut self) -> LoudnessMeter {
+		self.apply_effects();
+		let mut meter:LoudnessMeter = LoudnessMeter::new(self.sample_rate, self.channel_count);
+		meter.add_samples(&self.data);
+		meter
+	}
+
 	/// Get the amount of modifications scheduled.
 	#[cfg(test)]
 	pub(super) fn mod_count(&self) -> usize {
@@ -231,7 +432,7 @@ impl AudioBuffer {
 
 	/// Create a PNG image displaying the data in this buffer.
 	#[cfg(test)]
-	pub fn write_png(&self, path:&str) -> Result<(), Box<dyn Error>> {
+	pub fn write_png(&self, path:&str) -> Result<(), Box<dyn std::error::Error>> {
 		use image::{ ImageBuffer, Rgba };
 
 		const MAX_IMAGE_WIDTH:u32 = 8000;
@@ -274,54 +475,81 @@ impl AudioGenerator for AudioBuffer {
 	fn take<T>(&mut self, duration:T) -> Vec<Vec<f32>> where T:AudioBufferDataLength {
 
 		// Calculate sub-sample size.
-		let sample_size:usize = self.sample_size();
-		let target_sample_len:usize = duration.as_buffer_length(self.sample_rate).min(sample_size);
-		let target_sample_len_before_effects:usize = (target_sample_len as f32 / self.effects_duration_multiplier()) as usize;
+		let mut output_len:usize = self.effect_output.data.first().map(|channel| channel.len()).unwrap_or(0);
+		let effects_duration_multiplier:f32 = self.effects_duration_multiplier().abs();
+		let effects_tail:usize = self.effects_tail();
+		let remaining_output_len:usize = (AudioBuffer::processed_len(self.effect_output.input_len + self.unprocessed_len(), effects_duration_multiplier) + effects_tail).saturating_sub(self.effect_output.output_len);
+		let target_sample_len:usize = duration.as_buffer_length(self.sample_rate).min(output_len + remaining_output_len);
+
+		// Process data until the effects produced enough output. Effects keep their state between calls, so the output has no seams.
+		while output_len < target_sample_len && !(self.input_depleted() && self.effects_flushed()) {
+			let target_sample_len_before_effects:usize = (((target_sample_len - output_len) as f32 / effects_duration_multiplier).ceil() as usize).max(1);
+
+			// Grab sub-sample.
+			let sample_size:usize = self.sample_size();
+			let mut sub_data:Vec<Vec<f32>> = match &mut self.progression_tracker {
+				ProgressionTracker::Cursor(cursor) => {
+					let start:usize = *cursor;
+					*cursor = (*cursor + target_sample_len_before_effects).min(sample_size);
+					self.data.iter().map(|channel| channel[start..*cursor].to_vec()).collect::<Vec<Vec<f32>>>()
+				},
+				ProgressionTracker::Drain(drained) => {
+					let drain_len:usize = target_sample_len_before_effects.min(sample_size);
+					*drained += drain_len;
+					self.data.iter_mut().map(|channel| channel.drain(..drain_len).collect()).collect::<Vec<Vec<f32>>>()
+				}
+			};
+			if sub_data.is_empty() {
+				break;
+			}
 
-		// Grab sub-sample.
-		let mut sub_data:Vec<Vec<f32>> = match &mut self.progression_tracker {
-			ProgressionTracker::Cursor(cursor) => {
-				let start:usize = *cursor;
-				*cursor = (*cursor + target_sample_len_before_effects).min(sample_size);
-				self.data.iter().map(|channel| channel[start..*cursor].to_vec()).collect::<Vec<Vec<f32>>>()
-			},
-			ProgressionTracker::Drain => {
-				self.data.iter_mut().map(|channel| channel.drain(..target_sample_len_before_effects).collect()).collect::<Vec<Vec<f32>>>()
+			// Pad with silence after the end of the data, which lets effects that lag behind their input finish.
+			let taken_len:usize = sub_data[0].len();
+			for channel in &mut sub_data {
+				channel.extend(vec![0.0; target_sample_len_before_effects - channel.len()]);
 			}
-		};
-		for channel in &mut sub_data {
-			channel.extend(vec![0.0; target_sample_len_before_effects - channel.len()]);
-		}
+			self.effect_output.input_len += taken_len;
+			self.effect_output.flushed_len += target_sample_len_before_effects - taken_len;
 
-		// Apply effects.
-		if !sub_data.is_empty() {
+			// Apply effects.
 			let mut sample_rate:u32 = self.sample_rate;
 			let mut channel_count:usize = self.channel_count;
 			for effect in &mut self.effects {
 				effect.apply_to(&mut sub_data, &mut sample_rate, &mut channel_count);
 			}
-		}
 
-		// Stretch to target length.
-		for channel in &mut sub_data {
-			if channel.len() < target_sample_len {
-				let shortage:usize = target_sample_len - channel.len();
-				for insertion_index in 0..shortage {
-					let insertion_position:usize = insertion_index * channel.len() / shortage;
-					channel.insert(insertion_position, channel[insertion_position]);
+			// Cut off output of the padding beyond the expected length.
+			if taken_len < target_sample_len_before_effects {
+				let expected_output_len:usize = AudioBuffer::processed_len(self.effect_output.input_len, effects_duration_multiplier) + effects_tail;
+				for channel in &mut sub_data {
+					channel.truncate(expected_output_len.saturating_sub(self.effect_output.output_len));
 				}
 			}
-			if channel.len() > target_sample_len {
-				let overflow:usize = target_sample_len - channel.len();
-				for removal_index in 0..overflow {
-					let removal_position:usize = removal_index * channel.len() / overflow;
-					channel.remove(removal_position);
-				}
+
+			// Store output.
+			let new_output_len:usize = sub_data.first().map(|channel| channel.len()).unwrap_or(0);
+			if self.effect_output.data.len() != sub_data.len() {
+				self.effect_output.data = vec![vec![0.0; output_len]; sub_data.len()];
+			}
+			for (channel, new_channel) in self.effect_output.data.iter_mut().zip(sub_data) {
+				channel.extend(new_channel);
 			}
+			self.effect_output.output_len += new_output_len;
+			output_len += new_output_len;
 		}
 
-		// Return data.
-		sub_data
+		// Take output, filling any shortage with silence.
+		self.effect_output.data.iter_mut().map(|channel| {
+			if channel.len() < target_sample_len {
+				channel.extend(vec![0.0; target_sample_len - channel.len()]);
+			}
+			channel.drain(..target_sample_len).collect()
+		}).collect()
+	}
+
+	/// Check if all data has been taken from the buffer.
+	fn is_depleted(&self) -> bool {
+		self.input_depleted() && self.effects_flushed() && self.effect_output.data.iter().all(|channel| channel.is_empty())
 	}
 }
 impl Add<AudioBuffer> for AudioBuffer {
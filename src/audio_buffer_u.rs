@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
-	use crate::{ AudioBuffer, AudioGenerator };
-	use std::time::Duration;
+	use crate::{ AudioBuffer, AudioGenerator, AudioWelderError, Resampler, Reverb, WavFormat };
+	use std::{ fs::{ create_dir_all, read, write, File }, io::{ BufWriter, Cursor }, time::Duration };
 
 
 
@@ -62,4 +62,201 @@ mod tests {
 		assert_eq!(&buffer.raw_data()[0], &RAW_SAMPLES[5..]);
 		assert_eq!(&buffer.take(5)[0], &RAW_SAMPLES[5..10]);
 	}
+
+
+
+	#[test]
+	fn test_seek_and_position() {
+		const RAW_SAMPLES:&[f32] = &[1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, -1.0, -1.0, -1.0, -1.0, 0.0, 0.0, 0.0];
+
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![RAW_SAMPLES.to_vec()], 10);
+		assert_eq!(buffer.position(), Duration::ZERO);
+		buffer.seek(Duration::from_millis(700));
+		assert_eq!(buffer.position(), Duration::from_millis(700));
+		assert_eq!(&buffer.take(3)[0], &RAW_SAMPLES[7..10]);
+		assert_eq!(buffer.position(), Duration::from_millis(1000));
+		buffer.seek(Duration::from_millis(200));
+		assert_eq!(&buffer.take(3)[0], &RAW_SAMPLES[2..5]);
+		assert!(!buffer.is_depleted());
+		buffer.seek(Duration::from_secs(10));
+		assert_eq!(buffer.position(), Duration::from_millis(1500));
+		assert!(buffer.is_depleted());
+	}
+
+	#[test]
+	fn test_seek_drain() {
+		const RAW_SAMPLES:&[f32] = &[1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, -1.0, -1.0, -1.0, -1.0, 0.0, 0.0, 0.0];
+
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![RAW_SAMPLES.to_vec()], 10);
+		buffer.take(2);
+		let mut buffer:AudioBuffer = buffer.drain_progression();
+		assert_eq!(&buffer.raw_data()[0], &RAW_SAMPLES[2..]);
+		assert_eq!(buffer.position(), Duration::from_millis(200));
+		buffer.seek(Duration::from_millis(600));
+		assert_eq!(buffer.position(), Duration::from_millis(600));
+		assert_eq!(&buffer.raw_data()[0], &RAW_SAMPLES[6..]);
+		buffer.seek(Duration::from_millis(100));
+		assert_eq!(buffer.position(), Duration::from_millis(600));
+		assert_eq!(&buffer.take(9)[0], &RAW_SAMPLES[6..]);
+		assert!(buffer.is_depleted());
+	}
+
+	#[test]
+	fn test_seek_resets_effects() {
+		let samples:Vec<f32> = (0..4000).map(|index| (index as f32 * 0.05).sin() * 0.5).collect();
+		let create_buffer = |samples:&[f32]| {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.to_vec(); 2], 8000);
+			buffer.add_effect(Resampler::new(16000, 2));
+			buffer.add_effect(Reverb::new(0.5, 0.5));
+			buffer
+		};
+
+		// After seeking, the output is the same as that of a buffer starting at the new position.
+		let mut buffer:AudioBuffer = create_buffer(&samples);
+		buffer.take(1000);
+		buffer.seek(Duration::from_millis(100));
+		let output:Vec<Vec<f32>> = buffer.take(2000);
+		let expected_output:Vec<Vec<f32>> = create_buffer(&samples[800..]).take(2000);
+		for (channel, expected_channel) in output.iter().zip(&expected_output) {
+			assert!(channel.iter().zip(expected_channel).all(|(sample, expected_sample)| (sample - expected_sample).abs() < 1e-6));
+		}
+	}
+
+	/* WAV EXPORT TESTS */
+
+	#[test]
+	fn test_write_wav_formats() {
+		const RAW_SAMPLES:&[f32] = &[0.0, 0.25, 0.5, 1.0, -0.25, -0.5, -1.0, 0.0];
+
+		for format in [WavFormat::Int8, WavFormat::Int16, WavFormat::Int24, WavFormat::Int32, WavFormat::Float32] {
+			println!("Testing format {format:?}");
+
+			// Write buffer to memory.
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![RAW_SAMPLES.to_vec(), RAW_SAMPLES.iter().map(|sample| -sample).collect()], 100);
+			let mut wav_data:Cursor<Vec<u8>> = Cursor::new(Vec::new());
+			buffer.write_wav_to(&mut wav_data, format).unwrap();
+
+			// Validate written data.
+			wav_data.set_position(0);
+			let reader:hound::WavReader<Cursor<Vec<u8>>> = hound::WavReader::new(wav_data).unwrap();
+			assert_eq!(reader.spec(), format.spec(2, 100));
+			let written:Vec<f32> = match format {
+				WavFormat::Float32 => reader.into_samples::<f32>().map(|sample| sample.unwrap()).collect(),
+				_ => reader.into_samples::<i32>().map(|sample| sample.unwrap() as f32 / format.int_scale().unwrap() as f32).collect()
+			};
+			assert_eq!(written.len(), RAW_SAMPLES.len() * 2);
+			for (sample_index, sample) in RAW_SAMPLES.iter().enumerate() {
+				assert!((written[sample_index * 2] - sample).abs() < 0.01);
+				assert!((written[sample_index * 2 + 1] + sample).abs() < 0.01);
+			}
+		}
+	}
+
+	#[test]
+	fn test_write_wav_applies_effects() {
+		const RAW_SAMPLES:&[f32] = &[0.0, 0.25, 0.5, 1.0, -0.25, -0.5, -1.0, 0.0];
+		const FILE_PATH:&str = "target/debug_wavs/effects.wav";
+
+		create_dir_all("target/debug_wavs").expect("Could not create debug wavs dir.");
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![RAW_SAMPLES.to_vec()], 100);
+		buffer.multiply_volume(0.5);
+		buffer.write_wav(FILE_PATH, WavFormat::Int16).unwrap();
+		
+		let loaded:AudioBuffer = AudioBuffer::wav(FILE_PATH).unwrap();
+		assert_eq!(loaded.channel_count(), 1);
+		assert_eq!(loaded.sample_rate(), 100);
+		for (loaded_sample, sample) in loaded.raw_data()[0].iter().zip(RAW_SAMPLES) {
+			assert!((loaded_sample - sample * 0.5).abs() < 0.001);
+		}
+	}
+
+
+
+	/* WAV IMPORT TESTS */
+
+	#[test]
+	fn test_read_wav_integer_bit_depths() {
+		const RAW_SAMPLES:&[f32] = &[0.0, 0.25, 0.5, 1.0, -0.25, -0.5, -1.0, 0.0];
+
+		create_dir_all("target/debug_wavs").expect("Could not create debug wavs dir.");
+		for bits_per_sample in [8, 16, 24, 32] {
+			let file_path:String = format!("target/debug_wavs/int_{bits_per_sample}.wav");
+			println!("Testing {file_path}");
+
+			// Write file with full-scale integer values.
+			let full_scale:f64 = ((1u64 << (bits_per_sample - 1)) - 1) as f64;
+			let spec:hound::WavSpec = hound::WavSpec { channels: 2, sample_rate: 100, bits_per_sample, sample_format: hound::SampleFormat::Int };
+			let mut writer:hound::WavWriter<BufWriter<File>> = hound::WavWriter::create(&file_path, spec).unwrap();
+			for sample in RAW_SAMPLES {
+				writer.write_sample((*sample as f64 * full_scale).round() as i32).unwrap();
+				writer.write_sample((-*sample as f64 * full_scale).round() as i32).unwrap();
+			}
+			writer.finalize().unwrap();
+
+			// Validate loaded levels.
+			let buffer:AudioBuffer = AudioBuffer::wav(&file_path).unwrap();
+			assert_eq!(buffer.channel_count(), 2);
+			assert_eq!(buffer.sample_rate(), 100);
+			for (sample_index, sample) in RAW_SAMPLES.iter().enumerate() {
+				assert!((buffer.raw_data()[0][sample_index] - sample).abs() < 0.01);
+				assert!((buffer.raw_data()[1][sample_index] + sample).abs() < 0.01);
+			}
+		}
+	}
+
+	#[test]
+	fn test_read_wav_round_trip() {
+		const RAW_SAMPLES:&[f32] = &[0.0, 0.1, 0.2, 0.3, 0.4, -0.4, -0.3, -0.2, -0.1, 0.0];
+
+		create_dir_all("target/debug_wavs").expect("Could not create debug wavs dir.");
+		for (format, tolerance) in [(WavFormat::Int8, 0.01), (WavFormat::Int16, 0.0001), (WavFormat::Int24, 0.000001), (WavFormat::Int32, 0.000001), (WavFormat::Float32, 0.0)] {
+			let file_path:String = format!("target/debug_wavs/round_trip_{format:?}.wav");
+			AudioBuffer::new(vec![RAW_SAMPLES.to_vec()], 100).write_wav(&file_path, format).unwrap();
+			let buffer:AudioBuffer = AudioBuffer::wav(&file_path).unwrap();
+			for (loaded, sample) in buffer.raw_data()[0].iter().zip(RAW_SAMPLES) {
+				assert!((loaded - sample).abs() <= tolerance);
+			}
+		}
+	}
+
+	#[test]
+	fn test_read_wav_truncated() {
+		const FILE_PATH:&str = "target/debug_wavs/truncated.wav";
+		let raw_samples:Vec<f32> = (0..100).map(|index| index as f32 / 100.0).collect();
+
+		// Write a file and cut off the end of its data chunk.
+		create_dir_all("target/debug_wavs").expect("Could not create debug wavs dir.");
+		AudioBuffer::new(vec![raw_samples.clone()], 100).write_wav(FILE_PATH, WavFormat::Int16).unwrap();
+		let file_data:Vec<u8> = read(FILE_PATH).unwrap();
+		write(FILE_PATH, &file_data[..file_data.len() - 41]).unwrap();
+
+		// Strict mode returns an error.
+		let strict_error:AudioWelderError = AudioBuffer::wav(FILE_PATH).err().expect("Truncated file should not load in strict mode.");
+		assert!(matches!(strict_error, AudioWelderError::Decode(_)));
+		let strict_error:String = strict_error.to_string();
+		assert!(strict_error.contains("sample 79"));
+
+		// Lenient mode keeps the samples before the bad spot.
+		let (buffer, decode_error) = AudioBuffer::wav_lenient(FILE_PATH).unwrap();
+		assert_eq!(decode_error.expect("Truncated file should report an error in lenient mode.").to_string(), strict_error);
+		assert_eq!(buffer.raw_data()[0].len(), 79);
+		for (loaded, sample) in buffer.raw_data()[0].iter().zip(&raw_samples) {
+			assert!((loaded - sample).abs() < 0.0001);
+		}
+	}
+
+	#[test]
+	fn test_read_wav_corrupt_header() {
+		const FILE_PATH:&str = "target/debug_wavs/corrupt_header.wav";
+
+		create_dir_all("target/debug_wavs").expect("Could not create debug wavs dir.");
+		write(FILE_PATH, b"RIFF____WAVEnot a wav file").unwrap();
+		assert!(AudioBuffer::wav(FILE_PATH).is_err());
+		assert!(AudioBuffer::wav_lenient(FILE_PATH).is_err());
+	}
+
+	#[test]
+	fn test_read_wav_missing_file() {
+		assert!(matches!(AudioBuffer::wav("target/debug_wavs/does_not_exist.wav"), Err(AudioWelderError::Io(_))));
+	}
 }
\ No newline at end of file
@@ -34,12 +34,33 @@ pub trait AudioEffect:Send + Sync {
 		1.0
 	}
 
+	/// Get the amount of samples the output lags behind the input. After the input ends, this many samples of silence are processed so the remaining output comes out.
+	fn latency(&self, _sample_rate:u32, _channel_count:usize) -> usize {
+		0
+	}
+
+	/// Get the amount of samples the output continues after the input ends, like the decay of a reverb. After the input ends, this many samples of silence are processed on top of the latency and the output grows by this many samples.
+	fn tail(&self, _sample_rate:u32, _channel_count:usize) -> usize {
+		0
+	}
+
 
 
 	/* USAGE METHODS */
 
+	/// Check if the effect needs to measure all data it will be applied to before it is applied to parts of it, like a normalizer does.
+	fn needs_analysis(&self) -> bool {
+		false
+	}
+
+	/// Measure all data the effect will be applied to, so applying the effect to parts of the data gives the same result as applying it to all data at once.
+	fn analyze(&mut self, _data:&[Vec<f32>], _sample_rate:u32, _channel_count:usize) {}
+
 	/// Apply the effect to the given buffer.
 	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, channel_count:&mut usize);
+
+	/// Forget the audio processed so far, like after seeking, so the next output does not depend on data from before it. Settings and measurements of the whole source are kept.
+	fn reset(&mut self) {}
     
 	/// Try to combine two instances of the audio effect into one.
 	fn combine(&self, _other:&dyn AudioEffect) -> Option<Box<dyn AudioEffect>> {
@@ -76,10 +97,10 @@ pub trait AudioEffect:Send + Sync {
 		}
 	}
 
-	/// Test all settings being able to be modified.
+	/// Test all settings being able to be modified. Settings that are only readings, like a meter, are not in the mutable list and are skipped.
 	#[cfg(test)]
 	fn settings_test(&mut self) {
-		let setting_names:Vec<String> = self.settings().iter().map(|(name, _)| name.to_string()).collect();
+		let setting_names:Vec<String> = self.settings_mut().iter().map(|(name, _)| name.to_string()).collect();
 		for (setting_index, setting_name) in setting_names.iter().enumerate() {
 			let new_value:f32 = 867.3 + (setting_index + 8) as f32 * 4.5;
 			self.set_setting(setting_name, new_value);
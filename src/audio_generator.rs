@@ -7,6 +7,11 @@ pub trait AudioGenerator {
 	/// Take a specific amount of data.
 	fn take<T>(&mut self, duration:T) -> Vec<Vec<f32>> where T:AudioBufferDataLength;
 
+	/// Check if the generator has no more data to give.
+	fn is_depleted(&self) -> bool {
+		false
+	}
+
 	/// Take a specific amount of flattened data.
 	fn take_flat<T>(&mut self, duration:T) -> Vec<f32> where T:AudioBufferDataLength {
 
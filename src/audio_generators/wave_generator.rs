@@ -5,14 +5,14 @@ use crate::{ AudioBufferDataLength, AudioFrequency, AudioGenerator };
 pub struct WaveGenerator {
 	frequency:f32,
 	sample_rate:u32,
-	shape_function:Box<dyn Fn(&mut f32, f32, u32, usize) -> Vec<f32>>,
+	shape_function:Box<dyn Fn(&mut f32, f32, u32, usize) -> Vec<f32> + Send + Sync>,
 
 	progress:f32
 }
 impl WaveGenerator {
 
 	/// Create a new wave generator with custom function.
-	pub fn new<T, R>(frequency:R, sample_rate:u32, shape_function:T) -> WaveGenerator where T:Fn(&mut f32, f32, u32, usize) -> Vec<f32> + 'static, R:AudioFrequency {
+	pub fn new<T, R>(frequency:R, sample_rate:u32, shape_function:T) -> WaveGenerator where T:Fn(&mut f32, f32, u32, usize) -> Vec<f32> + Send + Sync + 'static, R:AudioFrequency {
 		WaveGenerator {
 			frequency: frequency.to_hz(),
 			sample_rate,
@@ -0,0 +1,111 @@
+use cpal::{ BuildStreamError, DefaultStreamConfigError, DeviceNameError, DevicesError, PauseStreamError, PlayStreamError, StreamError, SupportedStreamConfigsError };
+use std::{ error::Error, fmt::{ Display, Formatter, Result as FmtResult }, io::Error as IoError };
+
+
+
+#[derive(Debug)]
+pub enum AudioWelderError {
+	DeviceNotFound(String),
+	UnsupportedFormat(String),
+	Decode(String),
+	Stream(String),
+	Io(IoError)
+}
+impl Display for AudioWelderError {
+	fn fmt(&self, f:&mut Formatter<'_>) -> FmtResult {
+		match self {
+			AudioWelderError::DeviceNotFound(message) => write!(f, "Device not found: {message}"),
+			AudioWelderError::UnsupportedFormat(message) => write!(f, "Unsupported format: {message}"),
+			AudioWelderError::Decode(message) => write!(f, "Decode error: {message}"),
+			AudioWelderError::Stream(message) => write!(f, "Stream error: {message}"),
+			AudioWelderError::Io(error) => write!(f, "IO error: {error}")
+		}
+	}
+}
+impl Error for AudioWelderError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			AudioWelderError::Io(error) => Some(error),
+			_ => None
+		}
+	}
+}
+
+
+
+/* CONVERSIONS */
+
+impl From<IoError> for AudioWelderError {
+	fn from(error:IoError) -> Self {
+		AudioWelderError::Io(error)
+	}
+}
+impl From<hound::Error> for AudioWelderError {
+	fn from(error:hound::Error) -> Self {
+		match error {
+			hound::Error::IoError(error) => AudioWelderError::Io(error),
+			hound::Error::Unsupported | hound::Error::InvalidSampleFormat | hound::Error::TooWide => AudioWelderError::UnsupportedFormat(error.to_string()),
+			_ => AudioWelderError::Decode(error.to_string())
+		}
+	}
+}
+impl From<DevicesError> for AudioWelderError {
+	fn from(error:DevicesError) -> Self {
+		AudioWelderError::Stream(error.to_string())
+	}
+}
+impl From<DeviceNameError> for AudioWelderError {
+	fn from(error:DeviceNameError) -> Self {
+		AudioWelderError::Stream(error.to_string())
+	}
+}
+impl From<SupportedStreamConfigsError> for AudioWelderError {
+	fn from(error:SupportedStreamConfigsError) -> Self {
+		match error {
+			SupportedStreamConfigsError::DeviceNotAvailable => AudioWelderError::DeviceNotFound(error.to_string()),
+			_ => AudioWelderError::Stream(error.to_string())
+		}
+	}
+}
+impl From<DefaultStreamConfigError> for AudioWelderError {
+	fn from(error:DefaultStreamConfigError) -> Self {
+		match error {
+			DefaultStreamConfigError::DeviceNotAvailable => AudioWelderError::DeviceNotFound(error.to_string()),
+			DefaultStreamConfigError::StreamTypeNotSupported => AudioWelderError::UnsupportedFormat(error.to_string()),
+			_ => AudioWelderError::Stream(error.to_string())
+		}
+	}
+}
+impl From<BuildStreamError> for AudioWelderError {
+	fn from(error:BuildStreamError) -> Self {
+		match error {
+			BuildStreamError::DeviceNotAvailable => AudioWelderError::DeviceNotFound(error.to_string()),
+			BuildStreamError::StreamConfigNotSupported => AudioWelderError::UnsupportedFormat(error.to_string()),
+			_ => AudioWelderError::Stream(error.to_string())
+		}
+	}
+}
+impl From<PlayStreamError> for AudioWelderError {
+	fn from(error:PlayStreamError) -> Self {
+		match error {
+			PlayStreamError::DeviceNotAvailable => AudioWelderError::DeviceNotFound(error.to_string()),
+			_ => AudioWelderError::Stream(error.to_string())
+		}
+	}
+}
+impl From<PauseStreamError> for AudioWelderError {
+	fn from(error:PauseStreamError) -> Self {
+		match error {
+			PauseStreamError::DeviceNotAvailable => AudioWelderError::DeviceNotFound(error.to_string()),
+			_ => AudioWelderError::Stream(error.to_string())
+		}
+	}
+}
+impl From<StreamError> for AudioWelderError {
+	fn from(error:StreamError) -> Self {
+		match error {
+			StreamError::DeviceNotAvailable => AudioWelderError::DeviceNotFound(error.to_string()),
+			_ => AudioWelderError::Stream(error.to_string())
+		}
+	}
+}
\ No newline at end of file
@@ -1,13 +1,29 @@
-use cpal::{ traits::{ DeviceTrait, HostTrait }, Device as CpalDevice, Host, SupportedStreamConfig };
-use std::error::Error;
+use cpal::{ traits::{ DeviceTrait, HostTrait, StreamTrait }, BufferSize, BuildStreamError, Device as CpalDevice, Host, OutputStreamTimestamp, SampleFormat, SampleRate, Stream, StreamConfig, StreamError, SupportedStreamConfig };
+use std::{ sync::{ atomic::{ AtomicU64, Ordering }, mpsc::{ channel, Receiver, RecvTimeoutError, Sender }, Arc }, thread::{ self, sleep }, time::Duration };
+use crate::{ AudioWelderError, DeviceInfo };
+use super::{ output_backend::{ OutputBackend, OutputStream }, sample_converter::SampleConverter, stream_config_request::{ ResolvedStreamConfig, StreamConfigRequest } };
 
-use crate::AudioBuffer;
+
+
+const RECONNECT_INTERVAL:Duration = Duration::from_millis(500);
+
+
+
+type StreamStartResult = Result<(), AudioWelderError>;
+pub(super) type StreamErrorCallback = Box<dyn FnMut(StreamError) + Send>;
+
+
+
+enum StreamEvent { Error(StreamError), Ended }
 
 
 
 pub(super) struct AudioDevice {
 	pub channel_count:usize,
 	pub sample_rate:u32,
+	pub sample_format:SampleFormat,
+	pub buffer_size:Option<u32>,
+	pub is_output_device:bool,
 	pub cpal_device:CpalDevice
 }
 impl AudioDevice {
@@ -15,50 +31,220 @@ impl AudioDevice {
 	/* CONSTRUCTOR METHODS */
 
 	/// Create a new audio device.
-	pub fn new(device_name:&str, is_output_device:bool) -> Result<AudioDevice, Box<dyn Error>> {
+	pub fn new(device_name:&str, is_output_device:bool) -> Result<AudioDevice, AudioWelderError> {
 		let device:CpalDevice = AudioDevice::find_device(device_name, is_output_device)?;
 		let config:SupportedStreamConfig = if is_output_device { device.default_output_config() } else { device.default_input_config() }?;
 		Ok(AudioDevice {
 			channel_count: config.channels() as usize,
 			sample_rate: config.sample_rate().0,
+			sample_format: config.sample_format(),
+			buffer_size: None,
+			is_output_device,
+			cpal_device: device
+		})
+	}
+
+	/// Create a new audio device with a specific stream config.
+	pub fn with_config(device_name:&str, is_output_device:bool, request:&StreamConfigRequest) -> Result<AudioDevice, AudioWelderError> {
+		let device:CpalDevice = AudioDevice::find_device(device_name, is_output_device)?;
+		let info:DeviceInfo = DeviceInfo::from_cpal(&device, &device.name().unwrap_or(device_name.to_string()), is_output_device, false);
+		let config:ResolvedStreamConfig = request.resolve(info.name(), info.default_config(), info.config_ranges())?;
+		Ok(AudioDevice {
+			channel_count: config.channel_count,
+			sample_rate: config.sample_rate,
+			sample_format: config.sample_format,
+			buffer_size: config.buffer_size,
+			is_output_device,
 			cpal_device: device
 		})
 	}
 
 	/// Find a specific device.
-	fn find_device(name:&str, is_output_device:bool) -> Result<CpalDevice, Box<dyn Error>> {
+	fn find_device(name:&str, is_output_device:bool) -> Result<CpalDevice, AudioWelderError> {
 		let host:Host = cpal::default_host();
 
 		// Try default.
 		if name.to_lowercase() == "default" {
 			return match if is_output_device { host.default_output_device() } else { host.default_input_device() } {
 				Some(device) => Ok(device),
-				None => Err("Unable to find default device".into())
+				None => Err(AudioWelderError::DeviceNotFound(format!("No default {} device available.", if is_output_device { "output" } else { "input" })))
 			}
 		}
 
 		// Create list of devices.
 		let devices = if is_output_device { host.output_devices()? } else { host.input_devices()? };
 
-		// Filter for name.
+		// Filter for name, preferring exact matches over partial ones.
+		let mut partial_match:Option<CpalDevice> = None;
 		for device in devices.into_iter() {
 			if let Ok(device_name) = &device.name() {
-				if device_name.contains(name){
+				if device_name == name {
 					return Ok(device);
 				}
+				if partial_match.is_none() && device_name.contains(name) {
+					partial_match = Some(device);
+				}
 			}
 		}
+		if let Some(device) = partial_match {
+			return Ok(device);
+		}
 
-		Err(format!("Could not find {} device '{}'.", if is_output_device { "output" } else { "input" }, name).into())
+		Err(AudioWelderError::DeviceNotFound(format!("Could not find {} device '{}'.", if is_output_device { "output" } else { "input" }, name)))
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the config to open streams on this device with.
+	pub fn stream_config(&self) -> StreamConfig {
+		StreamConfig {
+			channels: self.channel_count as u16,
+			sample_rate: SampleRate(self.sample_rate),
+			buffer_size: match self.buffer_size {
+				Some(buffer_size) => BufferSize::Fixed(buffer_size),
+				None => BufferSize::Default
+			}
+		}
 	}
 
 
 
 	/* USAGE METHODS */
 
-	/// Resample buffer to match audio device sample rate and channel count.
-	pub fn prepare_buffer(&self, buffer:&mut AudioBuffer) {
-		buffer.resample_sample_rate(self.sample_rate);
-		buffer.resample_channel_count(self.channel_count);
+	/// Build and play a stream on a separate thread, as not all platforms allow moving streams between threads. Blocks until the stream is playing. The stream is kept open until the `await_end` function returns.
+	/// Errors of the stream are passed to the error handler, along with a flag indicating if the stream was lost. When the device becomes unavailable and auto reconnect is enabled, the stream is rebuilt on the default device with the same config, retrying until it succeeds or the stream ends. If the default device does not support the config, the mismatch is passed to the error handler as a lost stream. Otherwise, the error handler should make the `await_end` function return.
+	pub fn spawn_stream<T, U, V>(&self, stream_builder:T, await_end:U, error_handler:V, auto_reconnect:bool) -> Result<(), AudioWelderError> where T:Fn(&CpalDevice, &StreamConfig, StreamErrorCallback) -> Result<Stream, BuildStreamError> + Send + 'static, U:FnOnce() + Send + 'static, V:Fn(AudioWelderError, bool) + Send + 'static {
+		let cpal_device:CpalDevice = self.cpal_device.clone();
+		let stream_config:StreamConfig = self.stream_config();
+		let sample_format:SampleFormat = self.sample_format;
+		let is_output_device:bool = self.is_output_device;
+		let (result_sender, result_receiver):(Sender<StreamStartResult>, Receiver<StreamStartResult>) = channel();
+		let (event_sender, event_receiver):(Sender<StreamEvent>, Receiver<StreamEvent>) = channel();
+		thread::spawn(move || {
+			let mut stream:Option<Stream> = match AudioDevice::start_stream(&stream_builder, &cpal_device, &stream_config, &event_sender) {
+				Ok(stream) => {
+					result_sender.send(Ok(())).unwrap_or_default();
+					Some(stream)
+				},
+				Err(error) => {
+					result_sender.send(Err(error)).unwrap_or_default();
+					return;
+				}
+			};
+
+			// Notify the stream thread once the stream should end.
+			let end_sender:Sender<StreamEvent> = event_sender.clone();
+			thread::spawn(move || {
+				await_end();
+				end_sender.send(StreamEvent::Ended).unwrap_or_default();
+			});
+
+			// Handle stream events until the end.
+			loop {
+				let event:Result<StreamEvent, RecvTimeoutError> = if stream.is_some() { event_receiver.recv().map_err(|_| RecvTimeoutError::Disconnected) } else { event_receiver.recv_timeout(RECONNECT_INTERVAL) };
+				match event {
+					Ok(StreamEvent::Ended) | Err(RecvTimeoutError::Disconnected) => break,
+					Ok(StreamEvent::Error(error)) => {
+						let device_lost:bool = matches!(error, StreamError::DeviceNotAvailable);
+						if device_lost {
+							stream = None;
+						}
+						error_handler(AudioWelderError::from(error), device_lost && !auto_reconnect);
+					},
+					Err(RecvTimeoutError::Timeout) => {}
+				}
+
+				// Reconnect to the default device. A default device that does not support the config of the stream cannot take it over, so the stream is lost.
+				if stream.is_none() && auto_reconnect {
+					match AudioDevice::find_reconnect_device(is_output_device, &stream_config, sample_format) {
+						Ok(device) => stream = device.and_then(|device| AudioDevice::start_stream(&stream_builder, &device, &stream_config, &event_sender).ok()),
+						Err(error) => {
+							error_handler(error, true);
+							break;
+						}
+					}
+				}
+			}
+		});
+		result_receiver.recv().unwrap_or_else(|_| Err(AudioWelderError::Stream("Stream thread stopped before starting the stream.".to_string())))
+	}
+
+	/// Find the default device to reconnect a stream with the given config to. Returns None while there is no default device that reports its configs, and an error if the default device does not support the config.
+	fn find_reconnect_device(is_output_device:bool, stream_config:&StreamConfig, sample_format:SampleFormat) -> Result<Option<CpalDevice>, AudioWelderError> {
+		let device:CpalDevice = match AudioDevice::find_device("default", is_output_device) {
+			Ok(device) => device,
+			Err(_) => return Ok(None)
+		};
+		let info:DeviceInfo = DeviceInfo::from_cpal(&device, &device.name().unwrap_or("default".to_string()), is_output_device, true);
+		let (sample_rate, channel_count):(u32, usize) = (stream_config.sample_rate.0, stream_config.channels as usize);
+		if info.config_ranges().is_empty() {
+			Ok(None)
+		} else if info.supports(sample_rate, channel_count, sample_format) {
+			Ok(Some(device))
+		} else {
+			Err(AudioWelderError::UnsupportedFormat(format!("Device '{}' does not support {sample_rate}Hz with {channel_count} channels in {sample_format} format, the stream cannot reconnect to it.", info.name())))
+		}
+	}
+
+	/// Build and play a stream on the given device. Errors of the stream are sent as events to the given sender.
+	fn start_stream<T>(stream_builder:&T, cpal_device:&CpalDevice, stream_config:&StreamConfig, event_sender:&Sender<StreamEvent>) -> Result<Stream, AudioWelderError> where T:Fn(&CpalDevice, &StreamConfig, StreamErrorCallback) -> Result<Stream, BuildStreamError> {
+		let error_sender:Sender<StreamEvent> = event_sender.clone();
+		let stream:Stream = stream_builder(cpal_device, stream_config, Box::new(move |error| error_sender.send(StreamEvent::Error(error)).unwrap_or_default()))?;
+		stream.play()?;
+		Ok(stream)
+	}
+}
+impl OutputBackend for AudioDevice {
+	fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+	fn channel_count(&self) -> usize {
+		self.channel_count
+	}
+	fn sample_format(&self) -> SampleFormat {
+		self.sample_format
+	}
+	fn buffer_size(&self) -> Option<u32> {
+		self.buffer_size
+	}
+	fn start(&self, stream:OutputStream) -> Result<(), AudioWelderError> {
+		let sample_format:SampleFormat = self.sample_format;
+		let samples_per_second:u64 = self.sample_rate as u64 * self.channel_count.max(1) as u64;
+		let auto_reconnect:bool = stream.auto_reconnect();
+		let callback_stream:OutputStream = stream.clone();
+		let end_stream:OutputStream = stream.clone();
+
+		// Keep track of how long it takes the device to play the last data it received.
+		let output_latency_nanos:Arc<AtomicU64> = Arc::new(AtomicU64::new(0));
+		let end_latency_nanos:Arc<AtomicU64> = output_latency_nanos.clone();
+
+		self.spawn_stream(
+			move |cpal_device, stream_config, stream_error_callback| {
+				let callback_stream:OutputStream = callback_stream.clone();
+				let output_latency_nanos:Arc<AtomicU64> = output_latency_nanos.clone();
+				SampleConverter::build_output_stream(
+					cpal_device,
+					stream_config,
+					sample_format,
+					callback_stream.dither(),
+					move |data, info| {
+						callback_stream.fill(data);
+						let timestamp:OutputStreamTimestamp = info.timestamp();
+						let device_latency:Duration = timestamp.playback.duration_since(&timestamp.callback).unwrap_or_default();
+						let data_duration:Duration = Duration::from_nanos(data.len() as u64 * 1_000_000_000 / samples_per_second);
+						output_latency_nanos.store((device_latency + data_duration).as_nanos() as u64, Ordering::Relaxed);
+					},
+					stream_error_callback
+				)
+			},
+			move || {
+				end_stream.await_end();
+				sleep(Duration::from_nanos(end_latency_nanos.load(Ordering::Relaxed)));
+			},
+			move |error, stream_lost| stream.report_error(error, stream_lost),
+			auto_reconnect
+		)
 	}
 }
\ No newline at end of file
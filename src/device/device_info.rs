@@ -0,0 +1,211 @@
+use cpal::{ traits::{ DeviceTrait, HostTrait }, Device as CpalDevice, Host, SampleFormat, SupportedBufferSize, SupportedStreamConfig, SupportedStreamConfigRange };
+use crate::AudioWelderError;
+
+
+
+const COMMON_SAMPLE_RATES:&[u32] = &[8000, 11025, 16000, 22050, 32000, 44100, 48000, 88200, 96000, 176400, 192000, 352800, 384000];
+
+
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DeviceConfig {
+	pub sample_rate:u32,
+	pub channel_count:usize,
+	pub sample_format:SampleFormat,
+	pub buffer_size_range:Option<(u32, u32)>
+}
+impl DeviceConfig {
+
+	/// Create a device config from a cpal config.
+	pub(crate) fn from_cpal(config:&SupportedStreamConfig) -> DeviceConfig {
+		DeviceConfig {
+			sample_rate: config.sample_rate().0,
+			channel_count: config.channels() as usize,
+			sample_format: config.sample_format(),
+			buffer_size_range: DeviceConfigRange::buffer_size_range_from_cpal(config.buffer_size())
+		}
+	}
+}
+
+
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DeviceConfigRange {
+	pub channel_count:usize,
+	pub min_sample_rate:u32,
+	pub max_sample_rate:u32,
+	pub sample_format:SampleFormat,
+	pub buffer_size_range:Option<(u32, u32)>
+}
+impl DeviceConfigRange {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a device config range from a cpal config range.
+	pub(crate) fn from_cpal(config_range:&SupportedStreamConfigRange) -> DeviceConfigRange {
+		DeviceConfigRange {
+			channel_count: config_range.channels() as usize,
+			min_sample_rate: config_range.min_sample_rate().0,
+			max_sample_rate: config_range.max_sample_rate().0,
+			sample_format: config_range.sample_format(),
+			buffer_size_range: DeviceConfigRange::buffer_size_range_from_cpal(config_range.buffer_size())
+		}
+	}
+
+	/// Convert a cpal buffer size to a min and max buffer size. Returns None if the buffer size range is unknown.
+	fn buffer_size_range_from_cpal(buffer_size:&SupportedBufferSize) -> Option<(u32, u32)> {
+		match buffer_size {
+			SupportedBufferSize::Range { min, max } => Some((*min, *max)),
+			SupportedBufferSize::Unknown => None
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Check if this range supports the given sample rate.
+	pub fn supports_sample_rate(&self, sample_rate:u32) -> bool {
+		sample_rate >= self.min_sample_rate && sample_rate <= self.max_sample_rate
+	}
+
+	/// Check if this range supports the given configuration.
+	pub fn supports(&self, sample_rate:u32, channel_count:usize, sample_format:SampleFormat) -> bool {
+		self.supports_sample_rate(sample_rate) && self.channel_count == channel_count && self.sample_format == sample_format
+	}
+}
+
+
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DeviceInfo {
+	name:String,
+	is_output_device:bool,
+	is_default:bool,
+	default_config:Option<DeviceConfig>,
+	config_ranges:Vec<DeviceConfigRange>
+}
+impl DeviceInfo {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new device info.
+	pub(crate) fn new(name:&str, is_output_device:bool, is_default:bool, default_config:Option<DeviceConfig>, config_ranges:Vec<DeviceConfigRange>) -> DeviceInfo {
+		DeviceInfo {
+			name: name.to_string(),
+			is_output_device,
+			is_default,
+			default_config,
+			config_ranges
+		}
+	}
+
+	/// List all available output devices.
+	pub fn output_devices() -> Result<Vec<DeviceInfo>, AudioWelderError> {
+		DeviceInfo::list_devices(true)
+	}
+
+	/// List all available input devices.
+	pub fn input_devices() -> Result<Vec<DeviceInfo>, AudioWelderError> {
+		DeviceInfo::list_devices(false)
+	}
+
+	/// List all available input or output devices. Devices that can not be queried are left out.
+	fn list_devices(is_output_device:bool) -> Result<Vec<DeviceInfo>, AudioWelderError> {
+		let host:Host = cpal::default_host();
+		let default_device:Option<CpalDevice> = if is_output_device { host.default_output_device() } else { host.default_input_device() };
+		let default_name:Option<String> = default_device.and_then(|device| device.name().ok());
+		let devices = if is_output_device { host.output_devices()? } else { host.input_devices()? };
+		Ok(devices.filter_map(|device| {
+			let name:String = device.name().ok()?;
+			let is_default:bool = default_name.as_ref() == Some(&name);
+			Some(DeviceInfo::from_cpal(&device, &name, is_output_device, is_default))
+		}).collect())
+	}
+
+	/// Create a device info by querying a cpal device.
+	pub(crate) fn from_cpal(device:&CpalDevice, name:&str, is_output_device:bool, is_default:bool) -> DeviceInfo {
+		let default_config:Option<DeviceConfig> = if is_output_device { device.default_output_config() } else { device.default_input_config() }.ok().map(|config| DeviceConfig::from_cpal(&config));
+		let config_ranges:Vec<DeviceConfigRange> = if is_output_device {
+			device.supported_output_configs().map(|configs| configs.map(|config| DeviceConfigRange::from_cpal(&config)).collect()).unwrap_or_default()
+		} else {
+			device.supported_input_configs().map(|configs| configs.map(|config| DeviceConfigRange::from_cpal(&config)).collect()).unwrap_or_default()
+		};
+		DeviceInfo::new(name, is_output_device, is_default, default_config, config_ranges)
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the name of the device.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Check if the device is an output device.
+	pub fn is_output_device(&self) -> bool {
+		self.is_output_device
+	}
+
+	/// Check if the device is the default device of the host.
+	pub fn is_default(&self) -> bool {
+		self.is_default
+	}
+
+	/// Get the config the device uses by default.
+	pub fn default_config(&self) -> Option<DeviceConfig> {
+		self.default_config
+	}
+
+	/// Get all supported config ranges.
+	pub fn config_ranges(&self) -> &[DeviceConfigRange] {
+		&self.config_ranges
+	}
+
+	/// Get a sorted list of common sample rates supported by the device. The bounds of each supported range are included as well.
+	pub fn sample_rates(&self) -> Vec<u32> {
+		let mut sample_rates:Vec<u32> = COMMON_SAMPLE_RATES.iter().filter(|sample_rate| self.config_ranges.iter().any(|range| range.supports_sample_rate(**sample_rate))).cloned().collect();
+		for range in &self.config_ranges {
+			sample_rates.push(range.min_sample_rate);
+			sample_rates.push(range.max_sample_rate);
+		}
+		sample_rates.sort();
+		sample_rates.dedup();
+		sample_rates
+	}
+
+	/// Get a sorted list of supported channel counts.
+	pub fn channel_counts(&self) -> Vec<usize> {
+		let mut channel_counts:Vec<usize> = self.config_ranges.iter().map(|range| range.channel_count).collect();
+		channel_counts.sort();
+		channel_counts.dedup();
+		channel_counts
+	}
+
+	/// Get a list of supported sample formats.
+	pub fn sample_formats(&self) -> Vec<SampleFormat> {
+		let mut sample_formats:Vec<SampleFormat> = Vec::new();
+		for range in &self.config_ranges {
+			if !sample_formats.contains(&range.sample_format) {
+				sample_formats.push(range.sample_format);
+			}
+		}
+		sample_formats
+	}
+
+	/// Get the smallest and largest supported buffer size in samples. Returns None if the device does not report its buffer sizes.
+	pub fn buffer_size_range(&self) -> Option<(u32, u32)> {
+		let buffer_size_ranges:Vec<(u32, u32)> = self.config_ranges.iter().filter_map(|range| range.buffer_size_range).collect();
+		if buffer_size_ranges.is_empty() {
+			None
+		} else {
+			Some((buffer_size_ranges.iter().map(|(min, _)| *min).min().unwrap_or(0), buffer_size_ranges.iter().map(|(_, max)| *max).max().unwrap_or(0)))
+		}
+	}
+
+	/// Check if the device supports the given configuration.
+	pub fn supports(&self, sample_rate:u32, channel_count:usize, sample_format:SampleFormat) -> bool {
+		self.config_ranges.iter().any(|range| range.supports(sample_rate, channel_count, sample_format))
+	}
+}
\ No newline at end of file
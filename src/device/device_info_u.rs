@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ DeviceConfig, DeviceConfigRange, DeviceInfo, SampleFormat };
+
+
+
+	fn create_device_info() -> DeviceInfo {
+		DeviceInfo::new(
+			"Test Speakers",
+			true,
+			true,
+			Some(DeviceConfig { sample_rate: 48000, channel_count: 2, sample_format: SampleFormat::F32, buffer_size_range: Some((64, 4096)) }),
+			vec![
+				DeviceConfigRange { channel_count: 2, min_sample_rate: 44100, max_sample_rate: 96000, sample_format: SampleFormat::F32, buffer_size_range: Some((64, 4096)) },
+				DeviceConfigRange { channel_count: 1, min_sample_rate: 8000, max_sample_rate: 48000, sample_format: SampleFormat::I16, buffer_size_range: Some((32, 2048)) },
+				DeviceConfigRange { channel_count: 6, min_sample_rate: 12345, max_sample_rate: 12345, sample_format: SampleFormat::I16, buffer_size_range: None }
+			]
+		)
+	}
+
+
+
+	#[test]
+	fn test_device_info_properties() {
+		let info:DeviceInfo = create_device_info();
+		assert_eq!(info.name(), "Test Speakers");
+		assert!(info.is_output_device());
+		assert!(info.is_default());
+		assert_eq!(info.default_config().unwrap().sample_rate, 48000);
+		assert_eq!(info.config_ranges().len(), 3);
+	}
+
+	#[test]
+	fn test_device_info_capabilities() {
+		let info:DeviceInfo = create_device_info();
+		assert_eq!(info.sample_rates(), vec![8000, 11025, 12345, 16000, 22050, 32000, 44100, 48000, 88200, 96000]);
+		assert_eq!(info.channel_counts(), vec![1, 2, 6]);
+		assert_eq!(info.sample_formats(), vec![SampleFormat::F32, SampleFormat::I16]);
+		assert_eq!(info.buffer_size_range(), Some((32, 4096)));
+	}
+
+	#[test]
+	fn test_device_info_supports() {
+		let info:DeviceInfo = create_device_info();
+		assert!(info.supports(96000, 2, SampleFormat::F32));
+		assert!(info.supports(16000, 1, SampleFormat::I16));
+		assert!(!info.supports(96000, 1, SampleFormat::I16));
+		assert!(!info.supports(192000, 2, SampleFormat::F32));
+		assert!(!info.supports(48000, 2, SampleFormat::I16));
+	}
+
+	#[test]
+	fn test_device_enumeration() {
+		for devices in [DeviceInfo::output_devices(), DeviceInfo::input_devices()].into_iter().flatten() {
+			for device in devices {
+				assert!(!device.name().is_empty());
+				assert!(device.sample_formats().len() <= device.config_ranges().len());
+			}
+		}
+	}
+}
\ No newline at end of file
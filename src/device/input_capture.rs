@@ -0,0 +1,244 @@
+use std::{ sync::{ Arc, Condvar, Mutex, MutexGuard }, time::{ Duration, Instant } };
+use crate::{ AudioBuffer, AudioBufferDataLength, AudioEffect, AudioGenerator, AudioWelderError, Resampler };
+
+
+
+pub(crate) struct CaptureStatus {
+	pub data:Vec<Vec<f32>>,
+	pub closed:bool,
+	pub error:Option<AudioWelderError>
+}
+
+
+
+pub(crate) struct CaptureState {
+	sample_rate:u32,
+	channel_count:usize,
+	max_buffered_samples:Option<usize>,
+	status:Mutex<CaptureStatus>,
+	status_change:Condvar
+}
+impl CaptureState {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new capture state for the given input properties. When a maximum amount of buffered samples is given, the oldest samples are discarded when more data is captured.
+	pub fn new(sample_rate:u32, channel_count:usize, max_buffered_samples:Option<usize>) -> CaptureState {
+		CaptureState {
+			sample_rate,
+			channel_count,
+			max_buffered_samples,
+			status: Mutex::new(CaptureStatus {
+				data: vec![Vec::new(); channel_count],
+				closed: false,
+				error: None
+			}),
+			status_change: Condvar::new()
+		}
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Lock the status of the capture.
+	pub fn status(&self) -> MutexGuard<'_, CaptureStatus> {
+		self.status.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	/// Block the current thread until the capture is closed.
+	pub fn await_close(&self) {
+		let mut status:MutexGuard<'_, CaptureStatus> = self.status();
+		while !status.closed {
+			status = self.status_change.wait(status).unwrap_or_else(|poisoned| poisoned.into_inner());
+		}
+	}
+
+	/// Block the current thread until the given amount of samples was captured, the capture is closed or the timeout passed. Returns the amount of samples captured.
+	pub fn await_sample_count(&self, sample_count:usize, timeout:Duration) -> usize {
+		let end_time:Instant = Instant::now() + timeout;
+		let mut status:MutexGuard<'_, CaptureStatus> = self.status();
+		loop {
+			let captured_sample_count:usize = status.data.iter().map(|channel| channel.len()).min().unwrap_or(0);
+			let now:Instant = Instant::now();
+			if captured_sample_count >= sample_count || status.closed || now >= end_time {
+				return captured_sample_count;
+			}
+			status = self.status_change.wait_timeout(status, end_time - now).unwrap_or_else(|poisoned| poisoned.into_inner()).0;
+		}
+	}
+
+	/// Close the capture, which releases the input stream.
+	pub fn close(&self) {
+		self.status().closed = true;
+		self.status_change.notify_all();
+	}
+
+	/// Store an error of the input stream. When the stream was lost, the capture is closed.
+	pub fn report_error(&self, error:AudioWelderError, stream_lost:bool) {
+		self.status().error = Some(error);
+		if stream_lost {
+			self.close();
+		}
+	}
+
+	/// Store the given interleaved input data.
+	pub fn push(&self, data:&[f32]) {
+		let mut status:MutexGuard<'_, CaptureStatus> = self.status();
+		if status.closed || self.channel_count == 0 {
+			return;
+		}
+
+		// Split interleaved data into channels.
+		for (sample_index, sample) in data.iter().enumerate() {
+			status.data[sample_index % self.channel_count].push(*sample);
+		}
+
+		// Discard oldest data.
+		if let Some(max_buffered_samples) = self.max_buffered_samples {
+			for channel in &mut status.data {
+				if channel.len() > max_buffered_samples {
+					let overflow:usize = channel.len() - max_buffered_samples;
+					channel.drain(..overflow);
+				}
+			}
+		}
+		drop(status);
+		self.status_change.notify_all();
+	}
+
+	/// Take all captured data out of the capture.
+	pub fn take_all(&self) -> AudioBuffer {
+		let data:Vec<Vec<f32>> = self.status().data.iter_mut().map(std::mem::take).collect();
+		AudioBuffer::new(data, self.sample_rate)
+	}
+}
+
+
+
+pub struct RecordingHandle {
+	state:Arc<CaptureState>
+}
+impl RecordingHandle {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new handle controlling the given capture state.
+	pub(crate) fn new(state:Arc<CaptureState>) -> RecordingHandle {
+		RecordingHandle {
+			state
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the amount of samples recorded so far.
+	pub fn recorded_sample_count(&self) -> usize {
+		self.state.status().data.iter().map(|channel| channel.len()).min().unwrap_or(0)
+	}
+
+	/// Take the last error of the input stream, if any occurred since the last call. When the device was lost, the recording is stopped and this returns the reason.
+	pub fn take_error(&self) -> Option<AudioWelderError> {
+		self.state.status().error.take()
+	}
+
+
+
+	/* CONTROL METHODS */
+
+	/// Block the current thread until the given amount of samples was recorded, the recording is stopped or the timeout passed. Returns the amount of samples recorded.
+	pub(crate) fn await_sample_count(&self, sample_count:usize, timeout:Duration) -> usize {
+		self.state.await_sample_count(sample_count, timeout)
+	}
+
+	/// Stop the recording and return the recorded audio.
+	pub fn stop(self) -> AudioBuffer {
+		self.state.close();
+		self.state.take_all()
+	}
+}
+impl Drop for RecordingHandle {
+	fn drop(&mut self) {
+		self.state.close();
+	}
+}
+
+
+
+pub struct LiveInput {
+	state:Arc<CaptureState>,
+	sample_rate:u32,
+	channel_count:usize,
+	resampler:Option<Resampler>
+}
+impl LiveInput {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new live input reading from the given capture state. When the given sample rate or channel count differs from the capture, the captured audio is converted to it.
+	pub(crate) fn new(state:Arc<CaptureState>, sample_rate:u32, channel_count:usize) -> LiveInput {
+		let resampler:Option<Resampler> = if state.sample_rate != sample_rate || state.channel_count != channel_count { Some(Resampler::new(sample_rate, channel_count)) } else { None };
+		LiveInput {
+			state,
+			sample_rate,
+			channel_count,
+			resampler
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the sample rate of the audio given by the live input.
+	pub fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+
+	/// Get the channel count of the audio given by the live input.
+	pub fn channel_count(&self) -> usize {
+		self.channel_count
+	}
+
+	/// Get the amount of captured samples waiting to be taken, at the sample rate of the live input.
+	pub fn available_sample_count(&self) -> usize {
+		let captured_sample_count:usize = self.state.status().data.iter().map(|channel| channel.len()).min().unwrap_or(0);
+		(captured_sample_count as u64 * self.sample_rate as u64 / self.state.sample_rate.max(1) as u64) as usize
+	}
+
+	/// Take the last error of the input stream, if any occurred since the last call. When the device was lost, the live input is closed and this returns the reason.
+	pub fn take_error(&self) -> Option<AudioWelderError> {
+		self.state.status().error.take()
+	}
+}
+impl AudioGenerator for LiveInput {
+
+	/// Take a specific amount of data. Returns less data when not enough audio has been captured yet.
+	fn take<T>(&mut self, duration:T) -> Vec<Vec<f32>> where T:AudioBufferDataLength {
+		let target_sample_len:usize = duration.as_buffer_length(self.sample_rate).min(self.available_sample_count());
+		let source_sample_len:usize = (target_sample_len as u64 * self.state.sample_rate as u64 / self.sample_rate.max(1) as u64) as usize;
+		let mut data:Vec<Vec<f32>> = self.state.status().data.iter_mut().map(|channel| channel.drain(..source_sample_len).collect()).collect();
+
+		// Convert to the format of the live input.
+		if let Some(resampler) = &mut self.resampler {
+			if source_sample_len == 0 {
+				return vec![Vec::new(); self.channel_count];
+			}
+			resampler.apply_to(&mut data, &mut self.state.sample_rate.clone(), &mut self.state.channel_count.clone());
+		}
+		data
+	}
+
+	/// Check if the generator has no more data to give.
+	fn is_depleted(&self) -> bool {
+		let status:MutexGuard<'_, CaptureStatus> = self.state.status();
+		status.closed && status.data.iter().all(|channel| channel.is_empty())
+	}
+}
+impl Drop for LiveInput {
+	fn drop(&mut self) {
+		self.state.close();
+	}
+}
\ No newline at end of file
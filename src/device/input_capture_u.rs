@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioGenerator, AudioWelderError, LiveInput, OutputMixer, RecordingHandle };
+	use super::super::{ input_capture::CaptureState, output_mixer::MixerState };
+	use std::{ f32::consts::TAU, sync::Arc, thread, time::Duration };
+
+
+
+	#[test]
+	fn test_recording_deinterleaves() {
+		let state:Arc<CaptureState> = Arc::new(CaptureState::new(10, 2, None));
+		let recording:RecordingHandle = RecordingHandle::new(state.clone());
+		state.push(&[0.1, -0.1, 0.2, -0.2]);
+		state.push(&[0.3, -0.3]);
+		assert_eq!(recording.recorded_sample_count(), 3);
+
+		let buffer:AudioBuffer = recording.stop();
+		assert_eq!(buffer.sample_rate(), 10);
+		assert_eq!(buffer.raw_data(), &vec![vec![0.1, 0.2, 0.3], vec![-0.1, -0.2, -0.3]]);
+
+		// Data pushed after stopping is ignored.
+		state.push(&[0.4, -0.4]);
+		assert_eq!(state.status().data, vec![Vec::<f32>::new(); 2]);
+	}
+
+	#[test]
+	fn test_recording_awaits_sample_count() {
+		let state:Arc<CaptureState> = Arc::new(CaptureState::new(10, 1, None));
+		let recording:RecordingHandle = RecordingHandle::new(state.clone());
+		let push_state:Arc<CaptureState> = state.clone();
+		thread::spawn(move || {
+			for _ in 0..5 {
+				thread::sleep(Duration::from_millis(10));
+				push_state.push(&[0.1, 0.2]);
+			}
+		});
+		assert_eq!(recording.await_sample_count(10, Duration::from_secs(5)), 10);
+
+		// Waiting stops at the timeout or when the capture closes.
+		assert_eq!(recording.await_sample_count(20, Duration::from_millis(20)), 10);
+		state.close();
+		assert_eq!(recording.await_sample_count(20, Duration::from_secs(5)), 10);
+	}
+
+	#[test]
+	fn test_capture_errors() {
+		let state:Arc<CaptureState> = Arc::new(CaptureState::new(10, 1, None));
+		let recording:RecordingHandle = RecordingHandle::new(state.clone());
+
+		// Recoverable errors are stored without closing the capture.
+		state.report_error(AudioWelderError::Stream("Buffer overrun.".to_string()), false);
+		assert!(!state.status().closed);
+		assert!(matches!(recording.take_error(), Some(AudioWelderError::Stream(_))));
+		assert!(recording.take_error().is_none());
+
+		// Losing the stream closes the capture.
+		let live_state:Arc<CaptureState> = Arc::new(CaptureState::new(10, 1, None));
+		let mut live_input:LiveInput = LiveInput::new(live_state.clone(), 10, 1);
+		live_state.push(&[0.1]);
+		live_state.report_error(AudioWelderError::DeviceNotFound("Device unplugged.".to_string()), true);
+		assert!(matches!(live_input.take_error(), Some(AudioWelderError::DeviceNotFound(_))));
+		assert_eq!(live_input.take(10), vec![vec![0.1]]);
+		assert!(live_input.is_depleted());
+	}
+
+	#[test]
+	fn test_live_input_take() {
+		let state:Arc<CaptureState> = Arc::new(CaptureState::new(10, 1, None));
+		let mut live_input:LiveInput = LiveInput::new(state.clone(), 10, 1);
+		state.push(&[0.1, 0.2, 0.3, 0.4, 0.5]);
+		assert_eq!(live_input.available_sample_count(), 5);
+		assert_eq!(live_input.take(2), vec![vec![0.1, 0.2]]);
+		assert_eq!(live_input.take(10), vec![vec![0.3, 0.4, 0.5]]);
+		assert_eq!(live_input.take(10), vec![Vec::<f32>::new()]);
+		assert!(!live_input.is_depleted());
+
+		drop(live_input);
+		assert!(state.status().closed);
+	}
+
+	#[test]
+	fn test_live_input_discards_oldest() {
+		let state:Arc<CaptureState> = Arc::new(CaptureState::new(10, 1, Some(3)));
+		let mut live_input:LiveInput = LiveInput::new(state.clone(), 10, 1);
+		state.push(&[0.1, 0.2, 0.3, 0.4, 0.5]);
+		assert_eq!(live_input.take(10), vec![vec![0.3, 0.4, 0.5]]);
+	}
+
+	#[test]
+	fn test_live_input_converts_format() {
+		let state:Arc<CaptureState> = Arc::new(CaptureState::new(8000, 1, None));
+		let mut live_input:LiveInput = LiveInput::new(state.clone(), 16000, 2);
+		assert_eq!(live_input.sample_rate(), 16000);
+		assert_eq!(live_input.channel_count(), 2);
+		assert_eq!(live_input.take(10), vec![Vec::<f32>::new(); 2]);
+
+		// Captured audio comes out at the sample rate and channel count of the live input.
+		state.push(&(0..8000).map(|index| (index as f32 / 8000.0 * 440.0 * TAU).sin() * 0.5).collect::<Vec<f32>>());
+		assert_eq!(live_input.available_sample_count(), 16000);
+		let mut output:Vec<Vec<f32>> = vec![Vec::new(); 2];
+		while live_input.available_sample_count() > 0 {
+			for (channel, new_channel) in output.iter_mut().zip(live_input.take(512)) {
+				channel.extend(new_channel);
+			}
+		}
+		assert_eq!(output[0], output[1]);
+		assert!(output[0].len() > 15000 && output[0].len() <= 16000);
+		assert!(output[0].iter().all(|sample| sample.abs() < 0.6));
+	}
+
+	#[test]
+	fn test_live_input_into_mixer() {
+		let capture_state:Arc<CaptureState> = Arc::new(CaptureState::new(10, 1, None));
+		let mixer_state:Arc<MixerState> = Arc::new(MixerState::new(10, 1));
+		let mixer:OutputMixer = OutputMixer::new(mixer_state.clone());
+		mixer.add_source(LiveInput::new(capture_state.clone(), 10, 1));
+
+		capture_state.push(&[0.1, 0.2]);
+		let mut output:Vec<f32> = vec![1.0; 4];
+		mixer_state.fill(&mut output);
+		assert_eq!(output, vec![0.1, 0.2, 0.0, 0.0]);
+		assert_eq!(mixer.source_count(), 1);
+	}
+}
\ No newline at end of file
@@ -0,0 +1,102 @@
+use cpal::SampleFormat;
+use std::{ sync::Arc, time::Duration };
+use crate::{ AudioBuffer, AudioBufferDataLength, AudioWelderError };
+use super::{ audio_device::AudioDevice, input_capture::{ CaptureState, LiveInput, RecordingHandle }, sample_converter::SampleConverter };
+
+
+
+const LIVE_INPUT_MAX_BUFFER_DURATION:Duration = Duration::from_secs(1);
+const RECORD_TIMEOUT_MARGIN:Duration = Duration::from_secs(2);
+
+
+
+pub struct InputDevice {
+	device:AudioDevice
+}
+impl InputDevice {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new audio device.
+	pub fn new(device_name:&str) -> Result<InputDevice, AudioWelderError> {
+		Ok(InputDevice {
+			device: AudioDevice::new(device_name, false)?
+		})
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the sample rate of the device.
+	pub fn sample_rate(&self) -> u32 {
+		self.device.sample_rate
+	}
+
+	/// Get the channel count of the device.
+	pub fn channel_count(&self) -> usize {
+		self.device.channel_count
+	}
+
+
+
+	/* AUDIO RECORDING METHODS */
+
+	/// Record audio for the given duration. Blocks the current thread until the full duration was recorded. Fails when the input stream is lost or stops delivering data.
+	pub fn record(&self, duration:Duration) -> Result<AudioBuffer, AudioWelderError> {
+		let recording:RecordingHandle = self.start_recording()?;
+
+		// Wait until enough samples were recorded, as the stream may start delivering data later than requested.
+		let target_sample_len:usize = duration.as_buffer_length(self.device.sample_rate);
+		if recording.await_sample_count(target_sample_len, duration + RECORD_TIMEOUT_MARGIN) < target_sample_len {
+			return Err(recording.take_error().unwrap_or_else(|| AudioWelderError::Stream("Timed out waiting for input data.".to_string())));
+		}
+		let buffer:AudioBuffer = recording.stop();
+
+		// Trim to the requested duration.
+		let data:Vec<Vec<f32>> = buffer.raw_data().iter().map(|channel| channel[..target_sample_len.min(channel.len())].to_vec()).collect();
+		Ok(AudioBuffer::new(data, self.device.sample_rate))
+	}
+
+	/// Start recording audio without blocking the current thread. The recording continues until the returned handle is stopped.
+	pub fn start_recording(&self) -> Result<RecordingHandle, AudioWelderError> {
+		let state:Arc<CaptureState> = Arc::new(CaptureState::new(self.device.sample_rate, self.device.channel_count, None));
+		self.spawn_input_stream(state.clone())?;
+		Ok(RecordingHandle::new(state))
+	}
+
+	/// Open a live input. The live input is an audio generator that gives the captured audio as it comes in, converted to the given sample rate and channel count, like the ones of the mixer or output it is added to. Captured audio that is not taken within a second is discarded. The stream closes when the live input is dropped.
+	pub fn live(&self, sample_rate:u32, channel_count:usize) -> Result<LiveInput, AudioWelderError> {
+		let max_buffered_samples:usize = LIVE_INPUT_MAX_BUFFER_DURATION.as_buffer_length(self.device.sample_rate);
+		let state:Arc<CaptureState> = Arc::new(CaptureState::new(self.device.sample_rate, self.device.channel_count, Some(max_buffered_samples)));
+		self.spawn_input_stream(state.clone())?;
+		Ok(LiveInput::new(state, sample_rate, channel_count))
+	}
+
+	/// Open an input stream that stores its data in the given capture state. Blocks until the stream is running. The stream is kept open until the capture is closed. Stream errors are stored on the capture, and losing the device closes it.
+	fn spawn_input_stream(&self, state:Arc<CaptureState>) -> Result<(), AudioWelderError> {
+		let sample_format:SampleFormat = self.device.sample_format;
+		let callback_state:Arc<CaptureState> = state.clone();
+		let error_state:Arc<CaptureState> = state.clone();
+		self.device.spawn_stream(
+			move |cpal_device, stream_config, error_callback| {
+				let callback_state:Arc<CaptureState> = callback_state.clone();
+				SampleConverter::build_input_stream(
+					cpal_device,
+					stream_config,
+					sample_format,
+					move |data, _| callback_state.push(data),
+					error_callback
+				)
+			},
+			move || state.await_close(),
+			move |error, stream_lost| error_state.report_error(error, stream_lost),
+			false
+		)
+	}
+}
+impl Default for InputDevice {
+	fn default() -> Self {
+		InputDevice::new("default").unwrap()
+	}
+}
\ No newline at end of file
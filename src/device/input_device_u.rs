@@ -0,0 +1,11 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioWelderError, InputDevice };
+
+
+
+	#[test]
+	fn test_missing_device() {
+		assert!(matches!(InputDevice::new("audio_welder_nonexistent_device"), Err(AudioWelderError::DeviceNotFound(_))));
+	}
+}
\ No newline at end of file
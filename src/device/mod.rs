@@ -1,5 +1,38 @@
 mod audio_device;
+mod device_info;
+mod device_info_u;
+mod input_capture;
+mod input_capture_u;
+mod input_device;
+mod input_device_u;
+mod null_sink;
+mod null_sink_u;
+mod output_backend;
 mod output_device;
 mod output_device_u;
+mod output_device_builder;
+mod output_mixer;
+mod output_mixer_u;
+mod playback_handle;
+mod playback_handle_u;
+mod playlist;
+mod playlist_u;
+mod sample_converter;
+mod sample_converter_u;
+mod stream_config_request;
+mod stream_config_request_u;
+mod wav_sink;
+mod wav_sink_u;
 
-pub use output_device::OutputDevice;
\ No newline at end of file
+pub use cpal::SampleFormat;
+pub use device_info::{ DeviceConfig, DeviceConfigRange, DeviceInfo };
+pub use input_capture::{ LiveInput, RecordingHandle };
+pub use input_device::InputDevice;
+pub use null_sink::NullSink;
+pub use output_backend::{ OutputBackend, OutputStream };
+pub use output_device::OutputDevice;
+pub use output_device_builder::OutputDeviceBuilder;
+pub use output_mixer::OutputMixer;
+pub use playback_handle::PlaybackHandle;
+pub use playlist::{ Playlist, RepeatMode };
+pub use wav_sink::WavSink;
\ No newline at end of file
@@ -0,0 +1,63 @@
+use std::{ thread, time::Duration };
+use crate::AudioWelderError;
+use super::output_backend::{ OutputBackend, OutputStream };
+
+
+
+/// An output backend that consumes audio without playing it. Useful for running playback without audio hardware.
+pub struct NullSink {
+	sample_rate:u32,
+	channel_count:usize,
+	real_time:bool,
+	latency:Duration
+}
+impl NullSink {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new null sink consuming audio in real time.
+	pub fn new(sample_rate:u32, channel_count:usize) -> NullSink {
+		NullSink {
+			sample_rate,
+			channel_count: channel_count.max(1),
+			real_time: true,
+			latency: Duration::ZERO
+		}
+	}
+
+
+
+	/* BUILDER METHODS */
+
+	/// Return self with real time enabled or disabled. When disabled, audio is consumed as fast as possible. Enabled by default.
+	pub fn real_time(mut self, real_time:bool) -> Self {
+		self.real_time = real_time;
+		self
+	}
+
+	/// Return self with the given output latency. In real time, the stream is released this long after the last audio was consumed, like a device that plays audio some time after receiving it. Defaults to no latency.
+	pub fn latency(mut self, latency:Duration) -> Self {
+		self.latency = latency;
+		self
+	}
+}
+impl OutputBackend for NullSink {
+	fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+	fn channel_count(&self) -> usize {
+		self.channel_count
+	}
+	fn start(&self, stream:OutputStream) -> Result<(), AudioWelderError> {
+		let sample_rate:u32 = self.sample_rate;
+		let real_time:bool = self.real_time;
+		let latency:Duration = self.latency;
+		thread::spawn(move || {
+			stream.render(sample_rate, real_time, |_| Ok(()));
+			if real_time {
+				thread::sleep(latency);
+			}
+		});
+		Ok(())
+	}
+}
\ No newline at end of file
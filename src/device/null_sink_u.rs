@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, NullSink, OutputDevice, OutputMixer, PlaybackHandle };
+	use std::time::{ Duration, Instant };
+
+
+
+	#[test]
+	fn test_null_sink_real_time() {
+		let device:OutputDevice = OutputDevice::from_backend(NullSink::new(1000, 2));
+		assert_eq!(device.sample_rate(), 1000);
+		assert_eq!(device.channel_count(), 2);
+
+		let start_time:Instant = Instant::now();
+		let handle:PlaybackHandle = device.play_async(AudioBuffer::new(vec![vec![0.5; 200]], 1000)).unwrap();
+		handle.wait();
+		let time_played:u128 = start_time.elapsed().as_millis();
+		assert!(handle.is_finished());
+		assert_eq!(handle.position(), Duration::from_millis(200));
+		assert!((190..400).contains(&time_played), "played for {time_played}ms");
+	}
+
+	#[test]
+	fn test_null_sink_latency() {
+		let device:OutputDevice = OutputDevice::from_backend(NullSink::new(1000, 2).latency(Duration::from_millis(100)));
+
+		let start_time:Instant = Instant::now();
+		let handle:PlaybackHandle = device.play_async(AudioBuffer::new(vec![vec![0.5; 200]], 1000)).unwrap();
+		handle.wait();
+		let time_played:u128 = start_time.elapsed().as_millis();
+		assert_eq!(handle.position(), Duration::from_millis(200));
+		assert!((290..500).contains(&time_played), "played for {time_played}ms");
+	}
+
+	#[test]
+	fn test_null_sink_faster_than_real_time() {
+		let device:OutputDevice = OutputDevice::from_backend(NullSink::new(44100, 2).real_time(false));
+
+		let start_time:Instant = Instant::now();
+		let handle:PlaybackHandle = device.play_async(AudioBuffer::new(vec![vec![0.5; 441000]], 44100)).unwrap();
+		handle.wait();
+		assert!(handle.is_finished());
+		assert_eq!(handle.position(), Duration::from_secs(10));
+		assert!(start_time.elapsed() < Duration::from_secs(5));
+	}
+
+	#[test]
+	fn test_null_sink_drained_buffer() {
+		let device:OutputDevice = OutputDevice::from_backend(NullSink::new(1000, 1).real_time(false));
+		let handle:PlaybackHandle = device.play_async(AudioBuffer::new(vec![vec![0.5; 100]], 1000).drain_progression()).unwrap();
+		let start_time:Instant = Instant::now();
+		while !handle.is_finished() && start_time.elapsed() < Duration::from_secs(5) {
+			std::thread::sleep(Duration::from_millis(1));
+		}
+		assert!(handle.is_finished());
+	}
+
+	#[test]
+	fn test_null_sink_mixer() {
+		let device:OutputDevice = OutputDevice::from_backend(NullSink::new(1000, 1).real_time(false));
+		let mixer:OutputMixer = device.mixer().unwrap();
+		mixer.add_buffer(AudioBuffer::new(vec![vec![0.5; 100]], 1000));
+		let start_time:Instant = Instant::now();
+		while mixer.source_count() > 0 && start_time.elapsed() < Duration::from_secs(5) {
+			std::thread::sleep(Duration::from_millis(1));
+		}
+		assert_eq!(mixer.source_count(), 0);
+	}
+}
\ No newline at end of file
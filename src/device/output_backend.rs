@@ -0,0 +1,164 @@
+use cpal::SampleFormat;
+use std::{ sync::Arc, thread::sleep, time::{ Duration, Instant } };
+use crate::AudioWelderError;
+
+
+
+const RENDER_BLOCKS_PER_SECOND:usize = 100;
+
+
+
+pub(crate) type ErrorCallback = Arc<dyn Fn(&AudioWelderError) + Send + Sync>;
+
+
+
+/// A destination for output streams, like an audio device or a file.
+pub trait OutputBackend:Send {
+
+	/// Get the sample rate the backend plays at.
+	fn sample_rate(&self) -> u32;
+
+	/// Get the channel count the backend plays at.
+	fn channel_count(&self) -> usize;
+
+	/// Get the sample format the backend plays at.
+	fn sample_format(&self) -> SampleFormat {
+		SampleFormat::F32
+	}
+
+	/// Get the buffer size in samples per channel. Returns None when the backend uses a default buffer size.
+	fn buffer_size(&self) -> Option<u32> {
+		None
+	}
+
+	/// Start playing the given stream. Should return once the stream is playing. The stream should keep being filled until it has ended, after which the stream should be dropped.
+	fn start(&self, stream:OutputStream) -> Result<(), AudioWelderError>;
+}
+
+
+
+/// The source of an output stream, like a playback or a mixer.
+pub(crate) trait OutputSource:Send + Sync {
+	fn fill(&self, data:&mut [f32], channel_count:usize) -> usize;
+	fn has_ended(&self) -> bool;
+	fn await_end(&self);
+	fn await_activity(&self) {}
+	fn report_error(&self, error:AudioWelderError, stream_lost:bool);
+	fn stream_opened(&self) {}
+	fn stream_closed(&self) {}
+}
+
+
+
+struct OutputStreamSource {
+	source:Arc<dyn OutputSource>
+}
+impl Drop for OutputStreamSource {
+	fn drop(&mut self) {
+		self.source.stream_closed();
+	}
+}
+
+
+
+#[derive(Clone)]
+pub struct OutputStream {
+	source:Arc<OutputStreamSource>,
+	channel_count:usize,
+	error_callback:Option<ErrorCallback>,
+	dither:bool,
+	auto_reconnect:bool
+}
+impl OutputStream {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new output stream for the given source. The source is notified once all clones of the stream are dropped.
+	pub(crate) fn new(source:Arc<dyn OutputSource>, channel_count:usize, error_callback:Option<ErrorCallback>, dither:bool, auto_reconnect:bool) -> OutputStream {
+		source.stream_opened();
+		OutputStream {
+			source: Arc::new(OutputStreamSource { source }),
+			channel_count,
+			error_callback,
+			dither,
+			auto_reconnect
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Check if dither should be added when converting samples to an integer sample format of 16 bits or less.
+	pub fn dither(&self) -> bool {
+		self.dither
+	}
+
+	/// Check if the stream should reconnect to the default device when the device becomes unavailable.
+	pub fn auto_reconnect(&self) -> bool {
+		self.auto_reconnect
+	}
+
+	/// Check if the stream has ended, meaning it no longer needs to be filled.
+	pub fn has_ended(&self) -> bool {
+		self.source.source.has_ended()
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Fill the given interleaved output data with the next samples of the stream. Returns the amount of frames that were played, which is less than the data holds when the source ended before its end.
+	pub fn fill(&self, data:&mut [f32]) -> usize {
+		self.source.source.fill(data, self.channel_count)
+	}
+
+	/// Block the current thread until the stream has ended.
+	pub fn await_end(&self) {
+		self.source.source.await_end();
+	}
+
+	/// Block the current thread while the source has nothing to play, like a paused playback or a mixer without sources, until it has or the stream ends.
+	pub fn await_activity(&self) {
+		self.source.source.await_activity();
+	}
+
+	/// Report an error of the backend. When the stream was lost, the stream ends.
+	pub fn report_error(&self, error:AudioWelderError, stream_lost:bool) {
+		if let Some(error_callback) = &self.error_callback {
+			error_callback(&error);
+		}
+		self.source.source.report_error(error, stream_lost);
+	}
+
+	/// Fill blocks of data and pass them to the given output until the stream ends. The last block only holds the frames played before the source ended. When real time is enabled, blocks are produced at the pace they would be played at. Otherwise rendering waits while the source has nothing to play. Errors of the output stop the stream.
+	pub(crate) fn render<T>(&self, sample_rate:u32, real_time:bool, mut output:T) where T:FnMut(&[f32]) -> Result<(), AudioWelderError> {
+		let block_frames:usize = (sample_rate as usize / RENDER_BLOCKS_PER_SECOND).max(1);
+		let mut data:Vec<f32> = vec![0.0; block_frames * self.channel_count];
+		let start_time:Instant = Instant::now();
+		let mut rendered_frames:u64 = 0;
+		loop {
+			if !real_time {
+				self.await_activity();
+			}
+			if self.has_ended() {
+				return;
+			}
+			data.fill(0.0);
+			let played_frames:usize = self.fill(&mut data).min(block_frames);
+			if let Err(error) = output(&data[..played_frames * self.channel_count]) {
+				self.report_error(error, true);
+				return;
+			}
+
+			// Keep pace with playback.
+			rendered_frames += block_frames as u64;
+			if real_time {
+				let target_time:Instant = start_time + Duration::from_nanos(rendered_frames * 1_000_000_000 / sample_rate.max(1) as u64);
+				if let Some(wait_time) = target_time.checked_duration_since(Instant::now()) {
+					sleep(wait_time);
+				}
+			}
+		}
+	}
+}
\ No newline at end of file
@@ -1,22 +1,38 @@
-use cpal::{ traits::{ DeviceTrait, StreamTrait }, Device as CpalDevice, Stream, StreamConfig, StreamError };
-use std::{ error::Error, thread::sleep, time::Duration };
-use crate::{ AudioBuffer, AudioGenerator };
-use super::audio_device::AudioDevice;
+use cpal::SampleFormat;
+use std::{ sync::Arc, thread };
+use crate::{ AudioBuffer, AudioWelderError, OutputBackend, OutputDeviceBuilder };
+use super::{ audio_device::AudioDevice, output_backend::{ ErrorCallback, OutputSource, OutputStream }, output_mixer::{ MixerState, OutputMixer }, playback_handle::{ PlaybackHandle, PlaybackState }, playlist::{ Playlist, PlaylistState } };
 
 
 
 pub struct OutputDevice {
-	device:AudioDevice
+	backend:Box<dyn OutputBackend>,
+	dither:bool,
+	error_callback:Option<ErrorCallback>,
+	auto_reconnect:bool
 }
 impl OutputDevice {
 
 	/* CONSTRUCTOR METHODS */
 
 	/// Create a new audio device.
-	pub fn new(device_name:&str) -> Result<OutputDevice, Box<dyn Error>> {
-		Ok(OutputDevice {
-			device: AudioDevice::new(device_name, true)?
-		})
+	pub fn new(device_name:&str) -> Result<OutputDevice, AudioWelderError> {
+		Ok(OutputDevice::from_backend(AudioDevice::new(device_name, true)?))
+	}
+
+	/// Create a builder to open an output device with a specific stream config.
+	pub fn builder(device_name:&str) -> OutputDeviceBuilder {
+		OutputDeviceBuilder::new(device_name)
+	}
+
+	/// Create an output device playing through the given backend, like a `NullSink` or `WavSink`.
+	pub fn from_backend<T>(backend:T) -> OutputDevice where T:OutputBackend + 'static {
+		OutputDevice {
+			backend: Box::new(backend),
+			dither: false,
+			error_callback: None,
+			auto_reconnect: false
+		}
 	}
 
 
@@ -25,7 +41,51 @@ impl OutputDevice {
 
 	/// Get the sample rate of the device.
 	pub fn sample_rate(&self) -> u32 {
-		self.device.sample_rate
+		self.backend.sample_rate()
+	}
+
+	/// Get the channel count of the device.
+	pub fn channel_count(&self) -> usize {
+		self.backend.channel_count()
+	}
+
+	/// Get the sample format of the device.
+	pub fn sample_format(&self) -> SampleFormat {
+		self.backend.sample_format()
+	}
+
+	/// Get the buffer size in samples per channel. Returns None when the device uses its default buffer size.
+	pub fn buffer_size(&self) -> Option<u32> {
+		self.backend.buffer_size()
+	}
+
+	/// Check if dither is added when converting samples to an integer sample format of 16 bits or less.
+	pub fn dither(&self) -> bool {
+		self.dither
+	}
+
+	/// Check if streams reconnect to the default device when the device becomes unavailable.
+	pub fn auto_reconnect(&self) -> bool {
+		self.auto_reconnect
+	}
+
+
+
+	/* PROPERTY SETTER METHODS */
+
+	/// Enable or disable dither. When enabled, TPDF dither is added when converting samples to an integer sample format of 16 bits or less. Disabled by default.
+	pub fn set_dither(&mut self, dither:bool) {
+		self.dither = dither;
+	}
+
+	/// Set a callback that receives all errors of streams opened on this device. Errors are also available through the playback handle or mixer.
+	pub fn set_error_callback<T>(&mut self, callback:T) where T:Fn(&AudioWelderError) + Send + Sync + 'static {
+		self.error_callback = Some(Arc::new(callback));
+	}
+
+	/// Enable or disable auto reconnect. When enabled and the device becomes unavailable during playback, the stream reconnects to the default device, retrying until a default device supporting the stream config is available. When disabled, losing the device stops the playback. Disabled by default.
+	pub fn set_auto_reconnect(&mut self, auto_reconnect:bool) {
+		self.auto_reconnect = auto_reconnect;
 	}
 
 
@@ -34,42 +94,59 @@ impl OutputDevice {
 
 	/// Resample buffer to match audio device sample rate and channel count.
 	pub fn prepare_buffer(&self, buffer:&mut AudioBuffer) {
-		self.device.prepare_buffer(buffer);
+		buffer.resample(self.sample_rate(), self.channel_count());
 	}
 
 	/// Play a wav file through this device.
-	pub fn play_wav(&self, wav:&str) -> Result<(), Box<dyn Error>> {
+	pub fn play_wav(&self, wav:&str) -> Result<(), AudioWelderError> {
 		self.play(AudioBuffer::wav(wav)?.clone())
 	}
 
-	/// Play an audio buffer through this device.
-	pub fn play(&self, mut buffer:AudioBuffer) -> Result<(), Box<dyn Error>> {
-		
+	/// Play an audio buffer through this device. Blocks until the end of the audio was played. Returns the error that stopped the playback, if any.
+	pub fn play(&self, buffer:AudioBuffer) -> Result<(), AudioWelderError> {
+		let handle:PlaybackHandle = self.play_async(buffer)?;
+		handle.wait();
+		match handle.take_error() {
+			Some(error) => Err(error),
+			None => Ok(())
+		}
+	}
+
+	/// Play an audio buffer through this device without blocking the current thread. Returns a handle to control the playback.
+	pub fn play_async(&self, mut buffer:AudioBuffer) -> Result<PlaybackHandle, AudioWelderError> {
+
 		// Modify buffer sample to fit device.
 		self.prepare_buffer(&mut buffer);
-		let buffer_duration:Duration = buffer.duration();
-
-		// Create output stream.
-		let channel_count:usize = self.device.channel_count;
-		let cpal_device:&CpalDevice = &self.device.cpal_device;
-		let output_stream:Stream = cpal_device.build_output_stream(
-			&StreamConfig {
-				channels: self.device.channel_count as u16,
-				sample_rate: cpal::SampleRate(self.device.sample_rate),
-				buffer_size: cpal_device.default_output_config().unwrap().config().buffer_size
-			},
-			move |data, _| {
-				let new_data:Vec<f32> = buffer.take_flat(data.len() / channel_count);
-				data[..new_data.len()].clone_from_slice(&new_data);
-			},
-			|err:StreamError| panic!("{err}"),
-			None
-		)?;
-
-		// Play audio and await finish.
-		output_stream.play()?;
-		sleep(buffer_duration);
-		Ok(())
+		let state:Arc<PlaybackState> = Arc::new(PlaybackState::new(buffer));
+
+		// Create output stream and return handle once the stream is playing.
+		self.start_stream(state.clone())?;
+		Ok(PlaybackHandle::new(state))
+	}
+
+	/// Open a mixer that keeps a single output stream open on this device. Sources can be added and removed while the stream runs. The stream closes when the mixer is dropped.
+	pub fn mixer(&self) -> Result<OutputMixer, AudioWelderError> {
+		let state:Arc<MixerState> = Arc::new(MixerState::new(self.sample_rate(), self.channel_count()));
+
+		// Create output stream and return mixer once the stream is playing.
+		self.start_stream(state.clone())?;
+		Ok(OutputMixer::new(state))
+	}
+
+	/// Open a playlist that plays its items back to back on a single output stream on this device. Items are loaded and resampled in the background before they are needed. The stream closes when the playlist is dropped.
+	pub fn playlist(&self) -> Result<Playlist, AudioWelderError> {
+		let state:Arc<PlaylistState> = Arc::new(PlaylistState::new(self.sample_rate(), self.channel_count()));
+
+		// Create output stream and load items in the background once the stream is playing.
+		self.start_stream(state.clone())?;
+		let loader_state:Arc<PlaylistState> = state.clone();
+		thread::spawn(move || loader_state.run_loader());
+		Ok(Playlist::new(state))
+	}
+
+	/// Start an output stream on the backend that requests its data from the given source. Blocks until the stream is playing. The stream is kept open until the source has ended.
+	fn start_stream(&self, source:Arc<dyn OutputSource>) -> Result<(), AudioWelderError> {
+		self.backend.start(OutputStream::new(source, self.channel_count(), self.error_callback.clone(), self.dither, self.auto_reconnect))
 	}
 }
 impl Default for OutputDevice {
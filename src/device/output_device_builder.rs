@@ -0,0 +1,106 @@
+use cpal::SampleFormat;
+use std::{ sync::Arc, time::Duration };
+use crate::{ AudioWelderError, OutputDevice };
+use super::{ audio_device::AudioDevice, output_backend::ErrorCallback, stream_config_request::{ BufferSizeRequest, StreamConfigRequest } };
+
+
+
+pub struct OutputDeviceBuilder {
+	device_name:String,
+	request:StreamConfigRequest,
+	dither:bool,
+	error_callback:Option<ErrorCallback>,
+	auto_reconnect:bool
+}
+impl OutputDeviceBuilder {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new builder for the output device with the given name. Properties that are not set use the default config of the device.
+	pub fn new(device_name:&str) -> OutputDeviceBuilder {
+		OutputDeviceBuilder {
+			device_name: device_name.to_string(),
+			request: StreamConfigRequest::default(),
+			dither: false,
+			error_callback: None,
+			auto_reconnect: false
+		}
+	}
+
+
+
+	/* BUILDER METHODS */
+
+	/// Return self with a specific sample rate.
+	pub fn sample_rate(mut self, sample_rate:u32) -> Self {
+		self.request.sample_rate = Some(sample_rate);
+		self
+	}
+
+	/// Return self with a specific channel count.
+	pub fn channel_count(mut self, channel_count:usize) -> Self {
+		self.request.channel_count = Some(channel_count);
+		self
+	}
+
+	/// Return self with a specific sample format.
+	pub fn sample_format(mut self, sample_format:SampleFormat) -> Self {
+		self.request.sample_format = Some(sample_format);
+		self
+	}
+
+	/// Return self with a specific buffer size in samples per channel. Overrides any previously set latency target.
+	pub fn buffer_size(mut self, buffer_size:u32) -> Self {
+		self.request.buffer_size = Some(BufferSizeRequest::Samples(buffer_size));
+		self
+	}
+
+	/// Return self with a buffer size matching the given latency target. Overrides any previously set buffer size.
+	pub fn latency(mut self, latency:Duration) -> Self {
+		self.request.buffer_size = Some(BufferSizeRequest::Latency(latency));
+		self
+	}
+
+	/// Return self with fallback behavior. When enabled, the nearest supported config is used if the device does not support the requested one. When disabled, building fails instead. Disabled by default.
+	pub fn fallback_to_nearest(mut self, fallback_to_nearest:bool) -> Self {
+		self.request.fallback_to_nearest = fallback_to_nearest;
+		self
+	}
+
+
+
+	/// Return self with dithering enabled or disabled. When enabled, TPDF dither is added when converting samples to an integer sample format of 16 bits or less. Disabled by default.
+	pub fn dither(mut self, dither:bool) -> Self {
+		self.dither = dither;
+		self
+	}
+
+
+
+	/// Return self with a callback that receives all errors of streams opened on the device.
+	pub fn on_error<T>(mut self, callback:T) -> Self where T:Fn(&AudioWelderError) + Send + Sync + 'static {
+		self.error_callback = Some(Arc::new(callback));
+		self
+	}
+
+	/// Return self with auto reconnect enabled or disabled. When enabled and the device becomes unavailable during playback, the stream reconnects to the default device. If the default device does not support the config of the stream, the stream is lost and the error reports the mismatch. Disabled by default.
+	pub fn auto_reconnect(mut self, auto_reconnect:bool) -> Self {
+		self.auto_reconnect = auto_reconnect;
+		self
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Open the output device with the requested config.
+	pub fn build(self) -> Result<OutputDevice, AudioWelderError> {
+		let mut device:OutputDevice = OutputDevice::from_backend(AudioDevice::with_config(&self.device_name, true, &self.request)?);
+		device.set_dither(self.dither);
+		device.set_auto_reconnect(self.auto_reconnect);
+		if let Some(error_callback) = self.error_callback {
+			device.set_error_callback(move |error| error_callback(error));
+		}
+		Ok(device)
+	}
+}
\ No newline at end of file
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
-	use crate::{ AudioBuffer, OutputDevice };
-	use std::time::Instant;
+	use crate::{ AudioBuffer, AudioWelderError, DurationModifier, NullSink, OutputDevice, WavFormat, WavSink };
+	use std::{ fs::create_dir_all, time::{ Duration, Instant } };
 
 
 
@@ -10,7 +10,7 @@ mod tests {
 		const RAW_SAMPLES:[f32; 16] = [0.0; 16];
 
 		// Prepare output device.
-		let default_device:OutputDevice = OutputDevice::default();
+		let default_device:OutputDevice = OutputDevice::from_backend(NullSink::new(44100, 2).latency(Duration::from_millis(20)));
 
 		// Prepare buffer.
 		let mut buffer:AudioBuffer = AudioBuffer::new(vec![RAW_SAMPLES.to_vec()], 160);
@@ -25,4 +25,25 @@ mod tests {
 		println!("played for {time_played}ms");
 		assert!(time_played > 100 && time_played < 200);
 	}
+
+	#[test]
+	fn test_play_until_end_of_stream() {
+		const FILE_PATH:&str = "target/debug_wavs/play_end_of_stream.wav";
+
+		// Play buffer with an effect that changes its length.
+		create_dir_all("target/debug_wavs").expect("Could not create debug wavs dir.");
+		let device:OutputDevice = OutputDevice::from_backend(WavSink::new(FILE_PATH, 1000, 1, WavFormat::Float32).real_time(false));
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![vec![0.5; 100]], 1000);
+		buffer.add_effect(DurationModifier::new(2.0));
+		device.play(buffer).unwrap();
+
+		// Validate the full stretched buffer was played.
+		let recording:AudioBuffer = AudioBuffer::wav(FILE_PATH).unwrap();
+		assert_eq!(recording.raw_data()[0].iter().filter(|sample| **sample != 0.0).count(), 200);
+	}
+
+	#[test]
+	fn test_missing_device() {
+		assert!(matches!(OutputDevice::new("audio_welder_nonexistent_device"), Err(AudioWelderError::DeviceNotFound(_))));
+	}
 }
\ No newline at end of file
@@ -0,0 +1,317 @@
+use std::{ f32::consts::FRAC_PI_4, sync::{ Arc, Condvar, Mutex, MutexGuard } };
+use crate::{ AudioBuffer, AudioGenerator, AudioWelderError };
+use super::output_backend::OutputSource;
+
+
+
+const CLIP_GUARD_THRESHOLD:f32 = 0.8;
+
+
+
+/// Object-safe wrapper around audio generators, allowing different generator types in a single mixer.
+trait MixerInput:Send {
+	fn take_samples(&mut self, sample_count:usize) -> Vec<Vec<f32>>;
+	fn is_depleted(&self) -> bool;
+}
+impl<T> MixerInput for T where T:AudioGenerator + Send {
+	fn take_samples(&mut self, sample_count:usize) -> Vec<Vec<f32>> {
+		self.take(sample_count)
+	}
+	fn is_depleted(&self) -> bool {
+		AudioGenerator::is_depleted(self)
+	}
+}
+
+
+
+struct MixerSource {
+	id:usize,
+	input:Box<dyn MixerInput>,
+	gain:f32,
+	pan:f32
+}
+impl MixerSource {
+
+	/// Add the next samples of this source to the given interleaved output data.
+	fn mix_into(&mut self, data:&mut [f32], channel_count:usize) {
+		let source_data:Vec<Vec<f32>> = self.input.take_samples(data.len() / channel_count);
+		let source_channel_count:usize = source_data.len();
+		if source_channel_count == 0 || channel_count == 0 {
+			return;
+		}
+
+		// Calculate the gain of each source channel in each output channel.
+		let channel_gains:Vec<Vec<(usize, f32)>> = (0..source_channel_count).map(|source_channel_index| {
+			if channel_count == 1 {
+				vec![(0, self.gain / source_channel_count as f32)]
+			} else if source_channel_count == 1 {
+				let angle:f32 = (self.pan.clamp(-1.0, 1.0) + 1.0) * FRAC_PI_4;
+				vec![(0, self.gain * angle.cos()), (1, self.gain * angle.sin())]
+			} else {
+				let target_channel:usize = source_channel_index % channel_count;
+				let balance:f32 = match target_channel {
+					0 => (1.0 - self.pan).min(1.0),
+					1 => (1.0 + self.pan).min(1.0),
+					_ => 1.0
+				};
+				vec![(target_channel, self.gain * balance)]
+			}
+		}).collect();
+
+		// Mix samples into output.
+		for (source_channel, gains) in source_data.iter().zip(&channel_gains) {
+			for (frame_index, sample) in source_channel.iter().enumerate().take(data.len() / channel_count) {
+				for (target_channel, gain) in gains {
+					data[frame_index * channel_count + target_channel] += sample * gain;
+				}
+			}
+		}
+	}
+}
+
+
+
+struct MixerStatus {
+	sources:Vec<MixerSource>,
+	next_source_id:usize,
+	master_gain:f32,
+	clip_guard:bool,
+	closed:bool,
+	error:Option<AudioWelderError>
+}
+
+
+
+pub(crate) struct MixerState {
+	sample_rate:u32,
+	channel_count:usize,
+	status:Mutex<MixerStatus>,
+	status_change:Condvar
+}
+impl MixerState {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new mixer state for the given output properties.
+	pub fn new(sample_rate:u32, channel_count:usize) -> MixerState {
+		MixerState {
+			sample_rate,
+			channel_count,
+			status: Mutex::new(MixerStatus {
+				sources: Vec::new(),
+				next_source_id: 0,
+				master_gain: 1.0,
+				clip_guard: true,
+				closed: false,
+				error: None
+			}),
+			status_change: Condvar::new()
+		}
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Lock the status of the mixer.
+	fn status(&self) -> MutexGuard<'_, MixerStatus> {
+		self.status.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	/// Block the current thread until the mixer is closed.
+	pub fn await_close(&self) {
+		let mut status:MutexGuard<'_, MixerStatus> = self.status();
+		while !status.closed {
+			status = self.status_change.wait(status).unwrap_or_else(|poisoned| poisoned.into_inner());
+		}
+	}
+
+	/// Block the current thread while the mixer has no sources, until a source is added or the mixer is closed.
+	pub fn await_sources(&self) {
+		let mut status:MutexGuard<'_, MixerStatus> = self.status();
+		while !status.closed && status.sources.is_empty() {
+			status = self.status_change.wait(status).unwrap_or_else(|poisoned| poisoned.into_inner());
+		}
+	}
+
+	/// Store an error of the output stream. When the stream was lost, the mixer is closed.
+	pub fn report_error(&self, error:AudioWelderError, stream_lost:bool) {
+		let mut status:MutexGuard<'_, MixerStatus> = self.status();
+		status.error = Some(error);
+		if stream_lost {
+			status.closed = true;
+			drop(status);
+			self.status_change.notify_all();
+		}
+	}
+
+	/// Fill the given interleaved output data with the mix of all sources.
+	pub fn fill(&self, data:&mut [f32]) {
+		let mut status:MutexGuard<'_, MixerStatus> = self.status();
+		data.fill(0.0);
+
+		// Mix sources and remove the ones that ran out.
+		for source in &mut status.sources {
+			source.mix_into(data, self.channel_count);
+		}
+		status.sources.retain(|source| !source.input.is_depleted());
+
+		// Apply master gain and clip guard.
+		let master_gain:f32 = status.master_gain;
+		let clip_guard:bool = status.clip_guard;
+		for sample in data.iter_mut() {
+			*sample *= master_gain;
+			if clip_guard {
+				*sample = MixerState::guard_clip(*sample);
+			}
+		}
+	}
+
+	/// Softly saturate samples above the clip guard threshold so the output never exceeds full scale.
+	fn guard_clip(sample:f32) -> f32 {
+		let sample_abs:f32 = sample.abs();
+		if sample_abs <= CLIP_GUARD_THRESHOLD {
+			sample
+		} else {
+			let headroom:f32 = 1.0 - CLIP_GUARD_THRESHOLD;
+			sample.signum() * (CLIP_GUARD_THRESHOLD + headroom * ((sample_abs - CLIP_GUARD_THRESHOLD) / headroom).tanh())
+		}
+	}
+}
+impl OutputSource for MixerState {
+	fn fill(&self, data:&mut [f32], channel_count:usize) -> usize {
+		MixerState::fill(self, data);
+		data.len() / channel_count.max(1)
+	}
+	fn has_ended(&self) -> bool {
+		self.status().closed
+	}
+	fn await_end(&self) {
+		self.await_close();
+	}
+	fn await_activity(&self) {
+		self.await_sources();
+	}
+	fn report_error(&self, error:AudioWelderError, stream_lost:bool) {
+		MixerState::report_error(self, error, stream_lost);
+	}
+}
+
+
+
+pub struct OutputMixer {
+	state:Arc<MixerState>
+}
+impl OutputMixer {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new mixer controlling the given mixer state.
+	pub(crate) fn new(state:Arc<MixerState>) -> OutputMixer {
+		OutputMixer {
+			state
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the sample rate of the mixer output. Sources should generate audio at this sample rate.
+	pub fn sample_rate(&self) -> u32 {
+		self.state.sample_rate
+	}
+
+	/// Get the channel count of the mixer output.
+	pub fn channel_count(&self) -> usize {
+		self.state.channel_count
+	}
+
+	/// Get the amount of sources currently playing.
+	pub fn source_count(&self) -> usize {
+		self.state.status().sources.len()
+	}
+
+	/// Check if a source with the given ID is still playing.
+	pub fn has_source(&self, source_id:usize) -> bool {
+		self.state.status().sources.iter().any(|source| source.id == source_id)
+	}
+
+	/// Check if the output stream of the mixer was closed because the device was lost.
+	pub fn is_closed(&self) -> bool {
+		self.state.status().closed
+	}
+
+	/// Take the last error of the output stream, if any occurred since the last call.
+	pub fn take_error(&self) -> Option<AudioWelderError> {
+		self.state.status().error.take()
+	}
+
+
+
+	/* SOURCE METHODS */
+
+	/// Add a source to the mix. Returns the ID of the source. The source is removed automatically once it is depleted.
+	pub fn add_source<T>(&self, source:T) -> usize where T:AudioGenerator + Send + 'static {
+		let mut status:MutexGuard<'_, MixerStatus> = self.state.status();
+		let id:usize = status.next_source_id;
+		status.next_source_id += 1;
+		status.sources.push(MixerSource {
+			id,
+			input: Box::new(source),
+			gain: 1.0,
+			pan: 0.0
+		});
+		drop(status);
+		self.state.status_change.notify_all();
+		id
+	}
+
+	/// Resample an audio buffer to the mixer output and add it to the mix. Returns the ID of the source.
+	pub fn add_buffer(&self, mut buffer:AudioBuffer) -> usize {
+		buffer.resample(self.sample_rate(), self.channel_count());
+		self.add_source(buffer)
+	}
+
+	/// Remove a source from the mix. Returns false if the source was not found.
+	pub fn remove_source(&self, source_id:usize) -> bool {
+		let mut status:MutexGuard<'_, MixerStatus> = self.state.status();
+		let source_count:usize = status.sources.len();
+		status.sources.retain(|source| source.id != source_id);
+		status.sources.len() != source_count
+	}
+
+	/// Set the gain of a source.
+	pub fn set_source_gain(&self, source_id:usize, gain:f32) {
+		if let Some(source) = self.state.status().sources.iter_mut().find(|source| source.id == source_id) {
+			source.gain = gain;
+		}
+	}
+
+	/// Set the pan of a source, ranging from -1.0 (left) to 1.0 (right). Mono sources are panned with an equal-power pan law, other sources are balanced.
+	pub fn set_source_pan(&self, source_id:usize, pan:f32) {
+		if let Some(source) = self.state.status().sources.iter_mut().find(|source| source.id == source_id) {
+			source.pan = pan.clamp(-1.0, 1.0);
+		}
+	}
+
+
+
+	/* MASTER METHODS */
+
+	/// Set the gain applied to the full mix.
+	pub fn set_master_gain(&self, gain:f32) {
+		self.state.status().master_gain = gain;
+	}
+
+	/// Enable or disable the clip guard. When enabled, peaks above 0.8 are softly saturated so the mix never exceeds full scale. Enabled by default.
+	pub fn set_clip_guard(&self, enabled:bool) {
+		self.state.status().clip_guard = enabled;
+	}
+}
+impl Drop for OutputMixer {
+	fn drop(&mut self) {
+		self.state.status().closed = true;
+		self.state.status_change.notify_all();
+	}
+}
\ No newline at end of file
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioWelderError, OutputMixer, WaveGenerator };
+	use super::super::output_mixer::MixerState;
+	use std::{ f32::consts::FRAC_1_SQRT_2, sync::Arc };
+
+
+
+	fn create_mixer(sample_rate:u32, channel_count:usize) -> (Arc<MixerState>, OutputMixer) {
+		let state:Arc<MixerState> = Arc::new(MixerState::new(sample_rate, channel_count));
+		(state.clone(), OutputMixer::new(state))
+	}
+
+	fn assert_close(actual:&[f32], expected:&[f32]) {
+		assert_eq!(actual.len(), expected.len());
+		for (actual, expected) in actual.iter().zip(expected) {
+			assert!((actual - expected).abs() < 0.0001, "{actual:?} != {expected:?}");
+		}
+	}
+
+
+
+	#[test]
+	fn test_mixer_sums_sources() {
+		let (state, mixer) = create_mixer(10, 1);
+		mixer.add_source(AudioBuffer::new(vec![vec![0.1, 0.2, 0.3]], 10));
+		mixer.add_source(AudioBuffer::new(vec![vec![0.2, 0.2, 0.2, 0.2]], 10));
+		assert_eq!(mixer.source_count(), 2);
+
+		let mut output:Vec<f32> = vec![1.0; 4];
+		state.fill(&mut output);
+		assert_close(&output, &[0.3, 0.4, 0.5, 0.2]);
+		assert_eq!(mixer.source_count(), 0);
+	}
+
+	#[test]
+	fn test_mixer_gain_and_pan() {
+		let (state, mixer) = create_mixer(10, 2);
+		let source_id:usize = mixer.add_source(AudioBuffer::new(vec![vec![0.5; 10]], 10));
+
+		// Mono sources are panned with equal power.
+		let mut output:Vec<f32> = vec![0.0; 2];
+		state.fill(&mut output);
+		assert_close(&output, &[0.5 * FRAC_1_SQRT_2, 0.5 * FRAC_1_SQRT_2]);
+
+		mixer.set_source_pan(source_id, -1.0);
+		state.fill(&mut output);
+		assert_close(&output, &[0.5, 0.0]);
+
+		mixer.set_source_gain(source_id, 0.5);
+		mixer.set_source_pan(source_id, 1.0);
+		state.fill(&mut output);
+		assert_close(&output, &[0.0, 0.25]);
+	}
+
+	#[test]
+	fn test_mixer_stereo_balance() {
+		let (state, mixer) = create_mixer(10, 2);
+		let source_id:usize = mixer.add_source(AudioBuffer::new(vec![vec![0.4; 10], vec![0.6; 10]], 10));
+
+		let mut output:Vec<f32> = vec![0.0; 2];
+		state.fill(&mut output);
+		assert_close(&output, &[0.4, 0.6]);
+
+		mixer.set_source_pan(source_id, 0.5);
+		state.fill(&mut output);
+		assert_close(&output, &[0.2, 0.6]);
+	}
+
+	#[test]
+	fn test_mixer_remove_source() {
+		let (state, mixer) = create_mixer(100, 1);
+		let sine_id:usize = mixer.add_source(WaveGenerator::sine(10.0, 100));
+		let buffer_id:usize = mixer.add_source(AudioBuffer::new(vec![vec![0.25; 100]], 100));
+		assert!(mixer.remove_source(sine_id));
+		assert!(!mixer.remove_source(sine_id));
+		assert!(!mixer.has_source(sine_id));
+		assert!(mixer.has_source(buffer_id));
+
+		let mut output:Vec<f32> = vec![0.0; 4];
+		state.fill(&mut output);
+		assert_close(&output, &[0.25; 4]);
+	}
+
+	#[test]
+	fn test_mixer_clip_guard() {
+		let (state, mixer) = create_mixer(10, 1);
+		mixer.add_source(AudioBuffer::new(vec![vec![0.9; 10]], 10));
+		mixer.add_source(AudioBuffer::new(vec![vec![0.9; 10]], 10));
+
+		// Clip guard keeps output within full scale.
+		let mut output:Vec<f32> = vec![0.0; 2];
+		state.fill(&mut output);
+		assert!(output.iter().all(|sample| *sample > 0.8 && *sample <= 1.0));
+
+		// Without clip guard, the raw sum is output.
+		mixer.set_clip_guard(false);
+		state.fill(&mut output);
+		assert_close(&output, &[1.8, 1.8]);
+
+		// Master gain is applied before the clip guard.
+		mixer.set_master_gain(0.25);
+		mixer.set_clip_guard(true);
+		state.fill(&mut output);
+		assert_close(&output, &[0.45, 0.45]);
+	}
+
+	#[test]
+	fn test_mixer_errors() {
+		let (state, mixer) = create_mixer(10, 1);
+
+		state.report_error(AudioWelderError::Stream("Buffer underrun.".to_string()), false);
+		assert!(!mixer.is_closed());
+		assert!(matches!(mixer.take_error(), Some(AudioWelderError::Stream(_))));
+
+		state.report_error(AudioWelderError::DeviceNotFound("Device unplugged.".to_string()), true);
+		assert!(mixer.is_closed());
+		state.await_close();
+		assert!(matches!(mixer.take_error(), Some(AudioWelderError::DeviceNotFound(_))));
+	}
+}
\ No newline at end of file
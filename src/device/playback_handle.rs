@@ -0,0 +1,259 @@
+use std::{ sync::{ Arc, Condvar, Mutex, MutexGuard }, time::Duration };
+use crate::{ AudioBuffer, AudioGenerator, AudioWelderError };
+use super::output_backend::OutputSource;
+
+
+
+type EndCallback = Box<dyn FnOnce() + Send>;
+
+
+
+pub(crate) struct PlaybackStatus {
+	pub buffer:AudioBuffer,
+	pub live:bool,
+	pub paused:bool,
+	pub stopped:bool,
+	pub finished:bool,
+	pub stream_open:bool,
+	pub underrun_count:usize,
+	pub end_callback:Option<EndCallback>,
+	pub error:Option<AudioWelderError>
+}
+
+
+
+pub(crate) struct PlaybackState {
+	status:Mutex<PlaybackStatus>,
+	status_change:Condvar
+}
+impl PlaybackState {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new playback state for the given buffer.
+	pub fn new(buffer:AudioBuffer) -> PlaybackState {
+		PlaybackState {
+			status: Mutex::new(PlaybackStatus {
+				buffer,
+				live: false,
+				paused: false,
+				stopped: false,
+				finished: false,
+				stream_open: false,
+				underrun_count: 0,
+				end_callback: None,
+				error: None
+			}),
+			status_change: Condvar::new()
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Lock the status of the playback.
+	pub fn status(&self) -> MutexGuard<'_, PlaybackStatus> {
+		self.status.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	/// Check if the playback has ended, either by finishing or by being stopped.
+	pub fn has_ended(&self) -> bool {
+		let status:MutexGuard<'_, PlaybackStatus> = self.status();
+		status.stopped || status.finished
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Modify the status and notify all threads waiting for a status change.
+	pub fn modify<T>(&self, modification:T) where T:FnOnce(&mut PlaybackStatus) {
+		modification(&mut self.status());
+		self.status_change.notify_all();
+	}
+
+	/// Block the current thread until the playback has ended.
+	pub fn await_end(&self) {
+		let mut status:MutexGuard<'_, PlaybackStatus> = self.status();
+		while !status.stopped && !status.finished {
+			status = self.status_change.wait(status).unwrap_or_else(|poisoned| poisoned.into_inner());
+		}
+	}
+
+	/// Block the current thread while the playback has nothing to play, because it is paused or its live source ran dry, until it does or it ended.
+	pub fn await_activity(&self) {
+		let mut status:MutexGuard<'_, PlaybackStatus> = self.status();
+		while !status.stopped && !status.finished && (status.paused || (status.live && status.buffer.available_len() == 0)) {
+			status = self.status_change.wait(status).unwrap_or_else(|poisoned| poisoned.into_inner());
+		}
+	}
+
+	/// Block the current thread until the playback has ended and its output stream is closed.
+	pub fn await_release(&self) {
+		let mut status:MutexGuard<'_, PlaybackStatus> = self.status();
+		while (!status.stopped && !status.finished) || status.stream_open {
+			status = self.status_change.wait(status).unwrap_or_else(|poisoned| poisoned.into_inner());
+		}
+	}
+
+	/// Store an error of the output stream. When the stream was lost, the playback is stopped.
+	pub fn report_error(&self, error:AudioWelderError, stream_lost:bool) {
+		self.modify(|status| {
+			status.error = Some(error);
+			if stream_lost {
+				status.stopped = true;
+			}
+		});
+	}
+
+	/// Fill the given interleaved output data with the next samples of the buffer. When the buffer cannot supply enough samples, the rest of the data is filled with silence. Returns the amount of frames played before the playback ended.
+	pub fn fill(&self, data:&mut [f32], channel_count:usize) -> usize {
+		let mut status:MutexGuard<'_, PlaybackStatus> = self.status();
+		let frame_count:usize = data.len() / channel_count;
+		if status.stopped || status.finished {
+			data.fill(0.0);
+			return 0;
+		}
+		if status.paused {
+			data.fill(0.0);
+			return frame_count;
+		}
+
+		// Take new data. Live sources only give the data they have, so they are not flushed with silence when they briefly run dry.
+		let live:bool = status.live;
+		let take_len:usize = if live { frame_count.min(status.buffer.available_len()) } else { frame_count };
+		let new_data:Vec<f32> = status.buffer.take_flat(take_len);
+		let new_data_len:usize = new_data.len().min(data.len());
+		data[..new_data_len].copy_from_slice(&new_data[..new_data_len]);
+		data[new_data_len..].fill(0.0);
+
+		// Keep track of underruns from the data that was available before padding. Running out of data at the end of a buffer that is not live is not an underrun.
+		let depleted:bool = !live && status.buffer.is_depleted();
+		if new_data_len < data.len() && !depleted {
+			status.underrun_count += 1;
+		}
+
+		// Mark finished when the buffer runs out.
+		if depleted {
+			status.finished = true;
+			let end_callback:Option<EndCallback> = status.end_callback.take();
+			drop(status);
+			self.status_change.notify_all();
+			if let Some(end_callback) = end_callback {
+				end_callback();
+			}
+			return new_data_len / channel_count;
+		}
+		frame_count
+	}
+}
+impl OutputSource for PlaybackState {
+	fn fill(&self, data:&mut [f32], channel_count:usize) -> usize {
+		PlaybackState::fill(self, data, channel_count)
+	}
+	fn has_ended(&self) -> bool {
+		PlaybackState::has_ended(self)
+	}
+	fn await_end(&self) {
+		PlaybackState::await_end(self);
+	}
+	fn await_activity(&self) {
+		PlaybackState::await_activity(self);
+	}
+	fn report_error(&self, error:AudioWelderError, stream_lost:bool) {
+		PlaybackState::report_error(self, error, stream_lost);
+	}
+	fn stream_opened(&self) {
+		self.modify(|status| status.stream_open = true);
+	}
+	fn stream_closed(&self) {
+		self.modify(|status| status.stream_open = false);
+	}
+}
+
+
+
+pub struct PlaybackHandle {
+	state:Arc<PlaybackState>
+}
+impl PlaybackHandle {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new handle controlling the given playback state.
+	pub(crate) fn new(state:Arc<PlaybackState>) -> PlaybackHandle {
+		PlaybackHandle {
+			state
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the position of the playback in the played audio.
+	pub fn position(&self) -> Duration {
+		self.state.status().buffer.position()
+	}
+
+	/// Check if the playback is paused.
+	pub fn is_paused(&self) -> bool {
+		self.state.status().paused
+	}
+
+	/// Check if the playback has ended, either by finishing or by being stopped.
+	pub fn is_finished(&self) -> bool {
+		self.state.has_ended()
+	}
+
+	/// Get the amount of times the buffer could not supply enough data to fill the output.
+	pub fn underrun_count(&self) -> usize {
+		self.state.status().underrun_count
+	}
+
+	/// Take the last error of the output stream, if any occurred since the last call. When the device was lost, the playback is stopped and this returns the reason.
+	pub fn take_error(&self) -> Option<AudioWelderError> {
+		self.state.status().error.take()
+	}
+
+
+
+	/* CONTROL METHODS */
+
+	/// Set a callback that is called once the end of the played audio is reached. Not called when the playback is stopped. When the end was already reached, the callback is called immediately. The callback runs on the audio thread, so it should return quickly.
+	pub fn on_end<T>(&self, callback:T) where T:FnOnce() + Send + 'static {
+		let mut status:MutexGuard<'_, PlaybackStatus> = self.state.status();
+		if status.finished {
+			drop(status);
+			callback();
+		} else {
+			status.end_callback = Some(Box::new(callback));
+		}
+	}
+
+	/// Pause the playback. The device outputs silence until the playback is resumed.
+	pub fn pause(&self) {
+		self.state.modify(|status| status.paused = true);
+	}
+
+	/// Resume the playback after pausing.
+	pub fn resume(&self) {
+		self.state.modify(|status| status.paused = false);
+	}
+
+	/// Stop the playback and release the output stream.
+	pub fn stop(&self) {
+		self.state.modify(|status| status.stopped = true);
+	}
+
+	/// Move the playback to the given position in the played audio.
+	pub fn seek(&self, position:Duration) {
+		self.state.modify(|status| status.buffer.seek(position));
+	}
+
+	/// Block the current thread until the playback has ended and the output stream is released.
+	pub fn wait(&self) {
+		self.state.await_release();
+	}
+}
\ No newline at end of file
@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioWelderError, PlaybackHandle };
+	use super::super::playback_handle::PlaybackState;
+	use std::{ sync::{ atomic::{ AtomicUsize, Ordering }, Arc }, thread, time::Duration };
+
+
+
+	fn create_playback(samples:Vec<f32>) -> (Arc<PlaybackState>, PlaybackHandle) {
+		let state:Arc<PlaybackState> = Arc::new(PlaybackState::new(AudioBuffer::new(vec![samples], 10)));
+		(state.clone(), PlaybackHandle::new(state))
+	}
+
+
+
+	#[test]
+	fn test_playback_fill() {
+		let saw_shape:Vec<f32> = (0..10).map(|index| index as f32 / 10.0).collect();
+		let (state, handle) = create_playback(saw_shape.clone());
+
+		let mut output:Vec<f32> = vec![0.0; 4];
+		state.fill(&mut output, 1);
+		assert_eq!(output, saw_shape[..4]);
+		assert_eq!(handle.position(), Duration::from_millis(400));
+		assert!(!handle.is_finished());
+	}
+
+	#[test]
+	fn test_playback_pause_resume() {
+		let saw_shape:Vec<f32> = (0..10).map(|index| index as f32 / 10.0).collect();
+		let (state, handle) = create_playback(saw_shape.clone());
+
+		let mut output:Vec<f32> = vec![1.0; 4];
+		handle.pause();
+		assert!(handle.is_paused());
+		state.fill(&mut output, 1);
+		assert_eq!(output, vec![0.0; 4]);
+		assert_eq!(handle.position(), Duration::ZERO);
+
+		handle.resume();
+		assert!(!handle.is_paused());
+		state.fill(&mut output, 1);
+		assert_eq!(output, saw_shape[..4]);
+	}
+
+	#[test]
+	fn test_playback_seek() {
+		let saw_shape:Vec<f32> = (0..10).map(|index| index as f32 / 10.0).collect();
+		let (state, handle) = create_playback(saw_shape.clone());
+
+		let mut output:Vec<f32> = vec![0.0; 2];
+		handle.seek(Duration::from_millis(500));
+		assert_eq!(handle.position(), Duration::from_millis(500));
+		state.fill(&mut output, 1);
+		assert_eq!(output, saw_shape[5..7]);
+
+		handle.seek(Duration::from_millis(100));
+		state.fill(&mut output, 1);
+		assert_eq!(output, saw_shape[1..3]);
+	}
+
+	#[test]
+	fn test_playback_finish_and_wait() {
+		let (state, handle) = create_playback(vec![0.5; 10]);
+
+		let fill_state:Arc<PlaybackState> = state.clone();
+		let filler:thread::JoinHandle<()> = thread::spawn(move || {
+			let mut output:Vec<f32> = vec![0.0; 2];
+			while !fill_state.has_ended() {
+				thread::sleep(Duration::from_millis(5));
+				fill_state.fill(&mut output, 1);
+			}
+		});
+		handle.wait();
+		assert!(handle.is_finished());
+		filler.join().unwrap();
+	}
+
+	#[test]
+	fn test_playback_stop() {
+		let (state, handle) = create_playback(vec![0.5; 10]);
+
+		handle.stop();
+		assert!(handle.is_finished());
+		handle.wait();
+
+		let mut output:Vec<f32> = vec![1.0; 2];
+		state.fill(&mut output, 1);
+		assert_eq!(output, vec![0.0; 2]);
+	}
+
+	#[test]
+	fn test_playback_errors() {
+		let (state, handle) = create_playback(vec![0.5; 10]);
+
+		// Recoverable errors are stored without ending the playback.
+		state.report_error(AudioWelderError::Stream("Buffer underrun.".to_string()), false);
+		assert!(!handle.is_finished());
+		assert!(matches!(handle.take_error(), Some(AudioWelderError::Stream(_))));
+		assert!(handle.take_error().is_none());
+
+		// Losing the stream ends the playback.
+		state.report_error(AudioWelderError::DeviceNotFound("Device unplugged.".to_string()), true);
+		assert!(handle.is_finished());
+		handle.wait();
+		assert!(matches!(handle.take_error(), Some(AudioWelderError::DeviceNotFound(_))));
+	}
+
+	#[test]
+	fn test_playback_fills_silence_at_end() {
+		let (state, handle) = create_playback(vec![0.5; 3]);
+
+		let mut output:Vec<f32> = vec![1.0; 5];
+		state.fill(&mut output, 1);
+		assert_eq!(output, vec![0.5, 0.5, 0.5, 0.0, 0.0]);
+		assert!(handle.is_finished());
+		assert_eq!(handle.underrun_count(), 0);
+	}
+
+	#[test]
+	fn test_playback_drained_buffer_finishes() {
+		let state:Arc<PlaybackState> = Arc::new(PlaybackState::new(AudioBuffer::new(vec![vec![0.5; 3]], 10).drain_progression()));
+		let handle:PlaybackHandle = PlaybackHandle::new(state.clone());
+
+		// A buffer that drains its data is not live, so it finishes at its end.
+		let mut output:Vec<f32> = vec![1.0; 5];
+		state.fill(&mut output, 1);
+		assert_eq!(output, vec![0.5, 0.5, 0.5, 0.0, 0.0]);
+		assert!(handle.is_finished());
+		assert_eq!(handle.underrun_count(), 0);
+	}
+
+	#[test]
+	fn test_playback_live_underrun() {
+		let state:Arc<PlaybackState> = Arc::new(PlaybackState::new(AudioBuffer::new(vec![vec![0.5; 3]], 10).drain_progression()));
+		let handle:PlaybackHandle = PlaybackHandle::new(state.clone());
+		state.modify(|status| status.live = true);
+
+		// A live source that runs dry counts an underrun and keeps playing.
+		let mut output:Vec<f32> = vec![1.0; 5];
+		state.fill(&mut output, 1);
+		assert_eq!(output, vec![0.5, 0.5, 0.5, 0.0, 0.0]);
+		assert_eq!(handle.underrun_count(), 1);
+		state.fill(&mut output, 1);
+		assert_eq!(output, vec![0.0; 5]);
+		assert_eq!(handle.underrun_count(), 2);
+		assert!(!handle.is_finished());
+
+		// New data is played when it arrives.
+		state.modify(|status| status.buffer = AudioBuffer::new(vec![vec![0.25; 5]], 10).drain_progression());
+		state.fill(&mut output, 1);
+		assert_eq!(output, vec![0.25; 5]);
+		assert_eq!(handle.underrun_count(), 2);
+		assert!(!handle.is_finished());
+	}
+
+	#[test]
+	fn test_playback_end_callback() {
+		let end_count:Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+
+		// Callback fires once when the end is reached.
+		let (state, handle) = create_playback(vec![0.5; 4]);
+		let callback_end_count:Arc<AtomicUsize> = end_count.clone();
+		handle.on_end(move || { callback_end_count.fetch_add(1, Ordering::SeqCst); });
+		let mut output:Vec<f32> = vec![0.0; 2];
+		state.fill(&mut output, 1);
+		assert_eq!(end_count.load(Ordering::SeqCst), 0);
+		state.fill(&mut output, 1);
+		state.fill(&mut output, 1);
+		assert_eq!(end_count.load(Ordering::SeqCst), 1);
+
+		// Callback fires immediately when the end was already reached.
+		let callback_end_count:Arc<AtomicUsize> = end_count.clone();
+		handle.on_end(move || { callback_end_count.fetch_add(1, Ordering::SeqCst); });
+		assert_eq!(end_count.load(Ordering::SeqCst), 2);
+
+		// Callback does not fire when stopped.
+		let (state, handle) = create_playback(vec![0.5; 4]);
+		let callback_end_count:Arc<AtomicUsize> = end_count.clone();
+		handle.on_end(move || { callback_end_count.fetch_add(1, Ordering::SeqCst); });
+		handle.stop();
+		state.fill(&mut output, 1);
+		state.fill(&mut output, 1);
+		assert_eq!(end_count.load(Ordering::SeqCst), 2);
+	}
+}
\ No newline at end of file
@@ -0,0 +1,567 @@
+use std::{ f32::consts::FRAC_PI_2, sync::{ Arc, Condvar, Mutex, MutexGuard }, time::{ Duration, SystemTime, UNIX_EPOCH } };
+use crate::{ AudioBuffer, AudioBufferDataLength, AudioWelderError };
+use super::output_backend::OutputSource;
+
+
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RepeatMode { Off, One, All }
+
+
+
+#[derive(Clone)]
+enum PlaylistItem { Buffer(AudioBuffer), Wav(String) }
+impl PlaylistItem {
+
+	/// Load the item, resampled to the given sample rate and channel count with all effects applied.
+	fn load(self, sample_rate:u32, channel_count:usize) -> Result<Vec<Vec<f32>>, AudioWelderError> {
+		let buffer:AudioBuffer = match self {
+			PlaylistItem::Buffer(buffer) => buffer,
+			PlaylistItem::Wav(file_path) => AudioBuffer::wav(&file_path)?
+		};
+		let mut buffer:AudioBuffer = buffer.resampled(sample_rate, channel_count);
+		buffer.apply_effects();
+		Ok(buffer.raw_data().clone())
+	}
+}
+
+
+
+struct LoadedItem {
+	position:usize,
+	item_index:usize,
+	data:Vec<Vec<f32>>,
+	frame:usize
+}
+impl LoadedItem {
+
+	/// Get the amount of frames left to play.
+	fn remaining(&self) -> usize {
+		self.data.first().map(|channel| channel.len()).unwrap_or(0).saturating_sub(self.frame)
+	}
+
+	/// Add the next frame of the item to the given output frame.
+	fn add_frame_to(&mut self, output_frame:&mut [f32], gain:f32) {
+		if self.remaining() == 0 {
+			return;
+		}
+		for (output_sample, channel) in output_frame.iter_mut().zip(&self.data) {
+			*output_sample += channel[self.frame] * gain;
+		}
+		self.frame += 1;
+	}
+}
+
+
+
+struct Crossfade {
+	outgoing:LoadedItem,
+	progress:usize,
+	length:usize
+}
+
+
+
+struct PlaylistStatus {
+	items:Vec<PlaylistItem>,
+	failed_items:Vec<usize>,
+	order:Vec<usize>,
+	position:Option<usize>,
+	current:Option<LoadedItem>,
+	preloaded:Option<LoadedItem>,
+	crossfade:Option<Crossfade>,
+	crossfade_duration:Duration,
+	repeat:RepeatMode,
+	shuffle:bool,
+	random_state:u32,
+	paused:bool,
+	finished:bool,
+	closed:bool,
+	error:Option<AudioWelderError>
+}
+impl PlaylistStatus {
+
+	/* POSITION METHODS */
+
+	/// Get the position in the play order after the given position, skipping items that failed to load. Automatic advances repeat the same item when repeating one.
+	fn next_position(&self, position:usize, automatic:bool) -> Option<usize> {
+		let mut next_position:usize = position;
+		for _ in 0..self.order.len() {
+			next_position = self.next_order_position(next_position, automatic)?;
+			if !self.failed_items.contains(&self.order[next_position]) {
+				return Some(next_position);
+			}
+		}
+		None
+	}
+
+	/// Get the position in the play order directly after the given position.
+	fn next_order_position(&self, position:usize, automatic:bool) -> Option<usize> {
+		if self.order.is_empty() {
+			None
+		} else if automatic && self.repeat == RepeatMode::One {
+			Some(position)
+		} else if position + 1 < self.order.len() {
+			Some(position + 1)
+		} else if self.repeat != RepeatMode::Off {
+			Some(0)
+		} else {
+			None
+		}
+	}
+
+	/// Get the position in the play order before the given position.
+	fn previous_position(&self, position:usize) -> usize {
+		if position > 0 {
+			position - 1
+		} else if self.repeat != RepeatMode::Off {
+			self.order.len().saturating_sub(1)
+		} else {
+			0
+		}
+	}
+
+	/// Move to the given position in the play order. Uses the preloaded item if it matches. Moving to no position finishes the playlist.
+	fn move_to(&mut self, position:Option<usize>) {
+		self.crossfade = None;
+		self.position = position;
+		self.current = match (position, self.preloaded.take()) {
+			(Some(position), Some(preloaded)) if preloaded.position == position && preloaded.item_index == self.order[position] => Some(preloaded),
+			_ => None
+		};
+		self.finished = position.is_none();
+	}
+
+	/// Get the position in the play order and item index of the item that needs to be loaded next, if any.
+	fn pending_load(&self) -> Option<(usize, usize)> {
+		if self.closed {
+			return None;
+		}
+		let position:usize = self.position?;
+		if self.current.is_none() {
+			return Some((position, self.order[position]));
+		}
+		let next_position:usize = self.next_position(position, true)?;
+		match &self.preloaded {
+			Some(preloaded) if preloaded.position == next_position && preloaded.item_index == self.order[next_position] => None,
+			_ => Some((next_position, self.order[next_position]))
+		}
+	}
+
+
+
+	/* ORDER METHODS */
+
+	/// Get a random number up to the given maximum.
+	fn random_index(&mut self, max:usize) -> usize {
+		self.random_state ^= self.random_state << 13;
+		self.random_state ^= self.random_state >> 17;
+		self.random_state ^= self.random_state << 5;
+		self.random_state as usize % max.max(1)
+	}
+
+	/// Recreate the play order, keeping the current item playing.
+	fn reorder(&mut self) {
+		let current_item:Option<usize> = self.position.map(|position| self.order[position]);
+		self.order = (0..self.items.len()).collect();
+		if self.shuffle {
+			for index in (1..self.order.len()).rev() {
+				let swap_index:usize = self.random_index(index + 1);
+				self.order.swap(index, swap_index);
+			}
+			if let Some(current_item) = current_item {
+				self.order.retain(|item_index| *item_index != current_item);
+				self.order.insert(0, current_item);
+			}
+		}
+
+		// Update positions.
+		self.position = current_item.and_then(|current_item| self.order.iter().position(|item_index| *item_index == current_item));
+		if let (Some(current), Some(position)) = (&mut self.current, self.position) {
+			current.position = position;
+		}
+		self.preloaded = None;
+	}
+
+
+
+	/* PLAYING METHODS */
+
+	/// Fill the given output frame with the next frame of the playlist.
+	fn fill_frame(&mut self, output_frame:&mut [f32], crossfade_length:usize) {
+		if self.position.is_none() {
+			return;
+		}
+
+		// Skip items without data.
+		self.skip_ended_item();
+
+		// Start crossfading into the next item once it is preloaded. The crossfade ends before the incoming item does, so the incoming item cannot end mid-fade.
+		if self.crossfade.is_none() && crossfade_length > 0 {
+			let remaining:usize = self.current.as_ref().map(|current| current.remaining()).unwrap_or(0);
+			let next_position:Option<usize> = self.position.and_then(|position| self.next_position(position, true));
+			let next_remaining:usize = match (&self.preloaded, next_position) {
+				(Some(preloaded), Some(next_position)) if preloaded.position == next_position => preloaded.remaining(),
+				_ => 0
+			};
+			if remaining > 0 && remaining <= crossfade_length && next_remaining > 0 {
+				let outgoing:LoadedItem = self.current.take().unwrap();
+				self.move_to(next_position);
+				self.crossfade = Some(Crossfade { length: outgoing.remaining().min(next_remaining), outgoing, progress: 0 });
+			}
+		}
+
+		// Mix the current item with the item fading out, using equal-power gains. The outgoing item reaches silence on the last frame of the crossfade.
+		let fade_angle:Option<f32> = self.crossfade.as_ref().map(|crossfade| (crossfade.progress + 1) as f32 / crossfade.length as f32 * FRAC_PI_2);
+		if let Some(current) = &mut self.current {
+			current.add_frame_to(output_frame, fade_angle.map(|angle| angle.sin()).unwrap_or(1.0));
+		}
+		if let (Some(crossfade), Some(fade_angle)) = (&mut self.crossfade, fade_angle) {
+			crossfade.outgoing.add_frame_to(output_frame, fade_angle.cos());
+			crossfade.progress += 1;
+			if crossfade.progress >= crossfade.length {
+				self.crossfade = None;
+			}
+		}
+
+		// Move to the next item once the current item ends.
+		self.skip_ended_item();
+	}
+
+	/// Move to the next item if the current item has no more frames to play.
+	fn skip_ended_item(&mut self) {
+		if self.current.as_ref().is_some_and(|current| current.remaining() == 0) {
+			let next_position:Option<usize> = self.position.and_then(|position| self.next_position(position, true));
+			self.move_to(next_position);
+		}
+	}
+}
+
+
+
+pub(crate) struct PlaylistState {
+	sample_rate:u32,
+	channel_count:usize,
+	status:Mutex<PlaylistStatus>,
+	status_change:Condvar
+}
+impl PlaylistState {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new empty playlist state for the given output properties.
+	pub fn new(sample_rate:u32, channel_count:usize) -> PlaylistState {
+		let random_seed:u32 = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.subsec_nanos()).unwrap_or_default() | 1;
+		PlaylistState {
+			sample_rate,
+			channel_count,
+			status: Mutex::new(PlaylistStatus {
+				items: Vec::new(),
+				failed_items: Vec::new(),
+				order: Vec::new(),
+				position: None,
+				current: None,
+				preloaded: None,
+				crossfade: None,
+				crossfade_duration: Duration::ZERO,
+				repeat: RepeatMode::Off,
+				shuffle: false,
+				random_state: random_seed,
+				paused: false,
+				finished: false,
+				closed: false,
+				error: None
+			}),
+			status_change: Condvar::new()
+		}
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Lock the status of the playlist.
+	fn status(&self) -> MutexGuard<'_, PlaylistStatus> {
+		self.status.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	/// Modify the status and notify all threads waiting for a status change.
+	fn modify<T, U>(&self, modification:T) -> U where T:FnOnce(&mut PlaylistStatus) -> U {
+		let result:U = modification(&mut self.status());
+		self.status_change.notify_all();
+		result
+	}
+
+	/// Block the current thread until the status matches the given condition.
+	fn await_status<T>(&self, condition:T) where T:Fn(&PlaylistStatus) -> bool {
+		let mut status:MutexGuard<'_, PlaylistStatus> = self.status();
+		while !condition(&status) {
+			status = self.status_change.wait(status).unwrap_or_else(|poisoned| poisoned.into_inner());
+		}
+	}
+
+	/// Close the playlist, which releases the output stream.
+	pub fn close(&self) {
+		self.modify(|status| status.closed = true);
+	}
+
+	/// Load the item that is needed next, resampled to the output. Failing items are stored empty and marked as failed, so they are skipped from then on. Returns false if no item needed loading.
+	pub fn load_pending(&self) -> bool {
+		let (position, item_index, item):(usize, usize, PlaylistItem) = {
+			let status:MutexGuard<'_, PlaylistStatus> = self.status();
+			match status.pending_load() {
+				Some((position, item_index)) => (position, item_index, status.items[item_index].clone()),
+				None => return false
+			}
+		};
+		let loaded:Result<Vec<Vec<f32>>, AudioWelderError> = item.load(self.sample_rate, self.channel_count);
+
+		// Store the loaded item if it is still needed.
+		self.modify(|status| {
+			let data:Vec<Vec<f32>> = loaded.unwrap_or_else(|error| {
+				status.error = Some(error);
+				if !status.failed_items.contains(&item_index) {
+					status.failed_items.push(item_index);
+				}
+				Vec::new()
+			});
+			if status.pending_load() == Some((position, item_index)) {
+				let loaded_item:LoadedItem = LoadedItem { position, item_index, data, frame: 0 };
+				if status.position == Some(position) && status.current.is_none() {
+					status.current = Some(loaded_item);
+				} else {
+					status.preloaded = Some(loaded_item);
+				}
+			}
+		});
+		true
+	}
+
+	/// Keep loading items as they are needed until the playlist is closed.
+	pub fn run_loader(&self) {
+		loop {
+			self.await_status(|status| status.closed || status.pending_load().is_some());
+			if self.status().closed {
+				return;
+			}
+			self.load_pending();
+		}
+	}
+
+	/// Fill the given interleaved output data with the next frames of the playlist.
+	pub fn fill(&self, data:&mut [f32]) {
+		let mut status:MutexGuard<'_, PlaylistStatus> = self.status();
+		data.fill(0.0);
+		if status.paused || status.closed || self.channel_count == 0 {
+			return;
+		}
+
+		// Fill frames and notify the loader and waiting threads when the item changed.
+		let crossfade_length:usize = status.crossfade_duration.as_buffer_length(self.sample_rate);
+		let initial_position:Option<usize> = status.position;
+		let initial_item_loaded:bool = status.current.is_some();
+		for output_frame in data.chunks_mut(self.channel_count) {
+			status.fill_frame(output_frame, crossfade_length);
+		}
+		if status.position != initial_position || status.current.is_some() != initial_item_loaded {
+			drop(status);
+			self.status_change.notify_all();
+		}
+	}
+
+	/// Store an error of the output stream. When the stream was lost, the playlist is closed.
+	pub fn report_error(&self, error:AudioWelderError, stream_lost:bool) {
+		self.modify(|status| {
+			status.error = Some(error);
+			if stream_lost {
+				status.closed = true;
+			}
+		});
+	}
+}
+impl OutputSource for PlaylistState {
+	fn fill(&self, data:&mut [f32], channel_count:usize) -> usize {
+		PlaylistState::fill(self, data);
+		data.len() / channel_count.max(1)
+	}
+	fn has_ended(&self) -> bool {
+		self.status().closed
+	}
+	fn await_end(&self) {
+		self.await_status(|status| status.closed);
+	}
+	fn await_activity(&self) {
+		self.await_status(|status| status.closed || (!status.paused && status.current.is_some()));
+	}
+	fn report_error(&self, error:AudioWelderError, stream_lost:bool) {
+		PlaylistState::report_error(self, error, stream_lost);
+	}
+}
+
+
+
+pub struct Playlist {
+	state:Arc<PlaylistState>
+}
+impl Playlist {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new playlist controlling the given playlist state.
+	pub(crate) fn new(state:Arc<PlaylistState>) -> Playlist {
+		Playlist {
+			state
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the amount of items in the playlist.
+	pub fn len(&self) -> usize {
+		self.state.status().items.len()
+	}
+
+	/// Check if the playlist has no items.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Get the index of the item currently playing, in the order the items were added.
+	pub fn current_index(&self) -> Option<usize> {
+		let status:MutexGuard<'_, PlaylistStatus> = self.state.status();
+		status.position.map(|position| status.order[position])
+	}
+
+	/// Get the position of the playback in the current item.
+	pub fn position(&self) -> Duration {
+		let frame:usize = self.state.status().current.as_ref().map(|current| current.frame).unwrap_or(0);
+		Duration::from_nanos(frame as u64 * 1_000_000_000 / self.state.sample_rate.max(1) as u64)
+	}
+
+	/// Check if the playlist is paused.
+	pub fn is_paused(&self) -> bool {
+		self.state.status().paused
+	}
+
+	/// Check if the playlist reached its end. Never happens when repeating.
+	pub fn is_finished(&self) -> bool {
+		self.state.status().finished
+	}
+
+	/// Take the last error that occurred while loading items or playing the output stream, if any occurred since the last call.
+	pub fn take_error(&self) -> Option<AudioWelderError> {
+		self.state.status().error.take()
+	}
+
+
+
+	/* ITEM METHODS */
+
+	/// Add an audio buffer to the end of the playlist. Returns the index of the item. When shuffling, the item is placed at a random position after the current item.
+	pub fn add_buffer(&self, buffer:AudioBuffer) -> usize {
+		self.add_item(PlaylistItem::Buffer(buffer))
+	}
+
+	/// Add a wav file to the end of the playlist. The file is loaded shortly before it is played. Returns the index of the item. When shuffling, the item is placed at a random position after the current item.
+	pub fn add_wav(&self, file_path:&str) -> usize {
+		self.add_item(PlaylistItem::Wav(file_path.to_string()))
+	}
+
+	/// Add an item to the playlist.
+	fn add_item(&self, item:PlaylistItem) -> usize {
+		self.state.modify(|status| {
+			let item_index:usize = status.items.len();
+			status.items.push(item);
+			let first_free_position:usize = status.position.map(|position| position + 1).unwrap_or(0);
+			let insert_position:usize = if status.shuffle { first_free_position + status.random_index(status.order.len() - first_free_position + 1) } else { status.order.len() };
+			status.order.insert(insert_position, item_index);
+
+			// Start playing when this is the first item.
+			if status.position.is_none() && !status.finished {
+				status.move_to(Some(insert_position));
+			}
+
+			// Keep preloaded item in sync with the order.
+			if let Some(preloaded) = &status.preloaded {
+				if status.order.get(preloaded.position) != Some(&preloaded.item_index) {
+					status.preloaded = None;
+				}
+			}
+			item_index
+		})
+	}
+
+
+
+	/* CONTROL METHODS */
+
+	/// Set the duration of the equal-power crossfade between items. A duration of zero plays items back to back without a gap. Defaults to zero.
+	pub fn set_crossfade(&self, duration:Duration) {
+		self.state.modify(|status| status.crossfade_duration = duration);
+	}
+
+	/// Set the repeat mode. Defaults to `RepeatMode::Off`.
+	pub fn set_repeat(&self, repeat:RepeatMode) {
+		self.state.modify(|status| status.repeat = repeat);
+	}
+
+	/// Enable or disable shuffle. Enabling shuffle puts the items in a random order after the current item.
+	pub fn set_shuffle(&self, shuffle:bool) {
+		self.state.modify(|status| {
+			status.shuffle = shuffle;
+			status.reorder();
+		});
+	}
+
+	/// Skip to the next item. When at the last item and not repeating, the playlist finishes.
+	pub fn next(&self) {
+		self.state.modify(|status| {
+			let next_position:Option<usize> = match status.position {
+				Some(position) => status.next_position(position, false),
+				None => None
+			};
+			status.move_to(next_position);
+		});
+	}
+
+	/// Go back to the previous item. When at the first item and not repeating, the first item restarts.
+	pub fn previous(&self) {
+		self.state.modify(|status| {
+			if let Some(position) = status.position {
+				let previous_position:usize = status.previous_position(position);
+				status.move_to(Some(previous_position));
+			}
+		});
+	}
+
+	/// Start playing the item with the given index, in the order the items were added. Also restarts a finished playlist.
+	pub fn play_index(&self, item_index:usize) {
+		self.state.modify(|status| {
+			if let Some(position) = status.order.iter().position(|order_item_index| *order_item_index == item_index) {
+				status.move_to(Some(position));
+			}
+		});
+	}
+
+	/// Pause the playlist. The device outputs silence until the playlist is resumed.
+	pub fn pause(&self) {
+		self.state.modify(|status| status.paused = true);
+	}
+
+	/// Resume the playlist after pausing.
+	pub fn resume(&self) {
+		self.state.modify(|status| status.paused = false);
+	}
+
+	/// Block the current thread until the playlist reached its end or its output stream was lost.
+	pub fn wait(&self) {
+		self.state.await_status(|status| status.finished || status.closed);
+	}
+}
+impl Drop for Playlist {
+	fn drop(&mut self) {
+		self.state.close();
+	}
+}
\ No newline at end of file
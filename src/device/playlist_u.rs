@@ -0,0 +1,193 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioWelderError, NullSink, OutputDevice, Playlist, RepeatMode };
+	use super::super::playlist::PlaylistState;
+	use std::{ f32::consts::FRAC_1_SQRT_2, sync::Arc, time::Duration };
+
+
+
+	fn create_playlist(items:&[&[f32]]) -> (Arc<PlaylistState>, Playlist) {
+		let state:Arc<PlaylistState> = Arc::new(PlaylistState::new(10, 1));
+		let playlist:Playlist = Playlist::new(state.clone());
+		for item in items {
+			playlist.add_buffer(AudioBuffer::new(vec![item.to_vec()], 10));
+		}
+		(state, playlist)
+	}
+
+	fn fill(state:&PlaylistState, sample_count:usize) -> Vec<f32> {
+		(0..sample_count).map(|_| {
+			while state.load_pending() {}
+			let mut output:Vec<f32> = vec![1.0];
+			state.fill(&mut output);
+			output[0]
+		}).collect()
+	}
+
+	fn assert_close(actual:&[f32], expected:&[f32]) {
+		assert_eq!(actual.len(), expected.len());
+		for (actual, expected) in actual.iter().zip(expected) {
+			assert!((actual - expected).abs() < 0.0001, "{actual:?} != {expected:?}");
+		}
+	}
+
+
+
+	#[test]
+	fn test_playlist_gapless() {
+		let (state, playlist) = create_playlist(&[&[0.1, 0.1, 0.1], &[0.2, 0.2], &[0.3]]);
+		assert_eq!(playlist.len(), 3);
+		assert_eq!(playlist.current_index(), Some(0));
+
+		assert_eq!(fill(&state, 4), vec![0.1, 0.1, 0.1, 0.2]);
+		assert_eq!(playlist.current_index(), Some(1));
+		assert_eq!(fill(&state, 4), vec![0.2, 0.3, 0.0, 0.0]);
+		assert!(playlist.is_finished());
+		assert_eq!(playlist.current_index(), None);
+	}
+
+	#[test]
+	fn test_playlist_crossfade() {
+		let (state, playlist) = create_playlist(&[&[1.0; 6], &[1.0; 6]]);
+		playlist.set_crossfade(Duration::from_millis(400));
+
+		// Outgoing item fades out with cosine gain, incoming item fades in with sine gain.
+		let output:Vec<f32> = fill(&state, 12);
+		let quarter:f32 = (std::f32::consts::FRAC_PI_2 / 4.0).cos() + (std::f32::consts::FRAC_PI_2 / 4.0).sin();
+		let three_quarters:f32 = (std::f32::consts::FRAC_PI_2 * 0.75).cos() + (std::f32::consts::FRAC_PI_2 * 0.75).sin();
+		assert_close(&output, &[1.0, 1.0, quarter, FRAC_1_SQRT_2 * 2.0, three_quarters, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+		assert!(playlist.is_finished());
+	}
+
+	#[test]
+	fn test_playlist_crossfade_short_item() {
+		let (state, playlist) = create_playlist(&[&[1.0; 6], &[0.5; 2], &[0.25; 3]]);
+		playlist.set_crossfade(Duration::from_millis(400));
+
+		// The crossfade into an item shorter than the crossfade ends with the item, fading the outgoing item out completely.
+		let output:Vec<f32> = fill(&state, 10);
+		assert_close(&output, &[1.0, 1.0, FRAC_1_SQRT_2 * 1.5, 0.5, 0.25, 0.25, 0.25, 0.0, 0.0, 0.0]);
+		assert!(playlist.is_finished());
+	}
+
+	#[test]
+	fn test_playlist_next_previous() {
+		let (state, playlist) = create_playlist(&[&[0.1; 5], &[0.2; 5], &[0.3; 5]]);
+
+		playlist.next();
+		assert_eq!(playlist.current_index(), Some(1));
+		assert_eq!(fill(&state, 2), vec![0.2, 0.2]);
+		assert_eq!(playlist.position(), Duration::from_millis(200));
+
+		playlist.previous();
+		assert_eq!(playlist.current_index(), Some(0));
+		assert_eq!(fill(&state, 1), vec![0.1]);
+
+		// Previous on the first item restarts it.
+		playlist.previous();
+		assert_eq!(playlist.current_index(), Some(0));
+
+		// Next on the last item finishes.
+		playlist.play_index(2);
+		assert_eq!(fill(&state, 1), vec![0.3]);
+		playlist.next();
+		assert!(playlist.is_finished());
+		playlist.wait();
+
+		// Finished playlist can be restarted.
+		playlist.play_index(1);
+		assert!(!playlist.is_finished());
+		assert_eq!(fill(&state, 1), vec![0.2]);
+	}
+
+	#[test]
+	fn test_playlist_repeat() {
+		let (state, playlist) = create_playlist(&[&[0.1, 0.1], &[0.2]]);
+		playlist.set_repeat(RepeatMode::One);
+		assert_eq!(fill(&state, 5), vec![0.1, 0.1, 0.1, 0.1, 0.1]);
+
+		// Skipping still moves to the next item.
+		playlist.next();
+		assert_eq!(fill(&state, 3), vec![0.2, 0.2, 0.2]);
+
+		playlist.set_repeat(RepeatMode::All);
+		assert_eq!(fill(&state, 4), vec![0.2, 0.1, 0.1, 0.2]);
+		assert_eq!(playlist.current_index(), Some(0));
+		playlist.next();
+		playlist.next();
+		assert_eq!(playlist.current_index(), Some(0));
+		playlist.previous();
+		assert_eq!(playlist.current_index(), Some(1));
+		assert!(!playlist.is_finished());
+	}
+
+	#[test]
+	fn test_playlist_shuffle() {
+		let items:Vec<Vec<f32>> = (0..20).map(|index| vec![index as f32]).collect();
+		let item_refs:Vec<&[f32]> = items.iter().map(|item| item.as_slice()).collect();
+		let (state, playlist) = create_playlist(&item_refs);
+		assert_eq!(fill(&state, 3), vec![0.0, 1.0, 2.0]);
+
+		// Current item keeps playing, followed by all other items in random order.
+		playlist.set_shuffle(true);
+		assert_eq!(playlist.current_index(), Some(3));
+		let mut played:Vec<f32> = fill(&state, 20);
+		assert_eq!(played[0], 3.0);
+		assert_ne!(played[1..], (0..20).filter(|index| *index != 3).map(|index| index as f32).collect::<Vec<f32>>());
+		played.sort_by(|left, right| left.partial_cmp(right).unwrap());
+		assert_eq!(played, (0..20).map(|index| index as f32).collect::<Vec<f32>>());
+		assert!(playlist.is_finished());
+	}
+
+	#[test]
+	fn test_playlist_resamples_items() {
+		let (state, playlist) = create_playlist(&[]);
+		playlist.add_buffer(AudioBuffer::new(vec![vec![0.5; 20], vec![0.5; 20]], 20));
+		let played:Vec<f32> = fill(&state, 12);
+		assert!(played[4..8].iter().all(|sample| (sample - 0.5).abs() < 0.01));
+		assert!(played[..10].iter().all(|sample| *sample != 0.0));
+		assert_eq!(played[10..], [0.0; 2]);
+	}
+
+	#[test]
+	fn test_playlist_skips_failing_items() {
+		let (state, playlist) = create_playlist(&[]);
+		playlist.add_wav("audio_welder_nonexistent_file.wav");
+		playlist.add_buffer(AudioBuffer::new(vec![vec![0.5; 2]], 10));
+		let output:Vec<f32> = fill(&state, 4);
+		assert_eq!(output[..2], [0.5, 0.5]);
+		assert!(matches!(playlist.take_error(), Some(AudioWelderError::Io(_))));
+	}
+
+	#[test]
+	fn test_playlist_repeat_failing_items() {
+		let (state, playlist) = create_playlist(&[]);
+		playlist.add_wav("audio_welder_nonexistent_file.wav");
+		playlist.add_buffer(AudioBuffer::new(vec![vec![0.5; 2]], 10));
+		playlist.set_repeat(RepeatMode::All);
+
+		// Failed items are not loaded again when repeating.
+		assert_eq!(fill(&state, 6), vec![0.5; 6]);
+		assert!(playlist.take_error().is_some());
+		assert!(playlist.take_error().is_none());
+
+		// A playlist of only failing items finishes instead of retrying forever.
+		let (state, playlist) = create_playlist(&[]);
+		playlist.add_wav("audio_welder_nonexistent_file.wav");
+		playlist.add_wav("audio_welder_other_nonexistent_file.wav");
+		playlist.set_repeat(RepeatMode::All);
+		assert_eq!(fill(&state, 3), vec![0.0; 3]);
+		assert!(playlist.is_finished());
+	}
+
+	#[test]
+	fn test_playlist_on_device() {
+		let device:OutputDevice = OutputDevice::from_backend(NullSink::new(1000, 2).real_time(false));
+		let playlist:Playlist = device.playlist().unwrap();
+		playlist.add_buffer(AudioBuffer::new(vec![vec![0.5; 100]], 1000));
+		playlist.add_buffer(AudioBuffer::new(vec![vec![0.5; 200]], 500));
+		playlist.wait();
+		assert!(playlist.is_finished());
+		assert!(playlist.take_error().is_none());
+	}
+}
\ No newline at end of file
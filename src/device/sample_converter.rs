@@ -0,0 +1,146 @@
+use cpal::{ traits::DeviceTrait, BuildStreamError, Device as CpalDevice, FromSample, InputCallbackInfo, OutputCallbackInfo, SampleFormat, SizedSample, Stream, StreamConfig, StreamError };
+
+
+
+pub(crate) struct SampleConverter {
+	sample_format:SampleFormat,
+	dither:bool,
+	noise_state:u32
+}
+impl SampleConverter {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new converter from f32 samples to the given sample format. When dithering is enabled, TPDF dither is added before reducing the precision of the samples.
+	pub fn new(sample_format:SampleFormat, dither:bool) -> SampleConverter {
+		SampleConverter {
+			sample_format,
+			dither,
+			noise_state: 0x9E3779B9
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the scale of the sample format when converting to it reduces the precision of f32 samples. Returns None for formats precise enough to hold any f32 sample.
+	fn quantization_scale(&self) -> Option<f32> {
+		match self.sample_format {
+			SampleFormat::I8 | SampleFormat::U8 => Some(128.0),
+			SampleFormat::I16 | SampleFormat::U16 => Some(32768.0),
+			_ => None
+		}
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Convert the given f32 samples to the target samples.
+	pub fn convert<T>(&mut self, source:&[f32], target:&mut [T]) where T:SizedSample + FromSample<f32> {
+		match self.quantization_scale() {
+			Some(scale) => {
+				for (source_sample, target_sample) in source.iter().zip(target.iter_mut()) {
+					let dither:f32 = if self.dither { self.next_noise() + self.next_noise() - 1.0 } else { 0.0 };
+					let quantized:f32 = ((source_sample * scale + dither).round() / scale).clamp(-1.0, (scale - 1.0) / scale);
+					*target_sample = T::from_sample(quantized);
+				}
+			},
+			None => {
+				for (source_sample, target_sample) in source.iter().zip(target.iter_mut()) {
+					*target_sample = T::from_sample(source_sample.clamp(-1.0, 1.0));
+				}
+			}
+		}
+	}
+
+	/// Convert the given samples of any sample format to f32 samples, replacing the contents of the target.
+	pub fn convert_to_f32<T>(source:&[T], target:&mut Vec<f32>) where T:SizedSample, f32:FromSample<T> {
+		target.clear();
+		target.extend(source.iter().map(|sample| sample.to_sample::<f32>()));
+	}
+
+	/// Get the next value of the noise generator, ranging from 0.0 to 1.0.
+	fn next_noise(&mut self) -> f32 {
+		self.noise_state ^= self.noise_state << 13;
+		self.noise_state ^= self.noise_state >> 17;
+		self.noise_state ^= self.noise_state << 5;
+		(self.noise_state >> 8) as f32 / (1u32 << 24) as f32
+	}
+
+
+
+	/* STREAM METHODS */
+
+	/// Build an output stream in the given sample format. The data callback always fills f32 samples, which are converted to the sample format of the stream.
+	pub fn build_output_stream<T, U>(cpal_device:&CpalDevice, stream_config:&StreamConfig, sample_format:SampleFormat, dither:bool, data_callback:T, error_callback:U) -> Result<Stream, BuildStreamError> where T:FnMut(&mut [f32], &OutputCallbackInfo) + Send + 'static, U:FnMut(StreamError) + Send + 'static {
+		match sample_format {
+			SampleFormat::F32 => {
+				let mut data_callback:T = data_callback;
+				cpal_device.build_output_stream(stream_config, move |data:&mut [f32], info:&OutputCallbackInfo| data_callback(data, info), error_callback, None)
+			},
+			SampleFormat::I8 => SampleConverter::build_converted_output_stream::<i8, T, U>(cpal_device, stream_config, sample_format, dither, data_callback, error_callback),
+			SampleFormat::I16 => SampleConverter::build_converted_output_stream::<i16, T, U>(cpal_device, stream_config, sample_format, dither, data_callback, error_callback),
+			SampleFormat::I32 => SampleConverter::build_converted_output_stream::<i32, T, U>(cpal_device, stream_config, sample_format, dither, data_callback, error_callback),
+			SampleFormat::I64 => SampleConverter::build_converted_output_stream::<i64, T, U>(cpal_device, stream_config, sample_format, dither, data_callback, error_callback),
+			SampleFormat::U8 => SampleConverter::build_converted_output_stream::<u8, T, U>(cpal_device, stream_config, sample_format, dither, data_callback, error_callback),
+			SampleFormat::U16 => SampleConverter::build_converted_output_stream::<u16, T, U>(cpal_device, stream_config, sample_format, dither, data_callback, error_callback),
+			SampleFormat::U32 => SampleConverter::build_converted_output_stream::<u32, T, U>(cpal_device, stream_config, sample_format, dither, data_callback, error_callback),
+			SampleFormat::U64 => SampleConverter::build_converted_output_stream::<u64, T, U>(cpal_device, stream_config, sample_format, dither, data_callback, error_callback),
+			SampleFormat::F64 => SampleConverter::build_converted_output_stream::<f64, T, U>(cpal_device, stream_config, sample_format, dither, data_callback, error_callback),
+			_ => Err(BuildStreamError::StreamConfigNotSupported)
+		}
+	}
+
+	/// Build an output stream that converts the f32 samples of the data callback to samples of type `S`.
+	fn build_converted_output_stream<S, T, U>(cpal_device:&CpalDevice, stream_config:&StreamConfig, sample_format:SampleFormat, dither:bool, mut data_callback:T, error_callback:U) -> Result<Stream, BuildStreamError> where S:SizedSample + FromSample<f32>, T:FnMut(&mut [f32], &OutputCallbackInfo) + Send + 'static, U:FnMut(StreamError) + Send + 'static {
+		let mut converter:SampleConverter = SampleConverter::new(sample_format, dither);
+		let mut source_data:Vec<f32> = Vec::new();
+		cpal_device.build_output_stream(
+			stream_config,
+			move |data:&mut [S], info:&OutputCallbackInfo| {
+				source_data.clear();
+				source_data.resize(data.len(), 0.0);
+				data_callback(&mut source_data, info);
+				converter.convert(&source_data, data);
+			},
+			error_callback,
+			None
+		)
+	}
+
+	/// Build an input stream in the given sample format. The samples of the stream are converted to f32 samples before they are passed to the data callback.
+	pub fn build_input_stream<T, U>(cpal_device:&CpalDevice, stream_config:&StreamConfig, sample_format:SampleFormat, data_callback:T, error_callback:U) -> Result<Stream, BuildStreamError> where T:FnMut(&[f32], &InputCallbackInfo) + Send + 'static, U:FnMut(StreamError) + Send + 'static {
+		match sample_format {
+			SampleFormat::F32 => {
+				let mut data_callback:T = data_callback;
+				cpal_device.build_input_stream(stream_config, move |data:&[f32], info:&InputCallbackInfo| data_callback(data, info), error_callback, None)
+			},
+			SampleFormat::I8 => SampleConverter::build_converted_input_stream::<i8, T, U>(cpal_device, stream_config, data_callback, error_callback),
+			SampleFormat::I16 => SampleConverter::build_converted_input_stream::<i16, T, U>(cpal_device, stream_config, data_callback, error_callback),
+			SampleFormat::I32 => SampleConverter::build_converted_input_stream::<i32, T, U>(cpal_device, stream_config, data_callback, error_callback),
+			SampleFormat::I64 => SampleConverter::build_converted_input_stream::<i64, T, U>(cpal_device, stream_config, data_callback, error_callback),
+			SampleFormat::U8 => SampleConverter::build_converted_input_stream::<u8, T, U>(cpal_device, stream_config, data_callback, error_callback),
+			SampleFormat::U16 => SampleConverter::build_converted_input_stream::<u16, T, U>(cpal_device, stream_config, data_callback, error_callback),
+			SampleFormat::U32 => SampleConverter::build_converted_input_stream::<u32, T, U>(cpal_device, stream_config, data_callback, error_callback),
+			SampleFormat::U64 => SampleConverter::build_converted_input_stream::<u64, T, U>(cpal_device, stream_config, data_callback, error_callback),
+			SampleFormat::F64 => SampleConverter::build_converted_input_stream::<f64, T, U>(cpal_device, stream_config, data_callback, error_callback),
+			_ => Err(BuildStreamError::StreamConfigNotSupported)
+		}
+	}
+
+	/// Build an input stream that converts its samples of type `S` to the f32 samples of the data callback.
+	fn build_converted_input_stream<S, T, U>(cpal_device:&CpalDevice, stream_config:&StreamConfig, mut data_callback:T, error_callback:U) -> Result<Stream, BuildStreamError> where S:SizedSample, f32:FromSample<S>, T:FnMut(&[f32], &InputCallbackInfo) + Send + 'static, U:FnMut(StreamError) + Send + 'static {
+		let mut target_data:Vec<f32> = Vec::new();
+		cpal_device.build_input_stream(
+			stream_config,
+			move |data:&[S], info:&InputCallbackInfo| {
+				SampleConverter::convert_to_f32(data, &mut target_data);
+				data_callback(&target_data, info);
+			},
+			error_callback,
+			None
+		)
+	}
+}
\ No newline at end of file
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+	use crate::SampleFormat;
+	use super::super::sample_converter::SampleConverter;
+
+
+
+	const SOURCE:&[f32] = &[0.0, 0.5, -0.5, 1.0, -1.0, 1.5, -1.5];
+
+
+
+	#[test]
+	fn test_convert_integer_formats() {
+		let mut target:Vec<i16> = vec![0; SOURCE.len()];
+		SampleConverter::new(SampleFormat::I16, false).convert(SOURCE, &mut target);
+		assert_eq!(target, vec![0, 16384, -16384, 32767, -32768, 32767, -32768]);
+
+		let mut target:Vec<u16> = vec![0; SOURCE.len()];
+		SampleConverter::new(SampleFormat::U16, false).convert(SOURCE, &mut target);
+		assert_eq!(target, vec![32768, 49152, 16384, 65535, 0, 65535, 0]);
+
+		let mut target:Vec<u8> = vec![0; SOURCE.len()];
+		SampleConverter::new(SampleFormat::U8, false).convert(SOURCE, &mut target);
+		assert_eq!(target, vec![128, 192, 64, 255, 0, 255, 0]);
+
+		let mut target:Vec<i32> = vec![0; SOURCE.len()];
+		SampleConverter::new(SampleFormat::I32, false).convert(SOURCE, &mut target);
+		assert_eq!(target, vec![0, 1073741824, -1073741824, i32::MAX, i32::MIN, i32::MAX, i32::MIN]);
+	}
+
+	#[test]
+	fn test_convert_rounding() {
+		let mut target:Vec<i16> = vec![0; 3];
+		SampleConverter::new(SampleFormat::I16, false).convert(&[0.6 / 32768.0, -0.6 / 32768.0, 0.4 / 32768.0], &mut target);
+		assert_eq!(target, vec![1, -1, 0]);
+	}
+
+	#[test]
+	fn test_convert_float_formats() {
+		let mut target:Vec<f64> = vec![0.0; SOURCE.len()];
+		SampleConverter::new(SampleFormat::F64, true).convert(SOURCE, &mut target);
+		assert_eq!(target, vec![0.0, 0.5, -0.5, 1.0, -1.0, 1.0, -1.0]);
+	}
+
+	#[test]
+	fn test_convert_to_f32() {
+		let mut target:Vec<f32> = vec![1.0; 2];
+		SampleConverter::convert_to_f32(&[0i16, 16384, -16384, i16::MAX, i16::MIN], &mut target);
+		assert_eq!(target, vec![0.0, 0.5, -0.5, 32767.0 / 32768.0, -1.0]);
+
+		SampleConverter::convert_to_f32(&[32768u16, 49152, 16384, 0], &mut target);
+		assert_eq!(target, vec![0.0, 0.5, -0.5, -1.0]);
+
+		SampleConverter::convert_to_f32(&[0.5f64, -0.25], &mut target);
+		assert_eq!(target, vec![0.5, -0.25]);
+	}
+
+	#[test]
+	fn test_convert_dither() {
+		const SAMPLE_COUNT:usize = 10000;
+		let source:Vec<f32> = vec![0.25 / 32768.0; SAMPLE_COUNT];
+		let mut target:Vec<i16> = vec![0; SAMPLE_COUNT];
+
+		// Without dither, values below half a step are lost.
+		SampleConverter::new(SampleFormat::I16, false).convert(&source, &mut target);
+		assert!(target.iter().all(|sample| *sample == 0));
+
+		// With dither, the noise stays within one step and the average level is preserved.
+		SampleConverter::new(SampleFormat::I16, true).convert(&source, &mut target);
+		assert!(target.iter().all(|sample| (-1..=2).contains(sample)));
+		assert!(target.iter().any(|sample| *sample != 0));
+		let average:f32 = target.iter().map(|sample| *sample as f32).sum::<f32>() / SAMPLE_COUNT as f32;
+		assert!((average - 0.25).abs() < 0.05, "average {average} is not close to 0.25");
+	}
+}
\ No newline at end of file
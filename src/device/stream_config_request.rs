@@ -0,0 +1,91 @@
+use cpal::SampleFormat;
+use std::time::Duration;
+use crate::{ AudioBufferDataLength, AudioWelderError, DeviceConfig, DeviceConfigRange };
+
+
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum BufferSizeRequest { Samples(u32), Latency(Duration) }
+
+
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct ResolvedStreamConfig {
+	pub sample_rate:u32,
+	pub channel_count:usize,
+	pub sample_format:SampleFormat,
+	pub buffer_size:Option<u32>
+}
+
+
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub(crate) struct StreamConfigRequest {
+	pub sample_rate:Option<u32>,
+	pub channel_count:Option<usize>,
+	pub sample_format:Option<SampleFormat>,
+	pub buffer_size:Option<BufferSizeRequest>,
+	pub fallback_to_nearest:bool
+}
+impl StreamConfigRequest {
+
+	/// Find the config to use for a device with the given default config and supported config ranges. Unspecified properties use the default config. When the device does not support the requested config, either returns an error or falls back to the nearest supported config.
+	pub fn resolve(&self, device_name:&str, default_config:Option<DeviceConfig>, config_ranges:&[DeviceConfigRange]) -> Result<ResolvedStreamConfig, AudioWelderError> {
+
+		// Combine requested and default properties.
+		let sample_rate:u32 = match self.sample_rate.or(default_config.map(|config| config.sample_rate)) {
+			Some(sample_rate) => sample_rate,
+			None => return Err(AudioWelderError::UnsupportedFormat(format!("Device '{device_name}' has no default config, a sample rate needs to be specified.")))
+		};
+		let channel_count:usize = match self.channel_count.or(default_config.map(|config| config.channel_count)) {
+			Some(channel_count) => channel_count,
+			None => return Err(AudioWelderError::UnsupportedFormat(format!("Device '{device_name}' has no default config, a channel count needs to be specified.")))
+		};
+		let sample_format:SampleFormat = self.sample_format.or(default_config.map(|config| config.sample_format)).unwrap_or(SampleFormat::F32);
+		let mut resolved:ResolvedStreamConfig = ResolvedStreamConfig { sample_rate, channel_count, sample_format, buffer_size: None };
+
+		// Find matching config range. Devices that do not report their ranges are assumed to support the config.
+		let mut buffer_size_range:Option<(u32, u32)> = default_config.and_then(|config| config.buffer_size_range);
+		if !config_ranges.is_empty() {
+			let range:DeviceConfigRange = match config_ranges.iter().find(|range| range.supports(sample_rate, channel_count, sample_format)) {
+				Some(range) => *range,
+				None if self.fallback_to_nearest => StreamConfigRequest::nearest_range(&resolved, config_ranges),
+				None => return Err(AudioWelderError::UnsupportedFormat(format!("Device '{device_name}' does not support {sample_rate}Hz with {channel_count} channels in {sample_format} format.")))
+			};
+			resolved.sample_rate = sample_rate.clamp(range.min_sample_rate, range.max_sample_rate);
+			resolved.channel_count = range.channel_count;
+			resolved.sample_format = range.sample_format;
+			buffer_size_range = range.buffer_size_range;
+		}
+
+		// Find buffer size.
+		if let Some(buffer_size) = self.buffer_size {
+			let buffer_size:u32 = match buffer_size {
+				BufferSizeRequest::Samples(samples) => samples,
+				BufferSizeRequest::Latency(latency) => latency.as_buffer_length(resolved.sample_rate).max(1) as u32
+			};
+			resolved.buffer_size = Some(match buffer_size_range {
+				Some((min, max)) if buffer_size < min || buffer_size > max => {
+					if self.fallback_to_nearest {
+						buffer_size.clamp(min, max)
+					} else {
+						return Err(AudioWelderError::UnsupportedFormat(format!("Device '{device_name}' does not support a buffer size of {buffer_size} samples, supported sizes range from {min} to {max}.")));
+					}
+				},
+				_ => buffer_size
+			});
+		}
+
+		Ok(resolved)
+	}
+
+	/// Find the range nearest to the given config. Matching channel count has priority over matching sample format, which has priority over matching sample rate.
+	fn nearest_range(target:&ResolvedStreamConfig, config_ranges:&[DeviceConfigRange]) -> DeviceConfigRange {
+		*config_ranges.iter().min_by_key(|range| {
+			let channel_distance:usize = range.channel_count.abs_diff(target.channel_count);
+			let format_distance:usize = if range.sample_format == target.sample_format { 0 } else { 1 };
+			let sample_rate_distance:u32 = target.sample_rate.clamp(range.min_sample_rate, range.max_sample_rate).abs_diff(target.sample_rate);
+			(channel_distance, format_distance, sample_rate_distance)
+		}).unwrap()
+	}
+}
\ No newline at end of file
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+	use crate::{ AudioWelderError, DeviceConfig, DeviceConfigRange, SampleFormat };
+	use super::super::stream_config_request::{ BufferSizeRequest, ResolvedStreamConfig, StreamConfigRequest };
+
+
+
+	const DEFAULT_CONFIG:DeviceConfig = DeviceConfig { sample_rate: 48000, channel_count: 2, sample_format: SampleFormat::F32, buffer_size_range: Some((64, 4096)) };
+	const CONFIG_RANGES:&[DeviceConfigRange] = &[
+		DeviceConfigRange { channel_count: 2, min_sample_rate: 44100, max_sample_rate: 96000, sample_format: SampleFormat::F32, buffer_size_range: Some((64, 4096)) },
+		DeviceConfigRange { channel_count: 1, min_sample_rate: 8000, max_sample_rate: 48000, sample_format: SampleFormat::I16, buffer_size_range: Some((32, 2048)) }
+	];
+
+	fn resolve(request:StreamConfigRequest) -> Result<ResolvedStreamConfig, AudioWelderError> {
+		request.resolve("Test Speakers", Some(DEFAULT_CONFIG), CONFIG_RANGES)
+	}
+
+
+
+	#[test]
+	fn test_resolve_default() {
+		let config:ResolvedStreamConfig = resolve(StreamConfigRequest::default()).unwrap();
+		assert_eq!(config, ResolvedStreamConfig { sample_rate: 48000, channel_count: 2, sample_format: SampleFormat::F32, buffer_size: None });
+	}
+
+	#[test]
+	fn test_resolve_exact() {
+		let config:ResolvedStreamConfig = resolve(StreamConfigRequest { sample_rate: Some(16000), channel_count: Some(1), sample_format: Some(SampleFormat::I16), buffer_size: Some(BufferSizeRequest::Samples(256)), ..Default::default() }).unwrap();
+		assert_eq!(config, ResolvedStreamConfig { sample_rate: 16000, channel_count: 1, sample_format: SampleFormat::I16, buffer_size: Some(256) });
+
+		// Unspecified properties use the default config.
+		let config:ResolvedStreamConfig = resolve(StreamConfigRequest { sample_rate: Some(96000), ..Default::default() }).unwrap();
+		assert_eq!(config, ResolvedStreamConfig { sample_rate: 96000, channel_count: 2, sample_format: SampleFormat::F32, buffer_size: None });
+	}
+
+	#[test]
+	fn test_resolve_unsupported() {
+		let error:AudioWelderError = resolve(StreamConfigRequest { sample_rate: Some(192000), ..Default::default() }).unwrap_err();
+		assert!(matches!(error, AudioWelderError::UnsupportedFormat(_)));
+		assert_eq!(error.to_string(), "Unsupported format: Device 'Test Speakers' does not support 192000Hz with 2 channels in f32 format.");
+
+		let error:AudioWelderError = resolve(StreamConfigRequest { buffer_size: Some(BufferSizeRequest::Samples(16)), ..Default::default() }).unwrap_err();
+		assert_eq!(error.to_string(), "Unsupported format: Device 'Test Speakers' does not support a buffer size of 16 samples, supported sizes range from 64 to 4096.");
+	}
+
+	#[test]
+	fn test_resolve_nearest() {
+		let request:StreamConfigRequest = StreamConfigRequest { sample_rate: Some(192000), channel_count: Some(1), buffer_size: Some(BufferSizeRequest::Samples(16)), fallback_to_nearest: true, ..Default::default() };
+		let config:ResolvedStreamConfig = resolve(request).unwrap();
+		assert_eq!(config, ResolvedStreamConfig { sample_rate: 48000, channel_count: 1, sample_format: SampleFormat::I16, buffer_size: Some(32) });
+
+		let request:StreamConfigRequest = StreamConfigRequest { sample_rate: Some(8000), channel_count: Some(2), fallback_to_nearest: true, ..Default::default() };
+		let config:ResolvedStreamConfig = resolve(request).unwrap();
+		assert_eq!(config, ResolvedStreamConfig { sample_rate: 44100, channel_count: 2, sample_format: SampleFormat::F32, buffer_size: None });
+	}
+
+	#[test]
+	fn test_resolve_latency() {
+		let config:ResolvedStreamConfig = resolve(StreamConfigRequest { buffer_size: Some(BufferSizeRequest::Latency(Duration::from_millis(10))), ..Default::default() }).unwrap();
+		assert_eq!(config.buffer_size, Some(480));
+
+		let request:StreamConfigRequest = StreamConfigRequest { buffer_size: Some(BufferSizeRequest::Latency(Duration::from_secs(1))), fallback_to_nearest: true, ..Default::default() };
+		assert_eq!(resolve(request).unwrap().buffer_size, Some(4096));
+	}
+
+	#[test]
+	fn test_resolve_without_default() {
+		let error:AudioWelderError = StreamConfigRequest::default().resolve("Test Speakers", None, CONFIG_RANGES).unwrap_err();
+		assert!(matches!(error, AudioWelderError::UnsupportedFormat(_)));
+		let config:ResolvedStreamConfig = StreamConfigRequest { sample_rate: Some(22050), channel_count: Some(1), sample_format: Some(SampleFormat::I16), ..Default::default() }.resolve("Test Speakers", None, CONFIG_RANGES).unwrap();
+		assert_eq!(config.sample_rate, 22050);
+	}
+}
\ No newline at end of file
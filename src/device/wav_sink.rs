@@ -0,0 +1,69 @@
+use hound::WavWriter;
+use std::{ fs::File, io::BufWriter, thread };
+use crate::{ AudioWelderError, WavFormat };
+use super::output_backend::{ OutputBackend, OutputStream };
+
+
+
+/// An output backend that records everything it would play to a wav file. Each stream started on the sink overwrites the file.
+pub struct WavSink {
+	file_path:String,
+	sample_rate:u32,
+	channel_count:usize,
+	format:WavFormat,
+	real_time:bool
+}
+impl WavSink {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new wav sink recording audio in real time.
+	pub fn new(file_path:&str, sample_rate:u32, channel_count:usize, format:WavFormat) -> WavSink {
+		WavSink {
+			file_path: file_path.to_string(),
+			sample_rate,
+			channel_count: channel_count.max(1),
+			format,
+			real_time: true
+		}
+	}
+
+
+
+	/* BUILDER METHODS */
+
+	/// Return self with real time enabled or disabled. When disabled, audio is recorded as fast as possible. Enabled by default.
+	pub fn real_time(mut self, real_time:bool) -> Self {
+		self.real_time = real_time;
+		self
+	}
+}
+impl OutputBackend for WavSink {
+	fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+	fn channel_count(&self) -> usize {
+		self.channel_count
+	}
+	fn start(&self, stream:OutputStream) -> Result<(), AudioWelderError> {
+		let format:WavFormat = self.format;
+		let sample_rate:u32 = self.sample_rate;
+		let real_time:bool = self.real_time;
+		let mut wav_writer:WavWriter<BufWriter<File>> = WavWriter::create(&self.file_path, format.spec(self.channel_count, self.sample_rate))?;
+		thread::spawn(move || {
+			stream.render(sample_rate, real_time, |data| {
+				for sample in data {
+					match format {
+						WavFormat::Float32 => wav_writer.write_sample(*sample)?,
+						_ => wav_writer.write_sample(format.quantize(*sample))?
+					}
+				}
+				Ok(())
+			});
+			if let Err(error) = wav_writer.finalize() {
+				stream.report_error(error.into(), true);
+			}
+		});
+		Ok(())
+	}
+}
\ No newline at end of file
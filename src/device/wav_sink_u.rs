@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioWelderError, OutputDevice, OutputMixer, WavFormat, WavSink };
+	use std::{ fs::create_dir_all, thread, time::{ Duration, Instant } };
+
+
+
+	#[test]
+	fn test_wav_sink_records_playback() {
+		const FILE_PATH:&str = "target/debug_wavs/wav_sink.wav";
+		let left:Vec<f32> = (0..245).map(|index| index as f32 / 245.0).collect();
+		let right:Vec<f32> = left.iter().map(|sample| -sample).collect();
+
+		// Play through sink.
+		create_dir_all("target/debug_wavs").expect("Could not create debug wavs dir.");
+		let device:OutputDevice = OutputDevice::from_backend(WavSink::new(FILE_PATH, 1000, 2, WavFormat::Float32).real_time(false));
+		device.play_async(AudioBuffer::new(vec![left.clone(), right.clone()], 1000)).unwrap().wait();
+
+		// Validate recording. Playback is rendered in blocks, but the last block is cut off where the playback ended.
+		let recording:AudioBuffer = AudioBuffer::wav(FILE_PATH).unwrap();
+		assert_eq!(recording.sample_rate(), 1000);
+		assert_eq!(recording.channel_count(), 2);
+		assert_eq!(recording.raw_data()[0], left);
+		assert_eq!(recording.raw_data()[1], right);
+	}
+
+	#[test]
+	fn test_wav_sink_waits_for_sources() {
+		const FILE_PATH:&str = "target/debug_wavs/wav_sink_mixer.wav";
+
+		// Rendering without real time waits while the mixer has nothing to play, instead of recording silence.
+		create_dir_all("target/debug_wavs").expect("Could not create debug wavs dir.");
+		let device:OutputDevice = OutputDevice::from_backend(WavSink::new(FILE_PATH, 1000, 1, WavFormat::Float32).real_time(false));
+		let mixer:OutputMixer = device.mixer().unwrap();
+		thread::sleep(Duration::from_millis(50));
+		mixer.add_source(AudioBuffer::new(vec![vec![0.5; 100]], 1000));
+		let start_time:Instant = Instant::now();
+		while mixer.source_count() > 0 && start_time.elapsed() < Duration::from_secs(5) {
+			thread::sleep(Duration::from_millis(1));
+		}
+		thread::sleep(Duration::from_millis(50));
+		drop(mixer);
+
+		// Validate recording once the file is finalized.
+		let start_time:Instant = Instant::now();
+		let mut recording:Option<AudioBuffer> = None;
+		while recording.is_none() && start_time.elapsed() < Duration::from_secs(5) {
+			thread::sleep(Duration::from_millis(10));
+			recording = AudioBuffer::wav(FILE_PATH).ok().filter(|recording| recording.raw_data().first().is_some_and(|channel| !channel.is_empty()));
+		}
+		assert_eq!(recording.unwrap().raw_data()[0], vec![0.5; 100]);
+	}
+
+	#[test]
+	fn test_wav_sink_invalid_path() {
+		let device:OutputDevice = OutputDevice::from_backend(WavSink::new("target/audio_welder_nonexistent_dir/sink.wav", 1000, 1, WavFormat::Int16));
+		assert!(matches!(device.play_async(AudioBuffer::new(vec![vec![0.0; 10]], 1000)), Err(AudioWelderError::Io(_))));
+	}
+}
\ No newline at end of file
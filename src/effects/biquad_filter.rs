@@ -0,0 +1,267 @@
+use crate::{ audio_effect::create_effect_id, AudioEffect };
+use std::{ any::Any, f64::consts::TAU };
+
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BiquadFilterType { LowPass, HighPass, BandPass, Notch, AllPass, LowShelf, HighShelf, Peaking }
+
+
+
+#[derive(Clone, Copy, Default, PartialEq)]
+struct BiquadCoefficients {
+	b0:f64,
+	b1:f64,
+	b2:f64,
+	a1:f64,
+	a2:f64
+}
+impl BiquadCoefficients {
+
+	/// Calculate the coefficients of the given filter type using the formulas of the Audio EQ Cookbook by Robert Bristow-Johnson. The gain is in decibels and only used by shelf and peaking filters.
+	fn new(filter_type:BiquadFilterType, sample_rate:u32, frequency:f32, q:f32, gain:f32) -> BiquadCoefficients {
+		let nyquist:f64 = sample_rate as f64 * 0.5;
+		let frequency:f64 = (frequency as f64).clamp(nyquist * 1e-5, nyquist * 0.9999);
+		let q:f64 = (q as f64).max(1e-3);
+		let amplitude:f64 = 10.0_f64.powf(gain as f64 / 40.0);
+		let omega:f64 = TAU * frequency / sample_rate as f64;
+		let (sin, cos) = omega.sin_cos();
+		let alpha:f64 = sin / (2.0 * q);
+		let shelf_alpha:f64 = 2.0 * amplitude.sqrt() * alpha;
+
+		// Calculate the unnormalized coefficients.
+		let (b0, b1, b2, a0, a1, a2) = match filter_type {
+			BiquadFilterType::LowPass => ((1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+			BiquadFilterType::HighPass => ((1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+			BiquadFilterType::BandPass => (alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+			BiquadFilterType::Notch => (1.0, -2.0 * cos, 1.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+			BiquadFilterType::AllPass => (1.0 - alpha, -2.0 * cos, 1.0 + alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+			BiquadFilterType::LowShelf => (
+				amplitude * ((amplitude + 1.0) - (amplitude - 1.0) * cos + shelf_alpha),
+				2.0 * amplitude * ((amplitude - 1.0) - (amplitude + 1.0) * cos),
+				amplitude * ((amplitude + 1.0) - (amplitude - 1.0) * cos - shelf_alpha),
+				(amplitude + 1.0) + (amplitude - 1.0) * cos + shelf_alpha,
+				-2.0 * ((amplitude - 1.0) + (amplitude + 1.0) * cos),
+				(amplitude + 1.0) + (amplitude - 1.0) * cos - shelf_alpha
+			),
+			BiquadFilterType::HighShelf => (
+				amplitude * ((amplitude + 1.0) + (amplitude - 1.0) * cos + shelf_alpha),
+				-2.0 * amplitude * ((amplitude - 1.0) + (amplitude + 1.0) * cos),
+				amplitude * ((amplitude + 1.0) + (amplitude - 1.0) * cos - shelf_alpha),
+				(amplitude + 1.0) - (amplitude - 1.0) * cos + shelf_alpha,
+				2.0 * ((amplitude - 1.0) - (amplitude + 1.0) * cos),
+				(amplitude + 1.0) - (amplitude - 1.0) * cos - shelf_alpha
+			),
+			BiquadFilterType::Peaking => (1.0 + alpha * amplitude, -2.0 * cos, 1.0 - alpha * amplitude, 1.0 + alpha / amplitude, -2.0 * cos, 1.0 - alpha / amplitude)
+		};
+
+		// Normalize.
+		BiquadCoefficients {
+			b0: b0 / a0,
+			b1: b1 / a0,
+			b2: b2 / a0,
+			a1: a1 / a0,
+			a2: a2 / a0
+		}
+	}
+
+	/// Get the gain in decibels at the given frequency.
+	fn magnitude_response(&self, sample_rate:u32, frequency:f32) -> f32 {
+		let omega:f64 = TAU * frequency as f64 / sample_rate as f64;
+		let (sin, cos) = omega.sin_cos();
+		let (double_sin, double_cos) = (omega * 2.0).sin_cos();
+		let numerator:(f64, f64) = (self.b0 + self.b1 * cos + self.b2 * double_cos, -self.b1 * sin - self.b2 * double_sin);
+		let denominator:(f64, f64) = (1.0 + self.a1 * cos + self.a2 * double_cos, -self.a1 * sin - self.a2 * double_sin);
+		(10.0 * ((numerator.0 * numerator.0 + numerator.1 * numerator.1) / (denominator.0 * denominator.0 + denominator.1 * denominator.1)).log10()) as f32
+	}
+}
+
+
+
+#[derive(PartialEq)]
+pub struct BiquadFilter {
+	id:usize,
+	filter_type:BiquadFilterType,
+	frequency:f32,
+	q:f32,
+	gain:f32,
+
+	coefficients:BiquadCoefficients,
+	coefficient_settings:Option<(BiquadFilterType, u32, f32, f32, f32)>,
+	channel_states:Vec<[f64; 2]>
+}
+impl BiquadFilter {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new biquad filter of the given type. The frequency is in Hz and the gain in decibels. The gain is only used by shelf and peaking filters.
+	pub fn new(filter_type:BiquadFilterType, frequency:f32, q:f32, gain:f32) -> BiquadFilter {
+		BiquadFilter {
+			id: create_effect_id(),
+			filter_type,
+			frequency,
+			q,
+			gain,
+
+			coefficients: BiquadCoefficients::default(),
+			coefficient_settings: None,
+			channel_states: Vec::new()
+		}
+	}
+
+	/// Create a new filter that removes frequencies above the given frequency.
+	pub fn new_low_pass(frequency:f32, q:f32) -> BiquadFilter {
+		BiquadFilter::new(BiquadFilterType::LowPass, frequency, q, 0.0)
+	}
+
+	/// Create a new filter that removes frequencies below the given frequency.
+	pub fn new_high_pass(frequency:f32, q:f32) -> BiquadFilter {
+		BiquadFilter::new(BiquadFilterType::HighPass, frequency, q, 0.0)
+	}
+
+	/// Create a new filter that only keeps frequencies around the given frequency. A higher Q narrows the band.
+	pub fn new_band_pass(frequency:f32, q:f32) -> BiquadFilter {
+		BiquadFilter::new(BiquadFilterType::BandPass, frequency, q, 0.0)
+	}
+
+	/// Create a new filter that removes frequencies around the given frequency. A higher Q narrows the band.
+	pub fn new_notch(frequency:f32, q:f32) -> BiquadFilter {
+		BiquadFilter::new(BiquadFilterType::Notch, frequency, q, 0.0)
+	}
+
+	/// Create a new filter that keeps the volume of all frequencies, but shifts their phase around the given frequency.
+	pub fn new_all_pass(frequency:f32, q:f32) -> BiquadFilter {
+		BiquadFilter::new(BiquadFilterType::AllPass, frequency, q, 0.0)
+	}
+
+	/// Create a new filter that amplifies frequencies below the given frequency by the given amount of decibels.
+	pub fn new_low_shelf(frequency:f32, q:f32, gain:f32) -> BiquadFilter {
+		BiquadFilter::new(BiquadFilterType::LowShelf, frequency, q, gain)
+	}
+
+	/// Create a new filter that amplifies frequencies above the given frequency by the given amount of decibels.
+	pub fn new_high_shelf(frequency:f32, q:f32, gain:f32) -> BiquadFilter {
+		BiquadFilter::new(BiquadFilterType::HighShelf, frequency, q, gain)
+	}
+
+	/// Create a new filter that amplifies frequencies around the given frequency by the given amount of decibels.
+	pub fn new_peaking(frequency:f32, q:f32, gain:f32) -> BiquadFilter {
+		BiquadFilter::new(BiquadFilterType::Peaking, frequency, q, gain)
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the type of the filter.
+	pub fn filter_type(&self) -> BiquadFilterType {
+		self.filter_type
+	}
+
+	/// Get the gain in decibels the filter applies at the given frequency and sample rate.
+	pub fn magnitude_response(&self, sample_rate:u32, frequency:f32) -> f32 {
+		BiquadCoefficients::new(self.filter_type, sample_rate, self.frequency, self.q, self.gain).magnitude_response(sample_rate, frequency)
+	}
+
+
+
+	/* PROPERTY SETTER METHODS */
+
+	/// Set the type of the filter. The coefficients are recalculated on the next use, the state of the filter is kept.
+	pub fn set_filter_type(&mut self, filter_type:BiquadFilterType) {
+		self.filter_type = filter_type;
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Create a new filter with the same type and settings, without the state of this filter.
+	pub(crate) fn settings_copy(&self) -> BiquadFilter {
+		BiquadFilter::new(self.filter_type, self.frequency, self.q, self.gain)
+	}
+
+	/// Recalculate the coefficients if the sample rate, type or any setting changed since they were last calculated.
+	fn update_coefficients(&mut self, sample_rate:u32) {
+		let settings:(BiquadFilterType, u32, f32, f32, f32) = (self.filter_type, sample_rate, self.frequency, self.q, self.gain);
+		if self.coefficient_settings != Some(settings) {
+			self.coefficients = BiquadCoefficients::new(self.filter_type, sample_rate, self.frequency, self.q, self.gain);
+			self.coefficient_settings = Some(settings);
+		}
+	}
+}
+impl AudioEffect for BiquadFilter {
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the ID of the effect.
+	fn id(&self) -> usize {
+		self.id
+	}
+
+	/// Get the name of the effect.
+	fn name(&self) -> &str {
+		"biquad_filter"
+	}
+
+	/// Clone the effect into a box.
+	fn boxed(&self) -> Box<dyn AudioEffect> {
+		Box::new(self.settings_copy())
+	}
+
+	/// Allow downcasting.
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Apply the effect to the given buffer.
+	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, _channel_count:&mut usize) {
+		self.update_coefficients(*sample_rate);
+		if self.channel_states.len() != data.len() {
+			self.channel_states = vec![[0.0; 2]; data.len()];
+		}
+
+		// Filter each channel using the transposed direct form II, keeping the state for the next call.
+		let BiquadCoefficients { b0, b1, b2, a1, a2 } = self.coefficients;
+		for (channel, state) in data.iter_mut().zip(&mut self.channel_states) {
+			for sample in channel {
+				let input:f64 = *sample as f64;
+				let output:f64 = b0 * input + state[0];
+				state[0] = b1 * input - a1 * output + state[1];
+				state[1] = b2 * input - a2 * output;
+				*sample = output as f32;
+			}
+		}
+	}
+
+	/// Forget the audio processed so far, so the next output does not depend on it.
+	fn reset(&mut self) {
+		self.channel_states = Vec::new();
+	}
+
+
+
+	/* SETTING METHODS */
+
+	/// Get a list of settings with their names.
+	fn settings(&self) -> Vec<(&str, &f32)> {
+		vec![
+			("frequency", &self.frequency),
+			("q", &self.q),
+			("gain", &self.gain)
+		]
+	}
+
+	/// Get a mutable list of settings with their names.
+	fn settings_mut(&mut self) -> Vec<(&str, &mut f32)> {
+		vec![
+			("frequency", &mut self.frequency),
+			("q", &mut self.q),
+			("gain", &mut self.gain)
+		]
+	}
+}
\ No newline at end of file
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioEffect, AudioGenerator, BiquadFilter, BiquadFilterType };
+	use super::super::test_signals::sine;
+
+
+
+	/// Get the gain in decibels the given filter applies to a sine of the given frequency, measured after the filter settled.
+	fn filter_gain(filter:BiquadFilter, frequency:f32) -> f32 {
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(frequency, 0.5, 48000, 48000)], 48000);
+		buffer.add_effect(filter);
+		let data:&[f32] = &buffer.processed_data()[0][24000..];
+		let rms:f32 = (data.iter().map(|sample| sample * sample).sum::<f32>() / data.len() as f32).sqrt();
+		20.0 * (rms / (0.5 / 2.0_f32.sqrt())).log10()
+	}
+
+
+
+	/* FILTER TYPE TESTS */
+
+	#[test]
+	fn test_effect_biquad_pass_filters() {
+		assert!(filter_gain(BiquadFilter::new_low_pass(1000.0, 0.707), 100.0).abs() < 0.1);
+		assert!(filter_gain(BiquadFilter::new_low_pass(1000.0, 0.707), 1000.0) + 3.0 < 0.1);
+		assert!(filter_gain(BiquadFilter::new_low_pass(1000.0, 0.707), 10000.0) < -35.0);
+
+		assert!(filter_gain(BiquadFilter::new_high_pass(1000.0, 0.707), 10000.0).abs() < 0.1);
+		assert!(filter_gain(BiquadFilter::new_high_pass(1000.0, 0.707), 1000.0) + 3.0 < 0.1);
+		assert!(filter_gain(BiquadFilter::new_high_pass(1000.0, 0.707), 100.0) < -35.0);
+
+		assert!(filter_gain(BiquadFilter::new_band_pass(1000.0, 2.0), 1000.0).abs() < 0.1);
+		assert!(filter_gain(BiquadFilter::new_band_pass(1000.0, 2.0), 100.0) < -20.0);
+		assert!(filter_gain(BiquadFilter::new_band_pass(1000.0, 2.0), 10000.0) < -20.0);
+	}
+
+	#[test]
+	fn test_effect_biquad_notch_and_all_pass() {
+		assert!(filter_gain(BiquadFilter::new_notch(1000.0, 2.0), 1000.0) < -40.0);
+		assert!(filter_gain(BiquadFilter::new_notch(1000.0, 2.0), 100.0).abs() < 0.1);
+		for frequency in [100.0, 1000.0, 10000.0] {
+			assert!(filter_gain(BiquadFilter::new_all_pass(1000.0, 0.707), frequency).abs() < 0.01);
+		}
+	}
+
+	#[test]
+	fn test_effect_biquad_shelves_and_peaking() {
+		assert!((filter_gain(BiquadFilter::new_low_shelf(1000.0, 0.707, 6.0), 50.0) - 6.0).abs() < 0.1);
+		assert!(filter_gain(BiquadFilter::new_low_shelf(1000.0, 0.707, 6.0), 15000.0).abs() < 0.1);
+
+		assert!((filter_gain(BiquadFilter::new_high_shelf(1000.0, 0.707, -6.0), 15000.0) + 6.0).abs() < 0.1);
+		assert!(filter_gain(BiquadFilter::new_high_shelf(1000.0, 0.707, -6.0), 50.0).abs() < 0.1);
+
+		assert!((filter_gain(BiquadFilter::new_peaking(1000.0, 1.0, 9.0), 1000.0) - 9.0).abs() < 0.1);
+		assert!(filter_gain(BiquadFilter::new_peaking(1000.0, 1.0, 9.0), 50.0).abs() < 0.1);
+		assert!(filter_gain(BiquadFilter::new_peaking(1000.0, 1.0, 9.0), 15000.0).abs() < 0.1);
+	}
+
+
+
+	/* STATE TESTS */
+
+	#[test]
+	fn test_effect_biquad_streaming() {
+		let samples:Vec<Vec<f32>> = vec![sine(440.0, 0.5, 8000, 8000), sine(3000.0, 0.5, 8000, 8000)];
+		let mut whole_buffer:AudioBuffer = AudioBuffer::new(samples.clone(), 8000);
+		whole_buffer.add_effect(BiquadFilter::new_low_pass(1000.0, 0.707));
+		let whole:Vec<Vec<f32>> = whole_buffer.processed_data().clone();
+
+		// Taking the buffer in chunks should produce the same samples as processing it at once.
+		let mut buffer:AudioBuffer = AudioBuffer::new(samples, 8000);
+		buffer.add_effect(BiquadFilter::new_low_pass(1000.0, 0.707));
+		let mut chunked:Vec<Vec<f32>> = vec![Vec::new(); 2];
+		while !buffer.is_depleted() {
+			for (channel, new_data) in chunked.iter_mut().zip(buffer.take(70)) {
+				channel.extend(new_data);
+			}
+		}
+		for (whole_channel, chunked_channel) in whole.iter().zip(&chunked) {
+			assert_eq!(whole_channel[..], chunked_channel[..whole_channel.len()]);
+		}
+	}
+
+	#[test]
+	fn test_effect_biquad_setting_change() {
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(3000.0, 0.5, 8000, 16000)], 8000);
+		buffer.add_effect(BiquadFilter::new_low_pass(3500.0, 0.707));
+		let passed:Vec<f32> = buffer.take(8000).remove(0);
+
+		// Lowering the frequency should recalculate the coefficients and remove the sine.
+		buffer.set_effect_setting("biquad_filter", "frequency", 300.0);
+		let filtered:Vec<f32> = buffer.take(8000).remove(0);
+		let peak = |data:&[f32]| data[4000..].iter().fold(0.0, |peak:f32, sample| peak.max(sample.abs()));
+		assert!(peak(&passed) > 0.3);
+		assert!(peak(&filtered) < 0.01);
+	}
+
+	#[test]
+	fn test_effect_biquad_settings() {
+		let mut filter:BiquadFilter = BiquadFilter::new(BiquadFilterType::Peaking, 1000.0, 1.0, 3.0);
+		assert_eq!(filter.filter_type(), BiquadFilterType::Peaking);
+		filter.settings_test();
+	}
+}
\ No newline at end of file
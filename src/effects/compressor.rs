@@ -0,0 +1,248 @@
+use crate::{ audio_effect::create_effect_id, AudioEffect };
+use std::any::Any;
+
+
+
+const RMS_WINDOW_DURATION:f32 = 0.01;
+const MIN_LEVEL:f32 = 1e-9;
+
+
+
+#[derive(Clone, Copy, Default, PartialEq)]
+struct ChannelState {
+	mean_square:f32,
+	gain_reduction:f32
+}
+
+
+
+#[derive(PartialEq)]
+pub struct Compressor {
+	id:usize,
+	threshold:f32,
+	ratio:f32,
+	attack:f32,
+	release:f32,
+	knee:f32,
+	makeup_gain:f32,
+	rms_detection:f32,
+	channel_link:f32,
+
+	channels:Vec<ChannelState>,
+	gain_reduction:f32
+}
+impl Compressor {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new compressor that reduces the level above the threshold in decibels by the given ratio. Attacks in 10ms, releases in 100ms and has a soft knee of 6dB, detects peaks and links all channels.
+	pub fn new(threshold:f32, ratio:f32) -> Compressor {
+		Compressor {
+			id: create_effect_id(),
+			threshold,
+			ratio,
+			attack: 10.0,
+			release: 100.0,
+			knee: 6.0,
+			makeup_gain: 0.0,
+			rms_detection: 0.0,
+			channel_link: 1.0,
+
+			channels: Vec::new(),
+			gain_reduction: 0.0
+		}
+	}
+
+
+
+	/* BUILDER METHODS */
+
+	/// Return self with the given attack time in milliseconds. This is how fast the gain reduction increases when the level rises.
+	pub fn with_attack(mut self, attack:f32) -> Self {
+		self.attack = attack;
+		self
+	}
+
+	/// Return self with the given release time in milliseconds. This is how fast the gain reduction decreases when the level falls.
+	pub fn with_release(mut self, release:f32) -> Self {
+		self.release = release;
+		self
+	}
+
+	/// Return self with the given knee width in decibels. The ratio gradually increases over this range around the threshold. A knee of 0 compresses abruptly.
+	pub fn with_knee(mut self, knee:f32) -> Self {
+		self.knee = knee;
+		self
+	}
+
+	/// Return self with the given gain in decibels applied after compressing.
+	pub fn with_makeup_gain(mut self, makeup_gain:f32) -> Self {
+		self.makeup_gain = makeup_gain;
+		self
+	}
+
+	/// Return self with RMS detection enabled or disabled. RMS detection follows the average level instead of the peaks, which sounds smoother on speech. Disabled by default.
+	pub fn with_rms_detection(mut self, rms_detection:bool) -> Self {
+		self.rms_detection = if rms_detection { 1.0 } else { 0.0 };
+		self
+	}
+
+	/// Return self with the given channel link amount between 0 and 1. At 1, all channels are reduced by the level of the loudest channel, keeping the stereo image intact. At 0, each channel is compressed independently.
+	pub fn with_channel_link(mut self, channel_link:f32) -> Self {
+		self.channel_link = channel_link;
+		self
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the current gain reduction in decibels of the most reduced channel, for metering.
+	pub fn gain_reduction(&self) -> f32 {
+		self.gain_reduction
+	}
+
+	/// Get the gain reduction in decibels for the given level in decibels, before smoothing.
+	fn target_gain_reduction(&self, level:f32) -> f32 {
+		let slope:f32 = 1.0 - 1.0 / self.ratio.max(1.0);
+		let overshoot:f32 = level - self.threshold;
+		let half_knee:f32 = self.knee.max(0.0) * 0.5;
+		if overshoot <= -half_knee {
+			0.0
+		} else if overshoot < half_knee {
+			slope * (overshoot + half_knee).powi(2) / (4.0 * half_knee)
+		} else {
+			slope * overshoot
+		}
+	}
+
+	/// Get the smoothing coefficient per sample for the given time in milliseconds.
+	fn smoothing_coefficient(time:f32, sample_rate:u32) -> f32 {
+		let sample_count:f32 = time * 0.001 * sample_rate as f32;
+		if sample_count <= 0.0 { 0.0 } else { (-1.0 / sample_count).exp() }
+	}
+}
+impl AudioEffect for Compressor {
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the ID of the effect.
+	fn id(&self) -> usize {
+		self.id
+	}
+
+	/// Get the name of the effect.
+	fn name(&self) -> &str {
+		"compressor"
+	}
+
+	/// Clone the effect into a box.
+	fn boxed(&self) -> Box<dyn AudioEffect> {
+		Box::new(Compressor {
+			id: create_effect_id(),
+			threshold: self.threshold,
+			ratio: self.ratio,
+			attack: self.attack,
+			release: self.release,
+			knee: self.knee,
+			makeup_gain: self.makeup_gain,
+			rms_detection: self.rms_detection,
+			channel_link: self.channel_link,
+
+			channels: Vec::new(),
+			gain_reduction: 0.0
+		})
+	}
+
+	/// Allow downcasting.
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Apply the effect to the given buffer.
+	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, _channel_count:&mut usize) {
+		if self.channels.len() != data.len() {
+			self.channels = vec![ChannelState::default(); data.len()];
+		}
+		let attack_coefficient:f32 = Compressor::smoothing_coefficient(self.attack, *sample_rate);
+		let release_coefficient:f32 = Compressor::smoothing_coefficient(self.release, *sample_rate);
+		let rms_coefficient:f32 = Compressor::smoothing_coefficient(RMS_WINDOW_DURATION * 1000.0, *sample_rate);
+		let channel_link:f32 = self.channel_link.clamp(0.0, 1.0);
+		let makeup_multiplier:f32 = 10.0_f32.powf(self.makeup_gain / 20.0);
+
+		// Loop through data.
+		let mut channels:Vec<ChannelState> = std::mem::take(&mut self.channels);
+		let mut levels:Vec<f32> = vec![0.0; data.len()];
+		for sample_index in 0..data.first().map(|channel| channel.len()).unwrap_or(0) {
+
+			// Detect the level of each channel.
+			for ((level, channel), state) in levels.iter_mut().zip(data.iter()).zip(&mut channels) {
+				let sample:f32 = channel[sample_index];
+				*level = if self.rms_detection != 0.0 {
+					state.mean_square = sample * sample + (state.mean_square - sample * sample) * rms_coefficient;
+					state.mean_square.sqrt()
+				} else {
+					sample.abs()
+				};
+			}
+			let linked_level:f32 = levels.iter().fold(0.0, |max:f32, level| max.max(*level));
+
+			// Smooth the gain reduction towards the target of the linked level and apply it.
+			for ((level, channel), state) in levels.iter().zip(data.iter_mut()).zip(&mut channels) {
+				let level:f32 = level + (linked_level - level) * channel_link;
+				let target:f32 = self.target_gain_reduction(20.0 * level.max(MIN_LEVEL).log10());
+				let coefficient:f32 = if target > state.gain_reduction { attack_coefficient } else { release_coefficient };
+				state.gain_reduction = target + (state.gain_reduction - target) * coefficient;
+				if state.gain_reduction != 0.0 || makeup_multiplier != 1.0 {
+					channel[sample_index] *= 10.0_f32.powf(-state.gain_reduction / 20.0) * makeup_multiplier;
+				}
+			}
+		}
+		self.gain_reduction = channels.iter().fold(0.0, |max:f32, state| max.max(state.gain_reduction));
+		self.channels = channels;
+	}
+
+	/// Forget the audio processed so far, so the next output does not depend on it.
+	fn reset(&mut self) {
+		self.channels = Vec::new();
+		self.gain_reduction = 0.0;
+	}
+
+
+
+	/* SETTING METHODS */
+
+	/// Get a list of settings with their names.
+	fn settings(&self) -> Vec<(&str, &f32)> {
+		vec![
+			("threshold", &self.threshold),
+			("ratio", &self.ratio),
+			("attack", &self.attack),
+			("release", &self.release),
+			("knee", &self.knee),
+			("makeup_gain", &self.makeup_gain),
+			("rms_detection", &self.rms_detection),
+			("channel_link", &self.channel_link),
+
+			("gain_reduction", &self.gain_reduction)
+		]
+	}
+
+	/// Get a mutable list of settings with their names.
+	fn settings_mut(&mut self) -> Vec<(&str, &mut f32)> {
+		vec![
+			("threshold", &mut self.threshold),
+			("ratio", &mut self.ratio),
+			("attack", &mut self.attack),
+			("release", &mut self.release),
+			("knee", &mut self.knee),
+			("makeup_gain", &mut self.makeup_gain),
+			("rms_detection", &mut self.rms_detection),
+			("channel_link", &mut self.channel_link)
+		]
+	}
+}
\ No newline at end of file
@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioEffect, AudioGenerator, Compressor };
+	use std::f32::consts::TAU;
+
+
+
+	/// Convert a level in decibels to an amplitude.
+	fn amplitude(level:f32) -> f32 {
+		10.0_f32.powf(level / 20.0)
+	}
+
+	/// Get the level in decibels the given compressor settles a constant signal at the given level to.
+	fn settled_level(compressor:Compressor, level:f32) -> f32 {
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![vec![amplitude(level); 8000]], 8000);
+		buffer.add_effect(compressor);
+		20.0 * buffer.processed_data()[0][7999].log10()
+	}
+
+
+
+	/* GAIN CURVE TESTS */
+
+	#[test]
+	fn test_effect_compressor_ratio() {
+		for (level, expected_level) in [(-30.0, -30.0), (-20.0, -20.0), (-12.0, -18.0), (0.0, -15.0)] {
+			assert!((settled_level(Compressor::new(-20.0, 4.0).with_knee(0.0), level) - expected_level).abs() < 0.01);
+		}
+	}
+
+	#[test]
+	fn test_effect_compressor_knee() {
+
+		// Halfway into the knee, the reduction is an eighth of the knee width times the slope.
+		assert!((settled_level(Compressor::new(-20.0, 4.0).with_knee(12.0), -20.0) - (-20.0 - 0.75 * 12.0 / 8.0)).abs() < 0.01);
+		assert!((settled_level(Compressor::new(-20.0, 4.0).with_knee(12.0), -26.0) + 26.0).abs() < 0.01);
+		assert!((settled_level(Compressor::new(-20.0, 4.0).with_knee(12.0), -14.0) + 18.5).abs() < 0.01);
+	}
+
+	#[test]
+	fn test_effect_compressor_makeup_gain() {
+		let samples:Vec<f32> = (0..100).map(|index| (index as f32 * 0.3).sin() * 0.01).collect();
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 8000);
+		buffer.add_effect(Compressor::new(-20.0, 4.0).with_makeup_gain(6.0));
+		for (output, input) in buffer.processed_data()[0].iter().zip(&samples) {
+			assert!((output - input * amplitude(6.0)).abs() < 0.00001);
+		}
+	}
+
+	#[test]
+	fn test_effect_compressor_rms_detection() {
+
+		// The RMS level of a sine is 3dB below its peak, so less of it is above the threshold.
+		let samples:Vec<f32> = (0..16000).map(|index| (index as f32 / 16000.0 * 1000.0 * TAU).sin() * amplitude(-6.0)).collect();
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples], 16000);
+		buffer.add_effect(Compressor::new(-20.0, 4.0).with_knee(0.0).with_rms_detection(true));
+		buffer.take(16000);
+		let reduction:f32 = buffer.get_effect_setting("compressor", "gain_reduction").unwrap();
+		let expected_reduction:f32 = (-6.0 - 10.0 * 2.0_f32.log10() + 20.0) * 0.75;
+		assert!((reduction - expected_reduction).abs() < 0.3);
+	}
+
+
+
+	/* TIMING TESTS */
+
+	#[test]
+	fn test_effect_compressor_attack_release() {
+		let mut samples:Vec<f32> = vec![amplitude(0.0); 8000];
+		samples.extend(vec![amplitude(-40.0); 8000]);
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples], 8000);
+		buffer.add_effect(Compressor::new(-20.0, 4.0).with_knee(0.0).with_attack(10.0).with_release(100.0));
+
+		// After one attack time, the reduction is about 63% of the way to the target of 15dB.
+		buffer.take(80);
+		let reduction = |buffer:&AudioBuffer| buffer.get_effect_setting("compressor", "gain_reduction").unwrap();
+		assert!((reduction(&buffer) - 15.0 * 0.632).abs() < 0.1);
+		buffer.take(7920);
+		assert!((reduction(&buffer) - 15.0).abs() < 0.01);
+
+		// After one release time, the reduction has fallen by about 63%.
+		buffer.take(800);
+		assert!((reduction(&buffer) - 15.0 * 0.368).abs() < 0.1);
+	}
+
+	#[test]
+	fn test_effect_compressor_channel_link() {
+		let loud:Vec<f32> = vec![amplitude(0.0); 8000];
+		let quiet:Vec<f32> = vec![amplitude(-30.0); 8000];
+		for (channel_link, expected_quiet_level) in [(1.0, -45.0), (0.0, -30.0)] {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![loud.clone(), quiet.clone()], 8000);
+			buffer.add_effect(Compressor::new(-20.0, 4.0).with_knee(0.0).with_channel_link(channel_link));
+			let data:&Vec<Vec<f32>> = buffer.processed_data();
+			assert!((20.0 * data[0][7999].log10() + 15.0).abs() < 0.01);
+			assert!((20.0 * data[1][7999].log10() - expected_quiet_level).abs() < 0.01);
+		}
+	}
+
+	#[test]
+	fn test_effect_compressor_settings() {
+		let mut compressor:Compressor = Compressor::new(-20.0, 4.0);
+		assert_eq!(compressor.gain_reduction(), 0.0);
+		compressor.settings_test();
+	}
+}
\ No newline at end of file
@@ -1,4 +1,5 @@
-use crate::{ audio_effect::create_effect_id, AudioEffect };
+use crate::{ audio_effect::create_effect_id, AudioEffect, ResampleQuality };
+use super::sinc_resampler::SincResampler;
 use std::any::Any;
 
 
@@ -7,7 +8,8 @@ use std::any::Any;
 pub struct DurationModifier {
 	id:usize,
 	target_sample_rate:Option<f32>,
-	duration_multiplier:f32
+	duration_multiplier:f32,
+	resampler:SincResampler
 }
 impl DurationModifier {
 
@@ -16,7 +18,8 @@ impl DurationModifier {
 		DurationModifier {
 			id: create_effect_id(),
 			target_sample_rate: None,
-			duration_multiplier: multiplier
+			duration_multiplier: multiplier,
+			resampler: SincResampler::new(ResampleQuality::default())
 		}
 	}
 
@@ -25,9 +28,21 @@ impl DurationModifier {
 		DurationModifier {
 			id: create_effect_id(),
 			target_sample_rate: Some(sample_rate as f32),
-			duration_multiplier: 1.0
+			duration_multiplier: 1.0,
+			resampler: SincResampler::new(ResampleQuality::default())
 		}
 	}
+
+	/// Return self with the given resample quality. Defaults to `ResampleQuality::Medium`.
+	pub fn with_quality(mut self, quality:ResampleQuality) -> Self {
+		self.resampler = SincResampler::new(quality);
+		self
+	}
+
+	/// Get the resample quality.
+	pub fn quality(&self) -> ResampleQuality {
+		self.resampler.quality()
+	}
 }
 impl AudioEffect for DurationModifier {
 
@@ -47,13 +62,20 @@ impl AudioEffect for DurationModifier {
 	fn sample_multiplier(&self, sample_rate:u32, _channel_count:usize) -> f32 {
 		(1.0 / sample_rate as f32 * self.target_sample_rate.unwrap_or(sample_rate as f32) as f32) * self.duration_multiplier
 	}
+
+	/// Get the amount of samples the output lags behind the input.
+	fn latency(&self, sample_rate:u32, channel_count:usize) -> usize {
+		let multiplier:f32 = self.sample_multiplier(sample_rate, channel_count).abs();
+		if multiplier == 1.0 { 0 } else { self.resampler.latency(multiplier) }
+	}
 	
 	/// Clone the effect into a box.
 	fn boxed(&self) -> Box<dyn AudioEffect> {
 		Box::new(DurationModifier {
 			id: create_effect_id(),
 			target_sample_rate: self.target_sample_rate.clone(),
-			duration_multiplier: self.duration_multiplier
+			duration_multiplier: self.duration_multiplier,
+			resampler: SincResampler::new(self.resampler.quality())
 		})
 	}
 
@@ -81,24 +103,8 @@ impl AudioEffect for DurationModifier {
 			return;
 		}
 
-		// Calculate how much to increment the source index per each incrementation of the target index.
-		let source_sample_count:f32 = data[0].len() as f32;
-		let target_sample_count:f32 = (source_sample_count * multiplier).floor();
-		let source_index_increment:f32 = 1.0 / multiplier;
-		let source_index_max:usize = source_sample_count as usize - 1;
-
-		// For each new sample, calculate a new sample based on the progress between samples in the source.
-		let mut new_data:Vec<Vec<f32>> = vec![Vec::with_capacity(target_sample_count as usize); *channel_count];
-		let mut source_index:f32 = 0.0;
-		while source_index < source_sample_count {
-			let source_index_left:usize = (source_index.floor() as usize).min(source_index_max);
-			let source_index_right:usize = (source_index_left + 1).min(source_index_max);
-			let source_index_fact:f32 = source_index % 1.0;
-			for channel_index in 0..*channel_count {
-				new_data[channel_index].push(data[channel_index][source_index_left] + (data[channel_index][source_index_right] - data[channel_index][source_index_left]) * source_index_fact);
-			}
-			source_index += source_index_increment;
-		}
+		// Resample data. The resampler keeps the input it still needs, so the next call continues seamlessly.
+		let new_data:Vec<Vec<f32>> = self.resampler.process(data, multiplier);
 
 		// Set new data.
 		if let Some(rate) = self.target_sample_rate {
@@ -107,6 +113,11 @@ impl AudioEffect for DurationModifier {
 		*data = new_data;
 	}
 
+	/// Forget the audio processed so far, so the next output does not depend on it.
+	fn reset(&mut self) {
+		self.resampler.reset();
+	}
+
 
 
 	/* SETTING METHODS */
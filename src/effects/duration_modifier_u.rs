@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
-	use crate::{ AudioBuffer, AudioEffect, DurationModifier };
+	use crate::{ AudioBuffer, AudioEffect, AudioGenerator, DurationModifier, ResampleQuality };
+	use super::super::test_signals::sine;
 
 	
 
@@ -11,7 +12,7 @@ mod tests {
 		let saw_shape:Vec<f32> = (0..10).map(|index| index as f32 / 10.0).collect();
 		
 		let mut buffer:AudioBuffer = AudioBuffer::new(vec![saw_shape.clone()], 10);
-		buffer.multiply_duration(0.5);
+		buffer.add_effect(DurationModifier::new(0.5).with_quality(ResampleQuality::Linear));
 		assert_eq!(&buffer.processed_data()[0], &(0..5).map(|index| saw_shape[index * 2]).collect::<Vec<f32>>());
 	}
 
@@ -20,7 +21,7 @@ mod tests {
 		let saw_shape:Vec<f32> = (0..10).map(|index| index as f32 / 10.0).collect();
 		
 		let mut buffer:AudioBuffer = AudioBuffer::new(vec![saw_shape.clone()], 10);
-		buffer.multiply_duration(2.0);
+		buffer.add_effect(DurationModifier::new(2.0).with_quality(ResampleQuality::Linear));
 		assert_eq!(buffer.processed_data()[0].len(), 20);
 		for (left, right) in  buffer.processed_data()[0].iter().zip(&(0..20).map(|index| index as f32 * 0.05).collect::<Vec<f32>>()) {
 			println!("{left}, {right}");
@@ -33,8 +34,8 @@ mod tests {
 		let saw_shape:Vec<f32> = (0..10).map(|index| index as f32 / 10.0).collect();
 		
 		let mut buffer:AudioBuffer = AudioBuffer::new(vec![saw_shape.clone()], 10);
-		buffer.multiply_duration(2.0);
-		buffer.multiply_duration(2.0);
+		buffer.add_effect(DurationModifier::new(2.0).with_quality(ResampleQuality::Linear));
+		buffer.add_effect(DurationModifier::new(2.0).with_quality(ResampleQuality::Linear));
 		assert_eq!(buffer.processed_data()[0].len(), 40);
 		for (left, right) in  buffer.processed_data()[0].iter().zip(&(0..40).map(|index| index as f32 * 0.025).collect::<Vec<f32>>()) {
 			println!("{left}, {right}");
@@ -51,7 +52,7 @@ mod tests {
 		let saw_shape:Vec<f32> = (0..10).map(|index| index as f32 / 10.0).collect();
 		
 		let mut buffer:AudioBuffer = AudioBuffer::new(vec![saw_shape.clone()], 10);
-		buffer.resample_sample_rate(5);
+		buffer.add_effect(DurationModifier::new_sample_rate_modifier(5).with_quality(ResampleQuality::Linear));
 		assert_eq!(&buffer.processed_data()[0], &(0..5).map(|index| saw_shape[index * 2]).collect::<Vec<f32>>());
 		assert_eq!(buffer.sample_rate(), 5);
 	}
@@ -62,7 +63,7 @@ mod tests {
 		let stretched_saw_shape:Vec<f32> = (0..20).map(|index| index as f32 / 20.0).collect();
 		
 		let mut buffer:AudioBuffer = AudioBuffer::new(vec![saw_shape.clone()], 10);
-		buffer.resample_sample_rate(20);
+		buffer.add_effect(DurationModifier::new_sample_rate_modifier(20).with_quality(ResampleQuality::Linear));
 		for (left, right) in  buffer.processed_data()[0].iter().zip(&stretched_saw_shape) {
 			println!("{left}, {right}");
 			assert!((*left - *right) < 0.01);
@@ -106,6 +107,68 @@ mod tests {
 
 
 
+	/* QUALITY TESTS */
+
+	/// Get the largest difference between the given data and the expected data, ignoring the edges.
+	fn max_error(data:&[f32], expected:&[f32]) -> f32 {
+		data.iter().zip(expected).skip(100).take(data.len() - 200).map(|(left, right)| (left - right).abs()).fold(0.0, f32::max)
+	}
+
+	/// Get the root mean square of the given data, ignoring the edges.
+	fn rms(data:&[f32]) -> f32 {
+		let data:&[f32] = &data[100..data.len() - 100];
+		(data.iter().map(|sample| sample * sample).sum::<f32>() / data.len() as f32).sqrt()
+	}
+
+	#[test]
+	fn test_effect_sample_rate_sine_accuracy() {
+		let expected:Vec<f32> = sine(10000.0, 0.5, 48000, 4800);
+		let mut errors:Vec<f32> = Vec::new();
+		for quality in [ResampleQuality::Linear, ResampleQuality::Low, ResampleQuality::Medium, ResampleQuality::High] {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(10000.0, 0.5, 44100, 4410)], 44100);
+			buffer.add_effect(DurationModifier::new_sample_rate_modifier(48000).with_quality(quality));
+			assert_eq!(buffer.processed_data()[0].len(), 4800);
+			errors.push(max_error(&buffer.raw_data()[0], &expected));
+		}
+		println!("{errors:?}");
+		assert!(errors[0] > 0.05);
+		assert!(errors[2] < 0.001);
+		assert!(errors[3] < errors[2] && errors[2] < errors[1] && errors[1] < errors[0]);
+	}
+
+	#[test]
+	fn test_effect_sample_rate_aliasing() {
+
+		// A tone above the nyquist frequency of the target sample rate should be filtered out instead of folding back.
+		let mut linear_buffer:AudioBuffer = AudioBuffer::new(vec![sine(15000.0, 0.5, 48000, 4800)], 48000);
+		linear_buffer.add_effect(DurationModifier::new_sample_rate_modifier(22050).with_quality(ResampleQuality::Linear));
+		let mut sinc_buffer:AudioBuffer = AudioBuffer::new(vec![sine(15000.0, 0.5, 48000, 4800)], 48000);
+		sinc_buffer.resample_sample_rate(22050);
+		assert!(rms(&linear_buffer.processed_data()[0]) > 0.1);
+		assert!(rms(&sinc_buffer.processed_data()[0]) < 0.001);
+	}
+
+	#[test]
+	fn test_effect_sample_rate_chunked() {
+		let mut whole_buffer:AudioBuffer = AudioBuffer::new(vec![sine(1000.0, 0.5, 44100, 4410)], 44100);
+		whole_buffer.resample_sample_rate(48000);
+		let whole:Vec<f32> = whole_buffer.processed_data()[0].clone();
+
+		// Taking the buffer in small chunks should produce the same samples without seams.
+		let mut chunked_buffer:AudioBuffer = AudioBuffer::new(vec![sine(1000.0, 0.5, 44100, 4410)], 44100);
+		chunked_buffer.resample_sample_rate(48000);
+		let mut chunked:Vec<f32> = Vec::new();
+		while !chunked_buffer.is_depleted() {
+			chunked.extend(chunked_buffer.take(101).remove(0));
+		}
+		assert_eq!(chunked.len(), whole.len());
+		for (left, right) in whole.iter().zip(&chunked) {
+			assert!((left - right).abs() < 0.00001);
+		}
+	}
+
+
+
 	/* SETTINGS */
 	
 	#[test]
@@ -0,0 +1,52 @@
+use std::f64::consts::TAU;
+
+
+
+/// Transform the given complex data in place using a radix-2 fast fourier transform. The length of the data must be a power of two. The inverse transform is scaled by one over the length, so a forward and inverse transform return the original data.
+pub(crate) fn fft(real:&mut [f32], imaginary:&mut [f32], inverse:bool) {
+	let len:usize = real.len();
+	assert!(len.is_power_of_two() && imaginary.len() == len, "FFT data length must be a power of two.");
+
+	// Reorder data by bit-reversed index.
+	let mut reversed_index:usize = 0;
+	for index in 1..len {
+		let mut bit:usize = len >> 1;
+		while reversed_index & bit != 0 {
+			reversed_index ^= bit;
+			bit >>= 1;
+		}
+		reversed_index |= bit;
+		if index < reversed_index {
+			real.swap(index, reversed_index);
+			imaginary.swap(index, reversed_index);
+		}
+	}
+
+	// Combine transforms of increasing size.
+	let mut size:usize = 2;
+	while size <= len {
+		let angle:f64 = if inverse { TAU } else { -TAU } / size as f64;
+		let (step_real, step_imaginary):(f64, f64) = (angle.cos(), angle.sin());
+		for start in (0..len).step_by(size) {
+			let (mut twiddle_real, mut twiddle_imaginary):(f64, f64) = (1.0, 0.0);
+			for offset in 0..size / 2 {
+				let left:usize = start + offset;
+				let right:usize = left + size / 2;
+				let product_real:f32 = real[right] * twiddle_real as f32 - imaginary[right] * twiddle_imaginary as f32;
+				let product_imaginary:f32 = real[right] * twiddle_imaginary as f32 + imaginary[right] * twiddle_real as f32;
+				real[right] = real[left] - product_real;
+				imaginary[right] = imaginary[left] - product_imaginary;
+				real[left] += product_real;
+				imaginary[left] += product_imaginary;
+				(twiddle_real, twiddle_imaginary) = (twiddle_real * step_real - twiddle_imaginary * step_imaginary, twiddle_real * step_imaginary + twiddle_imaginary * step_real);
+			}
+		}
+		size <<= 1;
+	}
+
+	// Scale inverse transform.
+	if inverse {
+		let scale:f32 = 1.0 / len as f32;
+		real.iter_mut().chain(imaginary.iter_mut()).for_each(|value| *value *= scale);
+	}
+}
\ No newline at end of file
@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod tests {
+	use super::super::fft::fft;
+	use std::f32::consts::TAU;
+
+
+
+	#[test]
+	fn test_fft_matches_dft() {
+		let input:Vec<f32> = (0..64).map(|index| (index as f32 * 0.37).sin() + (index % 5) as f32 * 0.1).collect();
+		let mut real:Vec<f32> = input.clone();
+		let mut imaginary:Vec<f32> = vec![0.0; 64];
+		fft(&mut real, &mut imaginary, false);
+		for bin in 0..64 {
+			let dft_real:f32 = input.iter().enumerate().map(|(index, sample)| sample * (TAU * bin as f32 * index as f32 / 64.0).cos()).sum();
+			let dft_imaginary:f32 = input.iter().enumerate().map(|(index, sample)| -sample * (TAU * bin as f32 * index as f32 / 64.0).sin()).sum();
+			assert!((real[bin] - dft_real).abs() < 0.001);
+			assert!((imaginary[bin] - dft_imaginary).abs() < 0.001);
+		}
+	}
+
+	#[test]
+	fn test_fft_round_trip() {
+		let input:Vec<f32> = (0..256).map(|index| (index as f32 * 0.11).cos()).collect();
+		let mut real:Vec<f32> = input.clone();
+		let mut imaginary:Vec<f32> = vec![0.0; 256];
+		fft(&mut real, &mut imaginary, false);
+		fft(&mut real, &mut imaginary, true);
+		for (left, right) in real.iter().zip(&input) {
+			assert!((left - right).abs() < 0.0001);
+		}
+		assert!(imaginary.iter().all(|value| value.abs() < 0.0001));
+	}
+}
\ No newline at end of file
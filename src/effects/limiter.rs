@@ -0,0 +1,273 @@
+use crate::{ audio_effect::create_effect_id, AudioEffect };
+use super::true_peak_detector::TruePeakDetector;
+use std::{ any::Any, collections::VecDeque };
+
+
+
+#[derive(PartialEq)]
+pub struct Limiter {
+	id:usize,
+	ceiling:f32,
+	look_ahead:f32,
+	release:f32,
+	true_peak:f32,
+
+	configuration:Option<(u32, usize)>,
+	delay_configuration:(usize, bool),
+	delay:Vec<VecDeque<f32>>,
+	true_peak_detector:Option<TruePeakDetector>,
+	sample_index:usize,
+	gain_window:VecDeque<(usize, f32)>,
+	hold_window:VecDeque<f32>,
+	hold_sum:f64,
+	gain:f32,
+	skip_len:usize,
+	gain_reduction:f32
+}
+impl Limiter {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new limiter that keeps the peaks of the audio below the given ceiling in decibels. Looks ahead 5ms, releases in 50ms and detects sample peaks.
+	pub fn new(ceiling:f32) -> Limiter {
+		Limiter {
+			id: create_effect_id(),
+			ceiling,
+			look_ahead: 5.0,
+			release: 50.0,
+			true_peak: 0.0,
+
+			configuration: None,
+			delay_configuration: (0, false),
+			delay: Vec::new(),
+			true_peak_detector: None,
+			sample_index: 0,
+			gain_window: VecDeque::new(),
+			hold_window: VecDeque::new(),
+			hold_sum: 0.0,
+			gain: 1.0,
+			skip_len: 0,
+			gain_reduction: 0.0
+		}
+	}
+
+
+
+	/* BUILDER METHODS */
+
+	/// Return self with the given look-ahead time in milliseconds. The gain is lowered gradually over this time before a peak arrives, delaying the audio by the same time.
+	pub fn with_look_ahead(mut self, look_ahead:f32) -> Self {
+		self.look_ahead = look_ahead;
+		self
+	}
+
+	/// Return self with the given release time in milliseconds. This is how fast the gain recovers after a peak.
+	pub fn with_release(mut self, release:f32) -> Self {
+		self.release = release;
+		self
+	}
+
+	/// Return self with true peak detection enabled or disabled. True peak detection oversamples the audio 4 times to find peaks between samples, which can still clip after conversion to analog or lossy encoding. Adds a few samples of latency. Disabled by default.
+	pub fn with_true_peak(mut self, true_peak:bool) -> Self {
+		self.true_peak = if true_peak { 1.0 } else { 0.0 };
+		self
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the current gain reduction in decibels, for metering.
+	pub fn gain_reduction(&self) -> f32 {
+		self.gain_reduction
+	}
+
+	/// Get the amount of samples of look-ahead and the amount of samples true peak detection lags behind the input for the given sample rate.
+	fn delay_lengths(&self, sample_rate:u32) -> (usize, usize) {
+		let look_ahead_len:usize = (self.look_ahead.max(0.0) * 0.001 * sample_rate as f32).round() as usize;
+		(look_ahead_len, if self.true_peak != 0.0 { TruePeakDetector::latency() } else { 0 })
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Reset the state for the given sample rate and channel count.
+	fn initialize(&mut self, sample_rate:u32, channel_count:usize) {
+		let (look_ahead_len, true_peak_len) = self.delay_lengths(sample_rate);
+		self.configuration = Some((sample_rate, channel_count));
+		self.delay_configuration = (look_ahead_len, self.true_peak != 0.0);
+
+		// Start with silence in the delay, the output of that silence is skipped.
+		self.delay = vec![vec![0.0; look_ahead_len + true_peak_len].into(); channel_count];
+		self.true_peak_detector = if self.true_peak != 0.0 { Some(TruePeakDetector::new(channel_count)) } else { None };
+		self.sample_index = 0;
+		self.gain_window = VecDeque::new();
+		self.hold_window = vec![1.0; look_ahead_len.max(1)].into();
+		self.hold_sum = look_ahead_len.max(1) as f64;
+		self.gain = 1.0;
+		self.skip_len = look_ahead_len + true_peak_len;
+	}
+
+	/// Change the length of the delay for a new look-ahead or true peak detection, keeping the delayed samples. A longer delay is filled with silence of which the output is skipped. Samples that no longer fit a shorter delay are added to the given output right away.
+	fn resize_delay(&mut self, sample_rate:u32, ceiling:f32, output:&mut [Vec<f32>]) {
+		let (look_ahead_len, true_peak_len) = self.delay_lengths(sample_rate);
+		self.delay_configuration = (look_ahead_len, self.true_peak != 0.0);
+		let delay_len:usize = look_ahead_len + true_peak_len;
+		let current_delay_len:usize = self.delay.first().map(|delay| delay.len()).unwrap_or(0);
+		for (output_channel, delay) in output.iter_mut().zip(&mut self.delay) {
+			for _ in current_delay_len..delay_len {
+				delay.push_front(0.0);
+			}
+			for _ in delay_len..current_delay_len {
+				let sample:f32 = delay.pop_front().unwrap_or(0.0);
+				output_channel.push(if self.gain == 1.0 { sample } else { (sample * self.gain).clamp(-ceiling, ceiling) });
+			}
+		}
+		self.skip_len += delay_len.saturating_sub(current_delay_len);
+
+		// Update the detection to the new delay.
+		if (self.true_peak != 0.0) != self.true_peak_detector.is_some() {
+			self.true_peak_detector = if self.true_peak != 0.0 { Some(TruePeakDetector::new(self.delay.len())) } else { None };
+		}
+		while self.hold_window.len() < look_ahead_len.max(1) {
+			self.hold_window.push_front(self.gain);
+			self.hold_sum += self.gain as f64;
+		}
+	}
+}
+impl AudioEffect for Limiter {
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the ID of the effect.
+	fn id(&self) -> usize {
+		self.id
+	}
+
+	/// Get the name of the effect.
+	fn name(&self) -> &str {
+		"limiter"
+	}
+
+	/// Get the amount of samples the output lags behind the input.
+	fn latency(&self, sample_rate:u32, _channel_count:usize) -> usize {
+		let (look_ahead_len, true_peak_len) = self.delay_lengths(sample_rate);
+		look_ahead_len + true_peak_len
+	}
+
+	/// Clone the effect into a box.
+	fn boxed(&self) -> Box<dyn AudioEffect> {
+		let mut limiter:Limiter = Limiter::new(self.ceiling);
+		limiter.look_ahead = self.look_ahead;
+		limiter.release = self.release;
+		limiter.true_peak = self.true_peak;
+		Box::new(limiter)
+	}
+
+	/// Allow downcasting.
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Apply the effect to the given buffer.
+	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, _channel_count:&mut usize) {
+		if data.is_empty() {
+			return;
+		}
+		let (look_ahead_len, _) = self.delay_lengths(*sample_rate);
+		if self.configuration != Some((*sample_rate, data.len())) {
+			self.initialize(*sample_rate, data.len());
+		}
+		let ceiling:f32 = 10.0_f32.powf(self.ceiling / 20.0);
+		let release_coefficient:f32 = if self.release > 0.0 { (-1.0 / (self.release * 0.001 * *sample_rate as f32)).exp() } else { 0.0 };
+		let hold_len:usize = look_ahead_len.max(1);
+		let mut output:Vec<Vec<f32>> = vec![Vec::with_capacity(data[0].len()); data.len()];
+		if self.delay_configuration != (look_ahead_len, self.true_peak != 0.0) {
+			self.resize_delay(*sample_rate, ceiling, &mut output);
+		}
+
+		// Loop through data.
+		for sample_index in 0..data[0].len() {
+			for (channel, delay) in data.iter().zip(&mut self.delay) {
+				delay.push_back(channel[sample_index]);
+			}
+
+			// Keep the lowest gain required by any peak within the look-ahead.
+			let peak:f32 = match &mut self.true_peak_detector {
+				Some(detector) => detector.push(data.iter().map(|channel| channel[sample_index])),
+				None => data.iter().fold(0.0, |peak:f32, channel| peak.max(channel[sample_index].abs()))
+			};
+			let required_gain:f32 = if peak > ceiling { ceiling / peak } else { 1.0 };
+			while self.gain_window.back().map(|(_, gain)| *gain >= required_gain).unwrap_or(false) {
+				self.gain_window.pop_back();
+			}
+			self.gain_window.push_back((self.sample_index, required_gain));
+			while self.gain_window.front().map(|(index, _)| index + look_ahead_len < self.sample_index).unwrap_or(false) {
+				self.gain_window.pop_front();
+			}
+			self.sample_index += 1;
+
+			// Average the held gain over the look-ahead, so the gain moves smoothly and reaches the required gain when the peak comes out. Recover slowly after the peak.
+			let held_gain:f32 = self.gain_window.front().map(|(_, gain)| *gain).unwrap_or(1.0);
+			self.hold_window.push_back(held_gain);
+			self.hold_sum += held_gain as f64;
+			while self.hold_window.len() > hold_len {
+				self.hold_sum -= self.hold_window.pop_front().unwrap_or(1.0) as f64;
+			}
+			let target_gain:f32 = (self.hold_sum / hold_len as f64) as f32;
+			self.gain = if target_gain < self.gain { target_gain } else { target_gain + (self.gain - target_gain) * release_coefficient };
+
+			// Output the delayed samples.
+			for (output_channel, delay) in output.iter_mut().zip(&mut self.delay) {
+				let sample:f32 = delay.pop_front().unwrap_or(0.0);
+				output_channel.push(if self.gain == 1.0 { sample } else { (sample * self.gain).clamp(-ceiling, ceiling) });
+			}
+		}
+		self.gain_reduction = -20.0 * self.gain.log10();
+
+		// Skip the output of the silence before the first sample.
+		if self.skip_len > 0 {
+			let skip_len:usize = self.skip_len.min(output[0].len());
+			output.iter_mut().for_each(|channel| { channel.drain(..skip_len); });
+			self.skip_len -= skip_len;
+		}
+		*data = output;
+	}
+
+	/// Forget the audio processed so far, so the next output does not depend on it.
+	fn reset(&mut self) {
+		self.configuration = None;
+		self.gain_reduction = 0.0;
+	}
+
+
+
+	/* SETTING METHODS */
+
+	/// Get a list of settings with their names.
+	fn settings(&self) -> Vec<(&str, &f32)> {
+		vec![
+			("ceiling", &self.ceiling),
+			("look_ahead", &self.look_ahead),
+			("release", &self.release),
+			("true_peak", &self.true_peak),
+
+			("gain_reduction", &self.gain_reduction)
+		]
+	}
+
+	/// Get a mutable list of settings with their names.
+	fn settings_mut(&mut self) -> Vec<(&str, &mut f32)> {
+		vec![
+			("ceiling", &mut self.ceiling),
+			("look_ahead", &mut self.look_ahead),
+			("release", &mut self.release),
+			("true_peak", &mut self.true_peak)
+		]
+	}
+}
\ No newline at end of file
@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioEffect, AudioGenerator, Limiter };
+	use super::super::test_signals::sine;
+	use std::f32::consts::{ FRAC_PI_4, TAU };
+
+
+
+	/// Get the highest absolute sample in the given data.
+	fn peak(data:&[f32]) -> f32 {
+		data.iter().fold(0.0, |peak:f32, sample| peak.max(sample.abs()))
+	}
+
+
+
+	/* LEVEL TESTS */
+
+	#[test]
+	fn test_effect_limiter_ceiling() {
+		let mut samples:Vec<f32> = sine(440.0, 0.5, 48000, 24000);
+		samples.extend(sine(440.0, 2.0, 48000, 24000));
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone(), samples], 48000);
+		buffer.add_effect(Limiter::new(-1.0));
+		let data:&Vec<Vec<f32>> = buffer.processed_data();
+		let ceiling:f32 = 10.0_f32.powf(-1.0 / 20.0);
+		for channel in data {
+			assert_eq!(channel.len(), 48000);
+			assert!(peak(channel) <= ceiling);
+			assert!(peak(&channel[40000..]) > ceiling * 0.99);
+		}
+	}
+
+	#[test]
+	fn test_effect_limiter_below_ceiling() {
+		let samples:Vec<f32> = sine(440.0, 0.5, 48000, 4800);
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 48000);
+		buffer.add_effect(Limiter::new(-1.0));
+		assert_eq!(buffer.processed_data()[0], samples);
+	}
+
+	#[test]
+	fn test_effect_limiter_smooth_gain() {
+
+		// The gain lowers gradually before a sudden peak, instead of cutting in at the peak.
+		let mut samples:Vec<f32> = vec![0.5; 2000];
+		samples.extend(vec![1.0; 100]);
+		samples.extend(vec![0.5; 2000]);
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples], 48000);
+		buffer.add_effect(Limiter::new(-6.0206).with_look_ahead(5.0));
+		let data:&[f32] = &buffer.processed_data()[0];
+		assert!(peak(data) <= 0.5);
+		assert!((data[1700] - 0.5).abs() < 0.0001);
+		assert!(data[1880] < 0.5 && data[1880] > 0.25);
+		assert!(data[..2000].windows(2).all(|pair| (pair[1] - pair[0]).abs() < 0.01));
+	}
+
+	#[test]
+	fn test_effect_limiter_true_peak() {
+
+		// A sine at a quarter of the sample rate, sampled at 45 degrees, peaks between the samples.
+		let samples:Vec<f32> = (0..4800).map(|index| (index as f32 * TAU / 4.0 + FRAC_PI_4).sin()).collect();
+		let mut sample_peak_buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 48000);
+		sample_peak_buffer.add_effect(Limiter::new(-1.0));
+		assert!((peak(&sample_peak_buffer.processed_data()[0][2400..]) - FRAC_PI_4.sin()).abs() < 0.001);
+		let mut true_peak_buffer:AudioBuffer = AudioBuffer::new(vec![samples], 48000);
+		true_peak_buffer.add_effect(Limiter::new(-1.0).with_true_peak(true));
+		assert_eq!(true_peak_buffer.processed_data()[0].len(), 4800);
+		assert!((peak(&true_peak_buffer.processed_data()[0][2400..]) - FRAC_PI_4.sin() * 10.0_f32.powf(-1.0 / 20.0)).abs() < 0.02);
+	}
+
+
+
+	/* STREAMING TESTS */
+
+	#[test]
+	fn test_effect_limiter_latency() {
+		assert_eq!(Limiter::new(-1.0).latency(48000, 2), 240);
+		assert_eq!(Limiter::new(-1.0).with_look_ahead(2.0).latency(44100, 2), 88);
+		assert_eq!(Limiter::new(-1.0).with_true_peak(true).latency(48000, 2), 248);
+	}
+
+	#[test]
+	fn test_effect_limiter_streaming() {
+		let samples:Vec<f32> = (0..8000).map(|index| (index as f32 * 0.05).sin() * (index as f32 * 0.001).sin() * 3.0).collect();
+		let mut whole_buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 8000);
+		whole_buffer.add_effect(Limiter::new(-1.0).with_true_peak(true));
+		let whole:Vec<f32> = whole_buffer.processed_data()[0].clone();
+
+		// Taking the buffer in chunks should produce the same samples as processing it at once.
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples], 8000);
+		buffer.add_effect(Limiter::new(-1.0).with_true_peak(true));
+		let mut chunked:Vec<f32> = Vec::new();
+		while !buffer.is_depleted() {
+			chunked.extend(buffer.take(70).remove(0));
+		}
+		assert_eq!(whole.len(), 8000);
+		assert_eq!(whole[..], chunked[..whole.len()]);
+		assert!(buffer.get_effect_setting("limiter", "gain_reduction").unwrap() >= 0.0);
+	}
+
+	#[test]
+	fn test_effect_limiter_live_delay_change() {
+
+		// Changing the look-ahead or true peak detection while streaming keeps all delayed samples.
+		let samples:Vec<f32> = (0..8000).map(|index| (index as f32 * 0.05).sin() * 0.5).collect();
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 8000);
+		buffer.add_effect(Limiter::new(-1.0));
+		let mut chunked:Vec<f32> = Vec::new();
+		for (look_ahead, true_peak) in [(5.0, 0.0), (2.0, 0.0), (8.0, 0.0), (8.0, 1.0), (1.0, 0.0)] {
+			buffer.set_effect_setting("limiter", "look_ahead", look_ahead);
+			buffer.set_effect_setting("limiter", "true_peak", true_peak);
+			chunked.extend(buffer.take(1000).remove(0));
+		}
+		while !buffer.is_depleted() {
+			chunked.extend(buffer.take(1000).remove(0));
+		}
+		assert_eq!(chunked[..8000], samples[..]);
+		assert!(chunked[8000..].iter().all(|sample| *sample == 0.0));
+	}
+
+	#[test]
+	fn test_effect_limiter_settings() {
+		let mut limiter:Limiter = Limiter::new(-1.0);
+		assert_eq!(limiter.gain_reduction(), 0.0);
+		limiter.settings_test();
+	}
+}
\ No newline at end of file
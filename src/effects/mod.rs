@@ -1,16 +1,49 @@
+mod biquad_filter;
+mod biquad_filter_u;
+mod compressor;
+mod compressor_u;
 mod duration_modifier;
 mod duration_modifier_u;
+mod fft;
+mod fft_u;
 mod stereo_shaper;
 mod stereo_shaper_u;
 mod tape_stop;
 mod tape_stop_u;
+mod time_stretcher;
+mod time_stretcher_u;
+mod true_peak_detector;
 mod volume_amplifier;
 mod volume_amplifier_u;
+mod limiter;
+mod limiter_u;
 mod noise_gate;
 mod noise_gate_u;
+mod parametric_eq;
+mod parametric_eq_u;
+mod pitch_shifter;
+mod pitch_shifter_u;
+mod resampler;
+mod resampler_u;
+mod reverb;
+mod reverb_u;
+mod sinc_resampler;
+mod sinc_resampler_u;
+#[cfg(test)]
+pub(crate) mod test_signals;
 
+pub use biquad_filter::{ BiquadFilter, BiquadFilterType };
+pub use compressor::Compressor;
 pub use duration_modifier::DurationModifier;
 pub use stereo_shaper::StereoShaper;
 pub use tape_stop::TapeStop;
+pub use time_stretcher::TimeStretcher;
 pub use volume_amplifier::VolumeAmplifier;
-pub use noise_gate::*;
\ No newline at end of file
+pub(crate) use true_peak_detector::TruePeakDetector;
+pub use limiter::Limiter;
+pub use noise_gate::*;
+pub use parametric_eq::ParametricEq;
+pub use pitch_shifter::PitchShifter;
+pub use resampler::Resampler;
+pub use reverb::Reverb;
+pub use sinc_resampler::ResampleQuality;
\ No newline at end of file
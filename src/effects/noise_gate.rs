@@ -1,28 +1,88 @@
-use crate::{ audio_effect::create_effect_id, AudioEffect };
+use crate::{ audio_effect::create_effect_id, AudioEffect, BiquadFilter };
 use std::any::Any;
 
 
 
+const SIDECHAIN_Q:f32 = 0.707;
+const DETECTOR_RELEASE:f32 = 10.0;
+
+
+
 #[derive(PartialEq)]
 pub struct NoiseGate {
 	id:usize,
-	threshold:f32,
-	acceleration:f32,
-	deceleration:f32,
-	position:f32
+	open_threshold:f32,
+	close_threshold:f32,
+	attack:f32,
+	hold:f32,
+	release:f32,
+	sidechain_frequency:f32,
+
+	sidechain_filter:BiquadFilter,
+	envelope:f32,
+	open:bool,
+	hold_remaining:usize,
+	gain:f32
 }
 impl NoiseGate {
 
-	/// Create a new volume amplifier.
-	pub fn new(threshold:f32, acceleration:f32) -> NoiseGate {
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new noise gate that opens when the level of any channel rises above the threshold. The attack and release in milliseconds are the times it takes to fully open and close.
+	pub fn new(threshold:f32, attack:f32, release:f32) -> NoiseGate {
 		NoiseGate {
 			id: create_effect_id(),
-			threshold,
-			acceleration,
-			deceleration: acceleration,
-			position: 0.0
+			open_threshold: threshold,
+			close_threshold: threshold,
+			attack,
+			hold: 0.0,
+			release,
+			sidechain_frequency: 0.0,
+
+			sidechain_filter: BiquadFilter::new_high_pass(0.0, SIDECHAIN_Q),
+			envelope: 0.0,
+			open: false,
+			hold_remaining: 0,
+			gain: 0.0
 		}
 	}
+
+
+
+	/* BUILDER METHODS */
+
+	/// Return self with the given hold time in milliseconds. The gate stays open this long after the level falls below the close threshold, so short pauses in speech do not close it.
+	pub fn with_hold(mut self, hold:f32) -> Self {
+		self.hold = hold;
+		self
+	}
+
+	/// Return self with the given close threshold. An open gate only closes when the level falls below this threshold, which should be lower than the open threshold to stop the gate from chattering on levels around the threshold.
+	pub fn with_close_threshold(mut self, close_threshold:f32) -> Self {
+		self.close_threshold = close_threshold;
+		self
+	}
+
+	/// Return self with a high-pass filter at the given frequency applied to the audio the level is detected on, so low rumble does not open the gate. The audio itself is not filtered. A frequency of 0 disables the filter.
+	pub fn with_sidechain_high_pass(mut self, frequency:f32) -> Self {
+		self.sidechain_frequency = frequency;
+		self
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the current gain of the gate, from 0 when closed to 1 when open.
+	pub fn gain(&self) -> f32 {
+		self.gain
+	}
+
+	/// Get the amount the gain changes per sample for the given time in milliseconds.
+	fn gain_step(time:f32, sample_rate:u32) -> f32 {
+		let sample_count:f32 = time * 0.001 * sample_rate as f32;
+		if sample_count <= 1.0 { 1.0 } else { 1.0 / sample_count }
+	}
 }
 impl AudioEffect for NoiseGate {
 
@@ -37,16 +97,15 @@ impl AudioEffect for NoiseGate {
 	fn name(&self) -> &str {
 		"noise_gate"
 	}
-	
+
 	/// Clone the effect into a box.
 	fn boxed(&self) -> Box<dyn AudioEffect> {
-		Box::new(NoiseGate {
-			id: create_effect_id(),
-			threshold: self.threshold,
-			acceleration: self.acceleration,
-			deceleration: self.deceleration,
-			position: self.position
-		})
+		Box::new(
+			NoiseGate::new(self.open_threshold, self.attack, self.release)
+				.with_close_threshold(self.close_threshold)
+				.with_hold(self.hold)
+				.with_sidechain_high_pass(self.sidechain_frequency)
+		)
 	}
 
 	/// Allow downcasting.
@@ -59,28 +118,64 @@ impl AudioEffect for NoiseGate {
 	/* USAGE METHODS */
 
 	/// Apply the effect to the given buffer.
-	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, _sample_rate:&mut u32, _channel_count:&mut usize) {
-
-		// Loop through data.
-		for sample_index in 0..data[0].len() {
-			for channel in &mut *data {
-				let sample:&mut f32 = &mut channel[sample_index];
-
-				// Update noice-gate position.
-				if sample.abs() > self.threshold {
-					self.position = (self.position + self.acceleration).min(1.0);
-				} else {
-					self.position = (self.position - self.deceleration).max(0.0);
-				}
+	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, channel_count:&mut usize) {
+		let attack_step:f32 = NoiseGate::gain_step(self.attack, *sample_rate);
+		let release_step:f32 = NoiseGate::gain_step(self.release, *sample_rate);
+		let hold_len:usize = (self.hold.max(0.0) * 0.001 * *sample_rate as f32) as usize;
+		let close_threshold:f32 = self.close_threshold.min(self.open_threshold);
+		let detector_release_coefficient:f32 = (-1.0 / (DETECTOR_RELEASE * 0.001 * *sample_rate as f32)).exp();
+
+		// Detect the level on a filtered copy of the data when using the sidechain filter.
+		let sidechain:Option<Vec<Vec<f32>>> = if self.sidechain_frequency > 0.0 {
+			let mut sidechain:Vec<Vec<f32>> = data.clone();
+			self.sidechain_filter.set_setting("frequency", self.sidechain_frequency);
+			self.sidechain_filter.apply_to(&mut sidechain, &mut sample_rate.clone(), &mut channel_count.clone());
+			Some(sidechain)
+		} else {
+			None
+		};
+		let detector:&Vec<Vec<f32>> = sidechain.as_ref().unwrap_or(data);
+
+		// Update the state of the gate from the level of all channels together.
+		let sample_count:usize = data.first().map(|channel| channel.len()).unwrap_or(0);
+		let mut gains:Vec<f32> = Vec::with_capacity(sample_count);
+		for sample_index in 0..sample_count {
+			// Follow the peaks of the level, so the gate does not close at every zero crossing.
+			let level:f32 = detector.iter().fold(0.0, |level:f32, channel| level.max(channel[sample_index].abs()));
+			self.envelope = if level > self.envelope { level } else { level + (self.envelope - level) * detector_release_coefficient };
+			if self.envelope > self.open_threshold || (self.open && self.envelope >= close_threshold) {
+				self.open = true;
+				self.hold_remaining = hold_len;
+			} else if self.hold_remaining > 0 {
+				self.hold_remaining -= 1;
+			} else {
+				self.open = false;
+			}
+
+			// Move the gain towards open or closed.
+			self.gain = if self.open { (self.gain + attack_step).min(1.0) } else { (self.gain - release_step).max(0.0) };
+			gains.push(self.gain);
+		}
 
-				// Mod sample.
-				if self.position != 1.0 {
-					*sample *= self.position;
+		// Apply gains.
+		for channel in data {
+			for (sample, gain) in channel.iter_mut().zip(&gains) {
+				if *gain != 1.0 {
+					*sample *= gain;
 				}
 			}
 		}
 	}
 
+	/// Forget the audio processed so far, so the next output does not depend on it.
+	fn reset(&mut self) {
+		self.sidechain_filter.reset();
+		self.envelope = 0.0;
+		self.open = false;
+		self.hold_remaining = 0;
+		self.gain = 0.0;
+	}
+
 
 
 	/* SETTING METHODS */
@@ -88,20 +183,26 @@ impl AudioEffect for NoiseGate {
 	/// Get a list of settings with their names.
 	fn settings(&self) -> Vec<(&str, &f32)> {
 		vec![
-			("threshold", &self.threshold),
-			("acceleration", &self.acceleration),
-			("deceleration", &self.deceleration),
-
-			("position", &self.position)
+			("threshold", &self.open_threshold),
+			("close_threshold", &self.close_threshold),
+			("attack", &self.attack),
+			("hold", &self.hold),
+			("release", &self.release),
+			("sidechain_frequency", &self.sidechain_frequency),
+
+			("gain", &self.gain)
 		]
 	}
 
 	/// Get a mutable list of settings with their names.
 	fn settings_mut(&mut self) -> Vec<(&str, &mut f32)> {
 		vec![
-			("threshold", &mut self.threshold),
-			("acceleration", &mut self.acceleration),
-			("deceleration", &mut self.deceleration)
+			("threshold", &mut self.open_threshold),
+			("close_threshold", &mut self.close_threshold),
+			("attack", &mut self.attack),
+			("hold", &mut self.hold),
+			("release", &mut self.release),
+			("sidechain_frequency", &mut self.sidechain_frequency)
 		]
 	}
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
-	use crate::{ AudioBuffer, NoiseGate };
+	use crate::{ AudioBuffer, AudioEffect, NoiseGate };
+	use std::f32::consts::TAU;
 
 
 
@@ -10,7 +11,7 @@ mod tests {
 
 		// Create buffer and apply effects.
 		let mut buffer:AudioBuffer = AudioBuffer::new(vec![square_shape], 10);
-		buffer.add_effect(NoiseGate::new(0.2, 0.05));
+		buffer.add_effect(NoiseGate::new(0.2, 2000.0, 2000.0));
 		buffer.apply_effects();
 		
 		// Validate data.
@@ -30,7 +31,7 @@ mod tests {
 
 		// Create buffer and apply effects.
 		let mut buffer:AudioBuffer = AudioBuffer::new(vec![square_shape], 10);
-		buffer.add_effect(NoiseGate::new(0.2, 0.05));
+		buffer.add_effect(NoiseGate::new(0.2, 2000.0, 2000.0));
 		buffer.apply_effects();
 		
 		// Validate data.
@@ -56,10 +57,97 @@ mod tests {
 
 		// Create buffer and apply effects.
 		let mut buffer:AudioBuffer = AudioBuffer::new(vec![square_shape], 10);
-		buffer.add_effect(NoiseGate::new(0.2, 0.05));
+		buffer.add_effect(NoiseGate::new(0.2, 2000.0, 2000.0));
 		buffer.apply_effects();
 		
 		// Validate data.
 		assert!(buffer.raw_data().iter().flatten().all(|sample| sample.abs() < 0.1));
 	}
+
+	#[test]
+	fn test_effect_noise_gate_time_based() {
+
+		// The gate opens in the same time, regardless of sample rate and channel count.
+		for (sample_rate, channel_count) in [(8000, 1), (48000, 1), (48000, 6)] {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![vec![1.0; sample_rate as usize / 10]; channel_count], sample_rate);
+			buffer.add_effect(NoiseGate::new(0.2, 50.0, 50.0));
+			let data:&Vec<Vec<f32>> = buffer.processed_data();
+			for channel in data {
+				assert!((channel[sample_rate as usize / 40 - 1] - 0.5).abs() < 0.01);
+				assert!((channel[sample_rate as usize / 20 - 1] - 1.0).abs() < 0.01);
+			}
+		}
+	}
+
+	#[test]
+	fn test_effect_noise_gate_linked_channels() {
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![vec![1.0; 100], vec![0.1; 100]], 1000);
+		buffer.add_effect(NoiseGate::new(0.2, 0.0, 0.0));
+		assert_eq!(buffer.processed_data()[1], vec![0.1; 100]);
+	}
+
+	#[test]
+	fn test_effect_noise_gate_hold() {
+		let mut samples:Vec<f32> = vec![1.0; 100];
+		samples.extend(vec![0.1; 100]);
+		let open_len = |hold:f32| {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 1000);
+			buffer.add_effect(NoiseGate::new(0.2, 0.0, 0.0).with_hold(hold));
+			buffer.processed_data()[0].iter().filter(|sample| **sample != 0.0).count()
+		};
+
+		// The level detector releases over a few milliseconds after the level drops.
+		assert!((110..130).contains(&open_len(0.0)));
+		assert_eq!(open_len(50.0), open_len(0.0) + 50);
+	}
+
+	#[test]
+	fn test_effect_noise_gate_steady_sine() {
+
+		// A sine well above the threshold keeps the gate open through its zero crossings.
+		for frequency in [80.0, 440.0] {
+			let samples:Vec<f32> = (0..8000).map(|index| (index as f32 / 8000.0 * frequency * TAU).sin() * 0.5).collect();
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 8000);
+			buffer.add_effect(NoiseGate::new(0.2, 0.0, 5.0));
+			let open_index:usize = samples.iter().position(|sample| *sample > 0.2).unwrap();
+			assert_eq!(buffer.processed_data()[0][open_index..], samples[open_index..]);
+		}
+	}
+
+	#[test]
+	fn test_effect_noise_gate_hysteresis() {
+
+		// A level wavering around the open threshold makes the gate chatter, unless the close threshold is lower.
+		let samples:Vec<f32> = (0..1000).map(|index| 0.2 + (index as f32 * 0.1).sin() * 0.05).collect();
+		let closed_count = |gate:NoiseGate| {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 1000);
+			buffer.add_effect(gate);
+			buffer.processed_data()[0].iter().filter(|sample| **sample == 0.0).count()
+		};
+		assert!(closed_count(NoiseGate::new(0.2, 0.0, 0.0)) > 100);
+		assert_eq!(closed_count(NoiseGate::new(0.2, 0.0, 0.0).with_close_threshold(0.1)), 1);
+	}
+
+	#[test]
+	fn test_effect_noise_gate_sidechain_high_pass() {
+
+		// Low rumble only opens the gate without the sidechain filter.
+		let rumble:Vec<f32> = (0..8000).map(|index| (index as f32 / 8000.0 * 30.0 * TAU).sin() * 0.5).collect();
+		let open_count = |gate:NoiseGate| {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![rumble.clone()], 8000);
+			buffer.add_effect(gate);
+			buffer.processed_data()[0].iter().filter(|sample| **sample != 0.0).count()
+		};
+		assert!(open_count(NoiseGate::new(0.2, 1.0, 1.0)) > 4000);
+		assert_eq!(open_count(NoiseGate::new(0.2, 1.0, 1.0).with_sidechain_high_pass(500.0)), 0);
+	}
+
+	#[test]
+	fn test_effect_noise_gate_settings() {
+		let mut gate:NoiseGate = NoiseGate::new(0.2, 5.0, 50.0);
+		assert_eq!(gate.gain(), 0.0);
+		gate.set_setting("threshold", 0.5);
+		assert_eq!(gate.get_setting("threshold"), Some(0.5));
+		gate.settings_test();
+	}
 }
\ No newline at end of file
@@ -0,0 +1,191 @@
+use crate::{ audio_effect::create_effect_id, AudioEffect, BiquadFilter, BiquadFilterType };
+use std::any::Any;
+
+
+
+const RESPONSE_MIN_FREQUENCY:f32 = 20.0;
+
+
+
+#[derive(PartialEq)]
+struct EqBand {
+	setting_names:[String; 4],
+	enabled:f32,
+	filter:BiquadFilter
+}
+impl EqBand {
+
+	/// Create a new band with setting names for the given band index.
+	fn new(index:usize, enabled:f32, filter:BiquadFilter) -> EqBand {
+		EqBand {
+			setting_names: ["enabled", "frequency", "q", "gain"].map(|setting_name| format!("band_{index}_{setting_name}")),
+			enabled,
+			filter
+		}
+	}
+
+	/// Check if the band is enabled.
+	fn is_enabled(&self) -> bool {
+		self.enabled != 0.0
+	}
+}
+
+
+
+#[derive(PartialEq)]
+pub struct ParametricEq {
+	id:usize,
+	bands:Vec<EqBand>
+}
+impl ParametricEq {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new parametric equalizer without any bands.
+	pub fn new() -> ParametricEq {
+		ParametricEq {
+			id: create_effect_id(),
+			bands: Vec::new()
+		}
+	}
+
+
+
+	/* BUILDER METHODS */
+
+	/// Return self with an enabled band of the given filter type added. The frequency is in Hz and the gain in decibels. The settings of the band are named after its index, like `band_0_frequency`, `band_0_q`, `band_0_gain` and `band_0_enabled`.
+	pub fn with_band(mut self, filter_type:BiquadFilterType, frequency:f32, q:f32, gain:f32) -> Self {
+		self.bands.push(EqBand::new(self.bands.len(), 1.0, BiquadFilter::new(filter_type, frequency, q, gain)));
+		self
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the amount of bands.
+	pub fn band_count(&self) -> usize {
+		self.bands.len()
+	}
+
+	/// Get the filter type of the band at the given index.
+	pub fn band_type(&self, index:usize) -> Option<BiquadFilterType> {
+		self.bands.get(index).map(|band| band.filter.filter_type())
+	}
+
+	/// Check if the band at the given index is enabled.
+	pub fn band_enabled(&self, index:usize) -> bool {
+		self.bands.get(index).map(|band| band.is_enabled()).unwrap_or(false)
+	}
+
+	/// Get the combined gain in decibels of all enabled bands at the given amount of frequencies, spread logarithmically from 20Hz to the nyquist frequency of the given sample rate. Returns a list of frequencies and gains for plotting.
+	pub fn magnitude_response(&self, sample_rate:u32, point_count:usize) -> Vec<(f32, f32)> {
+		let max_frequency:f32 = sample_rate as f32 * 0.5;
+		let frequency_range:f32 = max_frequency / RESPONSE_MIN_FREQUENCY;
+		(0..point_count).map(|point_index| {
+			let position:f32 = if point_count > 1 { point_index as f32 / (point_count - 1) as f32 } else { 0.0 };
+			let frequency:f32 = RESPONSE_MIN_FREQUENCY * frequency_range.powf(position);
+			let gain:f32 = self.bands.iter().filter(|band| band.is_enabled()).map(|band| band.filter.magnitude_response(sample_rate, frequency)).sum();
+			(frequency, gain)
+		}).collect()
+	}
+
+
+
+	/* PROPERTY SETTER METHODS */
+
+	/// Enable or disable the band at the given index. Disabled bands do not affect the audio.
+	pub fn set_band_enabled(&mut self, index:usize, enabled:bool) {
+		if let Some(band) = self.bands.get_mut(index) {
+			band.enabled = if enabled { 1.0 } else { 0.0 };
+		}
+	}
+
+	/// Set the filter type of the band at the given index.
+	pub fn set_band_type(&mut self, index:usize, filter_type:BiquadFilterType) {
+		if let Some(band) = self.bands.get_mut(index) {
+			band.filter.set_filter_type(filter_type);
+		}
+	}
+}
+impl Default for ParametricEq {
+	fn default() -> Self {
+		ParametricEq::new()
+	}
+}
+impl AudioEffect for ParametricEq {
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the ID of the effect.
+	fn id(&self) -> usize {
+		self.id
+	}
+
+	/// Get the name of the effect.
+	fn name(&self) -> &str {
+		"parametric_eq"
+	}
+
+	/// Clone the effect into a box.
+	fn boxed(&self) -> Box<dyn AudioEffect> {
+		Box::new(ParametricEq {
+			id: create_effect_id(),
+			bands: self.bands.iter().enumerate().map(|(index, band)| EqBand::new(index, band.enabled, band.filter.settings_copy())).collect()
+		})
+	}
+
+	/// Allow downcasting.
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Apply the effect to the given buffer.
+	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, channel_count:&mut usize) {
+		for band in &mut self.bands {
+			if band.is_enabled() {
+				band.filter.apply_to(data, sample_rate, channel_count);
+			}
+		}
+	}
+
+	/// Forget the audio processed so far, so the next output does not depend on it.
+	fn reset(&mut self) {
+		for band in &mut self.bands {
+			band.filter.reset();
+		}
+	}
+
+
+
+	/* SETTING METHODS */
+
+	/// Get a list of settings with their names.
+	fn settings(&self) -> Vec<(&str, &f32)> {
+		let mut settings:Vec<(&str, &f32)> = Vec::new();
+		for band in &self.bands {
+			settings.push((&band.setting_names[0], &band.enabled));
+			for ((_, value), setting_name) in band.filter.settings().into_iter().zip(&band.setting_names[1..]) {
+				settings.push((setting_name, value));
+			}
+		}
+		settings
+	}
+
+	/// Get a mutable list of settings with their names.
+	fn settings_mut(&mut self) -> Vec<(&str, &mut f32)> {
+		let mut settings:Vec<(&str, &mut f32)> = Vec::new();
+		for band in &mut self.bands {
+			let EqBand { setting_names, enabled, filter } = band;
+			settings.push((&setting_names[0], enabled));
+			for ((_, value), setting_name) in filter.settings_mut().into_iter().zip(&setting_names[1..]) {
+				settings.push((setting_name, value));
+			}
+		}
+		settings
+	}
+}
\ No newline at end of file
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioEffect, BiquadFilterType, ParametricEq };
+	use std::f32::consts::TAU;
+
+
+
+	/// Create a buffer with a sine of the given frequency and the given equalizer.
+	fn sine_buffer(eq:ParametricEq, frequency:f32) -> AudioBuffer {
+		let samples:Vec<f32> = (0..48000).map(|index| (index as f32 / 48000.0 * frequency * TAU).sin() * 0.5).collect();
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples], 48000);
+		buffer.add_effect(eq);
+		buffer
+	}
+
+	/// Get the gain in decibels the effects of the given sine buffer apply, measured after the filters settled.
+	fn buffer_gain(buffer:&mut AudioBuffer) -> f32 {
+		let data:&[f32] = &buffer.processed_data()[0][24000..];
+		let rms:f32 = (data.iter().map(|sample| sample * sample).sum::<f32>() / data.len() as f32).sqrt();
+		20.0 * (rms / (0.5 / 2.0_f32.sqrt())).log10()
+	}
+
+	/// Get the gain in decibels the given equalizer applies to a sine of the given frequency.
+	fn eq_gain(eq:ParametricEq, frequency:f32) -> f32 {
+		buffer_gain(&mut sine_buffer(eq, frequency))
+	}
+
+	/// Create an equalizer with a low shelf and a peaking band.
+	fn two_band_eq() -> ParametricEq {
+		ParametricEq::new()
+			.with_band(BiquadFilterType::LowShelf, 100.0, 0.707, -6.0)
+			.with_band(BiquadFilterType::Peaking, 3000.0, 1.0, 4.0)
+	}
+
+
+
+	#[test]
+	fn test_effect_parametric_eq_bands() {
+		assert!((eq_gain(two_band_eq(), 20.0) + 6.0).abs() < 0.1);
+		assert!((eq_gain(two_band_eq(), 3000.0) - 4.0).abs() < 0.1);
+		assert!(eq_gain(two_band_eq(), 20000.0).abs() < 0.1);
+	}
+
+	#[test]
+	fn test_effect_parametric_eq_disabled_band() {
+		let mut eq:ParametricEq = two_band_eq();
+		eq.set_band_enabled(1, false);
+		assert!(!eq.band_enabled(1));
+		assert!(eq_gain(eq, 3000.0).abs() < 0.1);
+	}
+
+	#[test]
+	fn test_effect_parametric_eq_band_settings() {
+		let mut eq:ParametricEq = two_band_eq();
+		assert_eq!(eq.get_setting("band_1_frequency"), Some(3000.0));
+		eq.set_setting("band_0_enabled", 0.0);
+		assert!(!eq.band_enabled(0));
+
+		// Settings of bands can be changed through the buffer.
+		let mut buffer:AudioBuffer = sine_buffer(eq, 3000.0);
+		buffer.set_effect_setting("parametric_eq", "band_1_gain", -12.0);
+		assert!((buffer_gain(&mut buffer) + 12.0).abs() < 0.1);
+	}
+
+	#[test]
+	fn test_effect_parametric_eq_magnitude_response() {
+		let response:Vec<(f32, f32)> = two_band_eq().magnitude_response(48000, 200);
+		assert_eq!(response.len(), 200);
+		assert!((response[0].0 - 20.0).abs() < 0.001);
+		assert!((response[199].0 - 24000.0).abs() < 0.1);
+		assert!(response.windows(2).all(|pair| pair[1].0 > pair[0].0));
+		assert!((response[0].1 + 6.0).abs() < 0.1);
+
+		// The highest point is at the peaking band.
+		let (peak_frequency, peak_gain) = response.iter().fold((0.0, f32::MIN), |peak, point| if point.1 > peak.1 { *point } else { peak });
+		assert!((peak_frequency - 3000.0).abs() < 200.0);
+		assert!((peak_gain - 4.0).abs() < 0.1);
+	}
+
+	#[test]
+	fn test_effect_parametric_eq_settings() {
+		let mut eq:ParametricEq = two_band_eq();
+		assert_eq!(eq.band_count(), 2);
+		assert_eq!(eq.band_type(1), Some(BiquadFilterType::Peaking));
+		eq.set_band_type(1, BiquadFilterType::HighShelf);
+		assert_eq!(eq.band_type(1), Some(BiquadFilterType::HighShelf));
+		eq.settings_test();
+	}
+}
\ No newline at end of file
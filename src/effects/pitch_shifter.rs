@@ -0,0 +1,309 @@
+use crate::{ audio_effect::create_effect_id, AudioEffect };
+use super::fft::fft;
+use std::{ any::Any, f32::consts::TAU };
+
+
+
+const FRAME_DURATION:f32 = 0.046;
+const OVERLAP_FACTOR:usize = 4;
+const FORMANT_LIFTER_DURATION:f32 = 0.002;
+const ENVELOPE_FLOOR:f32 = 0.001;
+const ENVELOPE_ITERATIONS:usize = 8;
+
+
+
+#[derive(Clone, PartialEq)]
+struct ChannelState {
+	input:Vec<f32>,
+	output:Vec<f32>,
+	analysis_phases:Vec<f32>,
+	synthesis_phases:Vec<f32>
+}
+
+
+
+#[derive(PartialEq)]
+pub struct PitchShifter {
+	id:usize,
+	semitones:f32,
+	cents:f32,
+	preserve_formants:bool,
+
+	sample_rate:u32,
+	frame_len:usize,
+	window:Vec<f32>,
+	channels:Vec<ChannelState>,
+	skip_len:usize
+}
+impl PitchShifter {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new pitch shifter that transposes by the given amount of semitones and cents without changing the duration.
+	pub fn new(semitones:f32, cents:f32) -> PitchShifter {
+		PitchShifter {
+			id: create_effect_id(),
+			semitones,
+			cents,
+			preserve_formants: false,
+
+			sample_rate: 0,
+			frame_len: 0,
+			window: Vec::new(),
+			channels: Vec::new(),
+			skip_len: 0
+		}
+	}
+
+	/// Create a new pitch shifter that multiplies all frequencies by the given ratio without changing the duration.
+	pub fn new_ratio(ratio:f32) -> PitchShifter {
+		PitchShifter::new(ratio.log2() * 12.0, 0.0)
+	}
+
+	/// Return self with formant preservation enabled or disabled. When enabled, the spectral envelope of the audio is kept in place while the pitch moves, so voices keep their character instead of sounding like chipmunks. Disabled by default.
+	pub fn with_formant_preservation(mut self, preserve_formants:bool) -> Self {
+		self.preserve_formants = preserve_formants;
+		self
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the ratio all frequencies are multiplied by.
+	pub fn ratio(&self) -> f32 {
+		2.0_f32.powf((self.semitones + self.cents / 100.0) / 12.0)
+	}
+
+	/// Check if formants are preserved.
+	pub fn preserves_formants(&self) -> bool {
+		self.preserve_formants
+	}
+
+	/// Get the length of the analysis frames for the given sample rate.
+	fn frame_len_for(sample_rate:u32) -> usize {
+		((sample_rate as f32 * FRAME_DURATION) as usize).next_power_of_two().max(OVERLAP_FACTOR * 4)
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Reset the state for the given sample rate and channel count.
+	fn initialize(&mut self, sample_rate:u32, channel_count:usize) {
+		let frame_len:usize = PitchShifter::frame_len_for(sample_rate);
+		let hop_len:usize = frame_len / OVERLAP_FACTOR;
+		self.sample_rate = sample_rate;
+		self.frame_len = frame_len;
+		self.window = (0..frame_len).map(|index| 0.5 - 0.5 * (index as f32 / frame_len as f32 * TAU).cos()).collect();
+
+		// Start with silence so the first frames fade in before the first sample. The output of that silence is skipped.
+		self.channels = vec![ChannelState {
+			input: vec![0.0; frame_len - hop_len],
+			output: vec![0.0; frame_len],
+			analysis_phases: vec![0.0; frame_len / 2 + 1],
+			synthesis_phases: vec![0.0; frame_len / 2 + 1]
+		}; channel_count];
+		self.skip_len = frame_len - hop_len;
+	}
+
+	/// Shift the pitch of the frame at the start of the input of the channel and add it to the output of the channel.
+	fn process_frame(&self, channel:&mut ChannelState, ratio:f32) {
+		let frame_len:usize = self.frame_len;
+		let bin_count:usize = frame_len / 2 + 1;
+		let phase_advance:f32 = TAU / OVERLAP_FACTOR as f32;
+
+		// Analyze the frequency of each bin from the phase difference with the previous frame.
+		let mut real:Vec<f32> = channel.input[..frame_len].iter().zip(&self.window).map(|(sample, window)| sample * window).collect();
+		let mut imaginary:Vec<f32> = vec![0.0; frame_len];
+		fft(&mut real, &mut imaginary, false);
+		let mut magnitudes:Vec<f32> = Vec::with_capacity(bin_count);
+		let mut frequencies:Vec<f32> = Vec::with_capacity(bin_count);
+		for bin in 0..bin_count {
+			let phase:f32 = imaginary[bin].atan2(real[bin]);
+			let mut phase_difference:f32 = phase - channel.analysis_phases[bin] - bin as f32 * phase_advance;
+			phase_difference -= TAU * (phase_difference / TAU).round();
+			channel.analysis_phases[bin] = phase;
+			magnitudes.push(real[bin].hypot(imaginary[bin]));
+			frequencies.push(bin as f32 + phase_difference / phase_advance);
+		}
+
+		// Find the spectral peaks. Each bin belongs to the region of the nearest peak.
+		let peak_bins:Vec<usize> = (0..bin_count).filter(|bin| {
+			(*bin == 0 || magnitudes[*bin] > magnitudes[bin - 1]) && (*bin + 1 == bin_count || magnitudes[*bin] >= magnitudes[bin + 1])
+		}).collect();
+
+		// Move each peak region as a whole to the bin nearest to the shifted frequency of the peak, so the shape of the peak stays intact. The phase of the peak advances by its shifted frequency, other bins keep their phase relation to the peak. When preserving formants, only the excitation is moved and the original envelope is applied at the new position.
+		let envelope:Option<Vec<f32>> = if self.preserve_formants { Some(self.spectral_envelope(&magnitudes)) } else { None };
+		let mut shifted_real:Vec<f32> = vec![0.0; bin_count];
+		let mut shifted_imaginary:Vec<f32> = vec![0.0; bin_count];
+		let mut peak_phases:Vec<f32> = vec![0.0; peak_bins.len()];
+		for (peak_index, peak_bin) in peak_bins.iter().enumerate() {
+			let target_bin:usize = (*peak_bin as f32 * ratio).round() as usize;
+			if target_bin < bin_count {
+				peak_phases[peak_index] = channel.synthesis_phases[target_bin] + frequencies[*peak_bin] * ratio * phase_advance;
+			}
+		}
+		let mut peak_index:usize = 0;
+		for bin in 0..bin_count {
+			while peak_index + 1 < peak_bins.len() && peak_bins[peak_index + 1].abs_diff(bin) < peak_bins[peak_index].abs_diff(bin) {
+				peak_index += 1;
+			}
+			let Some(peak_bin) = peak_bins.get(peak_index) else { break; };
+			let target_bin:isize = bin as isize + (*peak_bin as f32 * ratio).round() as isize - *peak_bin as isize;
+			if target_bin < 0 || target_bin >= bin_count as isize {
+				continue;
+			}
+			let target_bin:usize = target_bin as usize;
+			let magnitude:f32 = match &envelope {
+				Some(envelope) => magnitudes[bin] / envelope[bin].max(f32::EPSILON) * envelope[target_bin],
+				None => magnitudes[bin]
+			};
+			let phase:f32 = peak_phases[peak_index] + channel.analysis_phases[bin] - channel.analysis_phases[*peak_bin];
+			shifted_real[target_bin] += magnitude * phase.cos();
+			shifted_imaginary[target_bin] += magnitude * phase.sin();
+		}
+		for bin in 0..bin_count {
+			channel.synthesis_phases[bin] = shifted_imaginary[bin].atan2(shifted_real[bin]);
+			real[bin] = shifted_real[bin];
+			imaginary[bin] = shifted_imaginary[bin];
+		}
+		for bin in 1..frame_len / 2 {
+			real[frame_len - bin] = real[bin];
+			imaginary[frame_len - bin] = -imaginary[bin];
+		}
+		fft(&mut real, &mut imaginary, true);
+
+		// Overlap-add the windowed frame. The squared hann windows of overlapping frames add up to the overlap factor times 3/8.
+		let scale:f32 = 8.0 / (3.0 * OVERLAP_FACTOR as f32);
+		for ((output, sample), window) in channel.output.iter_mut().zip(&real).zip(&self.window) {
+			*output += sample * window * scale;
+		}
+	}
+
+	/// Estimate the spectral envelope from the given magnitudes. The log magnitudes are smoothed with a cepstral lifter repeatedly, each time raising the spectrum to the envelope where it is below, so the envelope follows the harmonic peaks instead of the average between them.
+	fn spectral_envelope(&self, magnitudes:&[f32]) -> Vec<f32> {
+		let frame_len:usize = self.frame_len;
+		let lifter_len:usize = ((self.sample_rate as f32 * FORMANT_LIFTER_DURATION) as usize).clamp(2, frame_len / 2);
+
+		// Limit the dynamic range so near-silent bins do not dominate the envelope.
+		let floor:f32 = magnitudes.iter().fold(0.0, |peak:f32, magnitude| peak.max(*magnitude)) * ENVELOPE_FLOOR + f32::EPSILON;
+		let log_magnitudes:Vec<f32> = magnitudes.iter().map(|magnitude| magnitude.max(floor).ln()).collect();
+		let mut envelope:Vec<f32> = log_magnitudes.clone();
+		for _ in 0..ENVELOPE_ITERATIONS {
+
+			// Transform to the cepstrum, remove the fine structure of the spectrum and transform back.
+			let mut real:Vec<f32> = vec![0.0; frame_len];
+			let mut imaginary:Vec<f32> = vec![0.0; frame_len];
+			for (bin, (log_magnitude, log_envelope)) in log_magnitudes.iter().zip(&envelope).enumerate() {
+				real[bin] = log_magnitude.max(*log_envelope);
+				if bin > 0 && bin < frame_len / 2 {
+					real[frame_len - bin] = real[bin];
+				}
+			}
+			fft(&mut real, &mut imaginary, true);
+			for index in lifter_len..=frame_len - lifter_len {
+				real[index] = 0.0;
+				imaginary[index] = 0.0;
+			}
+			fft(&mut real, &mut imaginary, false);
+			envelope = real[..magnitudes.len()].to_vec();
+		}
+		envelope.iter().map(|log_magnitude| log_magnitude.exp()).collect()
+	}
+}
+impl AudioEffect for PitchShifter {
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the ID of the effect.
+	fn id(&self) -> usize {
+		self.id
+	}
+
+	/// Get the name of the effect.
+	fn name(&self) -> &str {
+		"pitch_shifter"
+	}
+
+	/// Get the amount of samples the output lags behind the input.
+	fn latency(&self, sample_rate:u32, _channel_count:usize) -> usize {
+		if self.ratio() == 1.0 && self.channels.is_empty() {
+			0
+		} else {
+			PitchShifter::frame_len_for(sample_rate)
+		}
+	}
+
+	/// Clone the effect into a box.
+	fn boxed(&self) -> Box<dyn AudioEffect> {
+		Box::new(PitchShifter::new(self.semitones, self.cents).with_formant_preservation(self.preserve_formants))
+	}
+
+	/// Allow downcasting.
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Apply the effect to the given buffer.
+	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, _channel_count:&mut usize) {
+		let ratio:f32 = self.ratio();
+		if (ratio == 1.0 && self.channels.is_empty()) || data.is_empty() {
+			return;
+		}
+		if self.sample_rate != *sample_rate || self.channels.len() != data.len() {
+			self.initialize(*sample_rate, data.len());
+		}
+
+		// Process a frame for each hop of input, outputting the samples no later frame overlaps.
+		let hop_len:usize = self.frame_len / OVERLAP_FACTOR;
+		let mut channels:Vec<ChannelState> = std::mem::take(&mut self.channels);
+		for (channel, channel_data) in channels.iter_mut().zip(data.iter_mut()) {
+			channel.input.extend_from_slice(channel_data);
+			channel_data.clear();
+			while channel.input.len() >= self.frame_len {
+				self.process_frame(channel, ratio);
+				channel_data.extend(channel.output.drain(..hop_len));
+				channel.output.extend(vec![0.0; hop_len]);
+				channel.input.drain(..hop_len);
+			}
+		}
+		self.channels = channels;
+
+		// Skip the output of the silence before the first sample.
+		if self.skip_len > 0 {
+			let skip_len:usize = self.skip_len.min(data[0].len());
+			data.iter_mut().for_each(|channel| { channel.drain(..skip_len); });
+			self.skip_len -= skip_len;
+		}
+	}
+
+	/// Forget the audio processed so far, so the next output does not depend on it.
+	fn reset(&mut self) {
+		self.channels = Vec::new();
+	}
+
+
+
+	/* SETTING METHODS */
+
+	/// Get a list of settings with their names.
+	fn settings(&self) -> Vec<(&str, &f32)> {
+		vec![
+			("semitones", &self.semitones),
+			("cents", &self.cents)
+		]
+	}
+
+	/// Get a mutable list of settings with their names.
+	fn settings_mut(&mut self) -> Vec<(&str, &mut f32)> {
+		vec![
+			("semitones", &mut self.semitones),
+			("cents", &mut self.cents)
+		]
+	}
+}
\ No newline at end of file
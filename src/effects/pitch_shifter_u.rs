@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+	use super::super::{ fft::fft, test_signals::{ frequency, sine } };
+	use crate::{ AudioBuffer, AudioEffect, AudioGenerator, PitchShifter };
+	use std::{ f32::consts::TAU, time::Duration };
+
+
+
+	/// Calculate the power-weighted spectral centroid of 2048 samples in the middle of the given data.
+	fn spectral_centroid(data:&[f32], sample_rate:u32) -> f32 {
+		let start:usize = data.len() / 2 - 1024;
+		let mut real:Vec<f32> = data[start..start + 2048].to_vec();
+		let mut imaginary:Vec<f32> = vec![0.0; 2048];
+		fft(&mut real, &mut imaginary, false);
+		let powers:Vec<f32> = (0..1024).map(|bin| real[bin] * real[bin] + imaginary[bin] * imaginary[bin]).collect();
+		let weighted_sum:f32 = powers.iter().enumerate().map(|(bin, power)| bin as f32 * power).sum();
+		weighted_sum / powers.iter().sum::<f32>() * sample_rate as f32 / 2048.0
+	}
+
+
+
+	/* PITCH TESTS */
+
+	#[test]
+	fn test_effect_pitch_shift_frequency() {
+		for (semitones, cents) in [(12.0, 0.0), (-12.0, 0.0), (7.0, 0.0), (3.0, 50.0)] {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 8000)], 8000);
+			buffer.add_effect(PitchShifter::new(semitones, cents));
+			let expected_frequency:f32 = 440.0 * 2.0_f32.powf((semitones + cents / 100.0) / 12.0);
+			let shifted_frequency:f32 = frequency(&buffer.processed_data()[0], 8000);
+			println!("{semitones} {cents}: {shifted_frequency}Hz, expected {expected_frequency}Hz");
+			assert!((shifted_frequency - expected_frequency).abs() < expected_frequency * 0.02);
+		}
+	}
+
+	#[test]
+	fn test_effect_pitch_shift_keeps_duration() {
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 8000)], 8000);
+		buffer.add_effect(PitchShifter::new(5.0, 0.0));
+		assert_eq!(buffer.duration(), Duration::from_secs(1));
+		assert_eq!(buffer.processed_data()[0].len(), 8000);
+
+		// The shifted sine keeps its amplitude.
+		let peak:f32 = buffer.raw_data()[0][1000..7000].iter().fold(0.0, |peak, sample| sample.abs().max(peak));
+		assert!((peak - 0.5).abs() < 0.05);
+	}
+
+	#[test]
+	fn test_effect_pitch_shift_ratio() {
+		let shifter:PitchShifter = PitchShifter::new_ratio(2.0);
+		assert!((shifter.get_setting("semitones").unwrap() - 12.0).abs() < 0.0001);
+		assert!((shifter.ratio() - 2.0).abs() < 0.0001);
+		assert!((PitchShifter::new(0.0, 1200.0).ratio() - 2.0).abs() < 0.0001);
+	}
+
+	#[test]
+	fn test_effect_pitch_shift_unchanged() {
+		let samples:Vec<f32> = sine(440.0, 0.5, 8000, 8000);
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 8000);
+		buffer.add_effect(PitchShifter::new(0.0, 0.0));
+		assert_eq!(buffer.processed_data()[0], samples);
+	}
+
+	#[test]
+	fn test_effect_pitch_shift_formants() {
+
+		// Create harmonics of 200Hz with a formant peak at 1000Hz.
+		let samples:Vec<f32> = (0..16000).map(|index| {
+			(1..40).map(|harmonic| {
+				let frequency:f32 = 200.0 * harmonic as f32;
+				let gain:f32 = (-((frequency - 1000.0) / 300.0).powi(2)).exp() * 0.1;
+				(index as f32 / 16000.0 * frequency * TAU).sin() * gain
+			}).sum()
+		}).collect();
+		let original_centroid:f32 = spectral_centroid(&samples, 16000);
+
+		// Shift with and without formant preservation.
+		let mut shifted:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 16000);
+		shifted.add_effect(PitchShifter::new(5.0, 0.0));
+		let shifted_centroid:f32 = spectral_centroid(&shifted.processed_data()[0], 16000);
+		let mut preserved:AudioBuffer = AudioBuffer::new(vec![samples], 16000);
+		preserved.add_effect(PitchShifter::new(5.0, 0.0).with_formant_preservation(true));
+		let preserved_centroid:f32 = spectral_centroid(&preserved.processed_data()[0], 16000);
+		println!("original {original_centroid}Hz, shifted {shifted_centroid}Hz, preserved {preserved_centroid}Hz");
+		assert!(shifted_centroid - original_centroid > 200.0);
+		assert!((preserved_centroid - original_centroid).abs() < (shifted_centroid - original_centroid) / 3.0);
+	}
+
+
+
+	/* STREAMING TESTS */
+
+	#[test]
+	fn test_effect_pitch_shift_streaming() {
+		let mut whole_buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 8000), sine(660.0, 0.5, 8000, 8000)], 8000);
+		whole_buffer.add_effect(PitchShifter::new(4.0, 0.0));
+		let whole:Vec<Vec<f32>> = whole_buffer.processed_data().clone();
+
+		// Taking the buffer in chunks should produce the same samples as processing it at once.
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 8000), sine(660.0, 0.5, 8000, 8000)], 8000);
+		buffer.add_effect(PitchShifter::new(4.0, 0.0));
+		let mut chunked:Vec<Vec<f32>> = vec![Vec::new(); 2];
+		while !buffer.is_depleted() {
+			for (channel, new_data) in chunked.iter_mut().zip(buffer.take(70)) {
+				channel.extend(new_data);
+			}
+		}
+		for (whole_channel, chunked_channel) in whole.iter().zip(&chunked) {
+			assert_eq!(whole_channel.len(), 8000);
+			assert_eq!(whole_channel[..], chunked_channel[..whole_channel.len()]);
+		}
+	}
+
+	#[test]
+	fn test_effect_pitch_shift_live_change() {
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 16000)], 8000);
+		buffer.add_effect(PitchShifter::new(0.0, 0.0));
+		buffer.set_effect_setting("pitch_shifter", "semitones", 12.0);
+		let first_half:Vec<f32> = buffer.take(8000).remove(0);
+		buffer.set_effect_setting("pitch_shifter", "semitones", -12.0);
+		let second_half:Vec<f32> = buffer.take(8000).remove(0);
+		assert!((frequency(&first_half, 8000) - 880.0).abs() < 880.0 * 0.03);
+		assert!((frequency(&second_half, 8000) - 220.0).abs() < 220.0 * 0.03);
+	}
+
+
+
+	/* SETTINGS */
+
+	#[test]
+	fn test_settings() {
+		PitchShifter::new(3.0, 20.0).settings_test();
+	}
+}
\ No newline at end of file
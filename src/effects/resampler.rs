@@ -1,5 +1,6 @@
-use crate::{ audio_effect::create_effect_id, AudioEffect };
-use std::any::Any;
+use crate::{ audio_effect::create_effect_id, AudioEffect, ResampleQuality };
+use super::sinc_resampler::SincResampler;
+use std::{ any::Any, f32::consts::FRAC_1_SQRT_2 };
 
 
 
@@ -7,18 +8,132 @@ use std::any::Any;
 pub struct Resampler {
 	id:usize,
 	sample_rate:f32,
-	channel_count:f32
+	channel_count:f32,
+	resampler:SincResampler
 }
 impl Resampler {
 
-	/// Create a new resampler.
+	/// Create a new resampler that converts the sample rate and channel count together.
 	pub fn new(sample_rate:u32, channel_count:usize) -> Resampler {
 		Resampler {
 			id: create_effect_id(),
 			sample_rate: sample_rate as f32,
-			channel_count: channel_count as f32
+			channel_count: channel_count as f32,
+			resampler: SincResampler::new(ResampleQuality::default())
 		}
 	}
+
+	/// Return self with the given resample quality. Defaults to `ResampleQuality::Medium`.
+	pub fn with_quality(mut self, quality:ResampleQuality) -> Self {
+		self.resampler = SincResampler::new(quality);
+		self
+	}
+
+	/// Get the resample quality.
+	pub fn quality(&self) -> ResampleQuality {
+		self.resampler.quality()
+	}
+
+	/// Get the gain of each source channel in each target channel. Channels are ordered like in wav files. Downmixing 3.0, quad, 5.0, 5.1 and 7.1 audio to stereo or mono folds center and surround channels into the front channels and drops the LFE channel. Mono audio is upmixed to the front channels. Other layouts keep matching channels, silencing new channels or averaging folded channels.
+	fn mix_matrix(source_channel_count:usize, target_channel_count:usize) -> Vec<Vec<f32>> {
+		if source_channel_count == target_channel_count {
+			(0..target_channel_count).map(|target_channel| (0..source_channel_count).map(|source_channel| if source_channel == target_channel { 1.0 } else { 0.0 }).collect()).collect()
+		} else if target_channel_count <= 2 {
+			let stereo_matrix:Vec<Vec<f32>> = Resampler::stereo_mix_matrix(source_channel_count);
+			if target_channel_count == 2 {
+				stereo_matrix
+			} else {
+				vec![(0..source_channel_count).map(|source_channel| (stereo_matrix[0][source_channel] + stereo_matrix[1][source_channel]) * 0.5).collect()]
+			}
+		} else if source_channel_count == 1 {
+			(0..target_channel_count).map(|target_channel| vec![if target_channel < 2 { 1.0 } else { 0.0 }]).collect()
+		} else if source_channel_count < target_channel_count {
+			(0..target_channel_count).map(|target_channel| (0..source_channel_count).map(|source_channel| if source_channel == target_channel { 1.0 } else { 0.0 }).collect()).collect()
+		} else {
+			Resampler::fold_mix_matrix(source_channel_count, target_channel_count)
+		}
+	}
+
+	/// Get the gain of each source channel in a stereo downmix or upmix.
+	fn stereo_mix_matrix(source_channel_count:usize) -> Vec<Vec<f32>> {
+		const C:f32 = FRAC_1_SQRT_2;
+		let matrix:Vec<Vec<f32>> = match source_channel_count {
+			1 => return vec![vec![1.0], vec![1.0]],
+			3 => vec![
+				vec![1.0, 0.0, C],
+				vec![0.0, 1.0, C]
+			],
+			4 => vec![
+				vec![1.0, 0.0, C, 0.0],
+				vec![0.0, 1.0, 0.0, C]
+			],
+			5 => vec![
+				vec![1.0, 0.0, C, C, 0.0],
+				vec![0.0, 1.0, C, 0.0, C]
+			],
+			6 => vec![
+				vec![1.0, 0.0, C, 0.0, C, 0.0],
+				vec![0.0, 1.0, C, 0.0, 0.0, C]
+			],
+			8 => vec![
+				vec![1.0, 0.0, C, 0.0, C, 0.0, C, 0.0],
+				vec![0.0, 1.0, C, 0.0, 0.0, C, 0.0, C]
+			],
+			_ => return Resampler::fold_mix_matrix(source_channel_count, 2)
+		};
+
+		// Scale down so a full-scale signal in all channels does not clip.
+		matrix.into_iter().map(|row| {
+			let row_gain:f32 = row.iter().sum();
+			row.into_iter().map(|gain| gain / row_gain).collect()
+		}).collect()
+	}
+
+	/// Get the gain of each source channel when folding source channels into the target channel with the same index modulo the target channel count, averaging all channels folded together.
+	fn fold_mix_matrix(source_channel_count:usize, target_channel_count:usize) -> Vec<Vec<f32>> {
+		(0..target_channel_count).map(|target_channel| {
+			let folded_count:usize = (0..source_channel_count).filter(|source_channel| source_channel % target_channel_count == target_channel).count();
+			(0..source_channel_count).map(|source_channel| if source_channel % target_channel_count == target_channel { 1.0 / folded_count as f32 } else { 0.0 }).collect()
+		}).collect()
+	}
+
+	/// Mix the channels of the given data to the target channel count.
+	fn mix_channels(&self, data:&mut Vec<Vec<f32>>, channel_count:&mut usize) {
+		let target_channel_count:usize = self.channel_count as usize;
+		if data.len() == target_channel_count {
+			*channel_count = target_channel_count;
+			return;
+		}
+		if data.is_empty() || target_channel_count == 0 {
+			*data = Vec::new();
+			*channel_count = target_channel_count;
+			return;
+		}
+
+		// Create new channels from the mix matrix.
+		let sample_count:usize = data[0].len();
+		*data = Resampler::mix_matrix(data.len(), target_channel_count).iter().map(|gains| {
+			let mut channel:Vec<f32> = vec![0.0; sample_count];
+			for (source_channel, gain) in data.iter().zip(gains) {
+				if *gain != 0.0 {
+					for (sample, source_sample) in channel.iter_mut().zip(source_channel) {
+						*sample += source_sample * gain;
+					}
+				}
+			}
+			channel
+		}).collect();
+		*channel_count = target_channel_count;
+	}
+
+	/// Resample the given data to the target sample rate.
+	fn resample_rate(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32) {
+		let multiplier:f32 = self.sample_multiplier(*sample_rate, data.len());
+		if multiplier != 1.0 && !data.is_empty() {
+			*data = self.resampler.process(data, multiplier);
+		}
+		*sample_rate = self.sample_rate as u32;
+	}
 }
 impl AudioEffect for Resampler {
 
@@ -35,18 +150,23 @@ impl AudioEffect for Resampler {
 	}
 
 	/// Return the time multiplier of this effect.
-	fn sample_multiplier(&self, sample_rate:u32, channel_count:usize) -> f32 {
-		let sample_rate_multiplier:f32 = 1.0 / sample_rate as f32 * self.sample_rate;
-		let channel_count_multiplier:f32 = 1.0 / channel_count as f32 * self.channel_count;
-		sample_rate_multiplier * channel_count_multiplier
+	fn sample_multiplier(&self, sample_rate:u32, _channel_count:usize) -> f32 {
+		1.0 / sample_rate as f32 * self.sample_rate
+	}
+
+	/// Get the amount of samples the output lags behind the input.
+	fn latency(&self, sample_rate:u32, channel_count:usize) -> usize {
+		let multiplier:f32 = self.sample_multiplier(sample_rate, channel_count);
+		if multiplier == 1.0 { 0 } else { self.resampler.latency(multiplier) }
 	}
-	
+
 	/// Clone the effect into a box.
 	fn boxed(&self) -> Box<dyn AudioEffect> {
 		Box::new(Resampler {
 			id: create_effect_id(),
 			sample_rate: self.sample_rate,
-			channel_count: self.channel_count
+			channel_count: self.channel_count,
+			resampler: SincResampler::new(self.resampler.quality())
 		})
 	}
 
@@ -60,53 +180,40 @@ impl AudioEffect for Resampler {
 	/* USAGE METHODS */
 
 	/// Apply the effect to the given buffer.
-	fn apply_to(&mut self, data:&mut Vec<f32>, sample_rate:&mut u32, channel_count:&mut usize) {
-		let target_sample_rate:u32 = self.sample_rate as u32;
-		let target_channel_count:usize = self.channel_count as usize;
-
-		// Do nothing if not needed.
-		if *sample_rate == target_sample_rate && *channel_count == target_channel_count {
-			return;
+	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, channel_count:&mut usize) {
+
+		// Resample the rate with as few channels as possible.
+		if (self.channel_count as usize) < data.len() {
+			self.mix_channels(data, channel_count);
+			self.resample_rate(data, sample_rate);
+		} else {
+			self.resample_rate(data, sample_rate);
+			self.mix_channels(data, channel_count);
 		}
+	}
 
-		// Resample channel count.
-		if *channel_count != target_channel_count {
-			if target_channel_count < *channel_count {
-				*data = data.chunks(*channel_count).map(|chunk| chunk[..target_channel_count].to_vec()).flatten().collect();
-			} else {
-				*data = data.chunks(*channel_count).map(|chunk| [
-					chunk.to_vec(),
-					(0..target_channel_count - *channel_count).map(|index| chunk[index % *channel_count]).collect()
-				]).flatten().flatten().collect();
-			}
-			*channel_count = target_channel_count;
-		}
+	/// Forget the audio processed so far, so the next output does not depend on it.
+	fn reset(&mut self) {
+		self.resampler.reset();
+	}
 
-		// Resample sample rate.
-		if *sample_rate != target_sample_rate {
-
-			// Calculate target to source scale.
-			let channel_data:Vec<&[f32]> = data.chunks(1).collect();
-			let target_sample_count:f32 = (channel_data.len() as f32 / *sample_rate as f32 * target_sample_rate as f32).floor();
-			let target_to_source_scale:f32 = 1.0 / (channel_data.len() - 1) as f32 * target_sample_count as f32;
-			let source_sample_count:usize = channel_data.len();
-
-			// Create new buffer.
-			let mut new_data:Vec<f32> = Vec::with_capacity(target_sample_count as usize * *channel_count);
-			let mut source_index:f32 = 0.0;
-			for _ in 0..target_sample_count as usize {
-				let cursor_left:usize = (source_index as usize).min(source_sample_count - 2);
-				let cursor_right:usize = cursor_left + 1;
-				let cursor_factor:f32 = source_index % 1.0;
-				for channel_index in 0..*channel_count {
-					new_data.push(channel_data[cursor_left][channel_index] + (channel_data[cursor_right][channel_index] - channel_data[cursor_left][channel_index]) * cursor_factor);
-				}
-				source_index += target_to_source_scale;
-			}
 
-			// Set new data.
-			*data = new_data;
-			*sample_rate = target_sample_rate;
-		}
+
+	/* SETTING METHODS */
+
+	/// Get a list of settings with their names.
+	fn settings(&self) -> Vec<(&str, &f32)> {
+		vec![
+			("target_sample_rate", &self.sample_rate),
+			("target_channel_count", &self.channel_count)
+		]
+	}
+
+	/// Get a mutable list of settings with their names.
+	fn settings_mut(&mut self) -> Vec<(&str, &mut f32)> {
+		vec![
+			("target_sample_rate", &mut self.sample_rate),
+			("target_channel_count", &mut self.channel_count)
+		]
 	}
 }
\ No newline at end of file
@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioEffect, AudioGenerator, Resampler };
+	use std::f32::consts::FRAC_1_SQRT_2;
+
+
+
+	/* CHANNEL COUNT */
+
+	#[test]
+	fn test_effect_resampler_stereo_to_mono() {
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![vec![1.0; 10], vec![0.0; 10]], 10);
+		buffer.resample(10, 1);
+		assert_eq!(buffer.processed_data(), &vec![vec![0.5; 10]]);
+		assert_eq!(buffer.channel_count(), 1);
+	}
+
+	#[test]
+	fn test_effect_resampler_mono_to_stereo() {
+		let saw_shape:Vec<f32> = (0..10).map(|index| index as f32 / 10.0).collect();
+
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![saw_shape.clone()], 10);
+		buffer.resample(10, 2);
+		assert_eq!(buffer.processed_data(), &vec![saw_shape; 2]);
+		assert_eq!(buffer.channel_count(), 2);
+	}
+
+	#[test]
+	fn test_effect_resampler_surround_to_stereo() {
+
+		// Left, right, center, LFE, left surround, right surround.
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![vec![0.1; 10], vec![0.2; 10], vec![0.3; 10], vec![1.0; 10], vec![0.4; 10], vec![0.5; 10]], 10);
+		buffer.resample(10, 2);
+		let row_gain:f32 = 1.0 + FRAC_1_SQRT_2 * 2.0;
+		let expected_left:f32 = (0.1 + (0.3 + 0.4) * FRAC_1_SQRT_2) / row_gain;
+		let expected_right:f32 = (0.2 + (0.3 + 0.5) * FRAC_1_SQRT_2) / row_gain;
+		let data:&Vec<Vec<f32>> = buffer.processed_data();
+		assert_eq!(data.len(), 2);
+		assert!(data[0].iter().all(|sample| (sample - expected_left).abs() < 0.00001));
+		assert!(data[1].iter().all(|sample| (sample - expected_right).abs() < 0.00001));
+	}
+
+	#[test]
+	fn test_effect_resampler_layout_routing() {
+
+		// Get the level of each target channel for a signal in a single source channel.
+		let route = |source_channel_count:usize, source_channel:usize, target_channel_count:usize| {
+			let mut buffer:AudioBuffer = AudioBuffer::new((0..source_channel_count).map(|channel| vec![if channel == source_channel { 1.0 } else { 0.0 }; 10]).collect(), 10);
+			buffer.resample(10, target_channel_count);
+			buffer.processed_data().iter().map(|channel| channel[0]).collect::<Vec<f32>>()
+		};
+		let is_left = |levels:Vec<f32>| levels[0] > 0.0 && levels[1] == 0.0;
+		let is_right = |levels:Vec<f32>| levels[0] == 0.0 && levels[1] > 0.0;
+		let is_center = |levels:Vec<f32>| levels[0] > 0.0 && (levels[0] - levels[1]).abs() < 0.00001;
+
+		// Left, right, center.
+		assert!(is_left(route(3, 0, 2)) && is_right(route(3, 1, 2)) && is_center(route(3, 2, 2)));
+		assert_eq!(route(3, 0, 1), route(3, 1, 1));
+		assert!(route(3, 2, 1)[0] > route(3, 0, 1)[0]);
+
+		// Left, right, left surround, right surround.
+		assert!(is_left(route(4, 0, 2)) && is_right(route(4, 1, 2)) && is_left(route(4, 2, 2)) && is_right(route(4, 3, 2)));
+
+		// Left, right, center, left surround, right surround.
+		assert!(is_left(route(5, 0, 2)) && is_right(route(5, 1, 2)) && is_center(route(5, 2, 2)) && is_left(route(5, 3, 2)) && is_right(route(5, 4, 2)));
+	}
+
+	#[test]
+	fn test_effect_resampler_fold_channels() {
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![vec![0.1; 10], vec![0.2; 10], vec![0.3; 10], vec![0.4; 10], vec![0.5; 10]], 10);
+		buffer.resample(10, 3);
+		let data:&Vec<Vec<f32>> = buffer.processed_data();
+		assert!(data[0].iter().all(|sample| (sample - 0.25).abs() < 0.00001));
+		assert!(data[1].iter().all(|sample| (sample - 0.35).abs() < 0.00001));
+		assert!(data[2].iter().all(|sample| (sample - 0.3).abs() < 0.00001));
+	}
+
+
+
+	/* SAMPLE RATE */
+
+	#[test]
+	fn test_effect_resampler_rate_and_channels() {
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![vec![0.5; 1000], vec![0.5; 1000]], 1000);
+		buffer.resample(500, 1);
+		assert_eq!(buffer.processed_data()[0].len(), 500);
+		assert_eq!(buffer.channel_count(), 1);
+		assert_eq!(buffer.sample_rate(), 500);
+		assert!(buffer.raw_data()[0][50..450].iter().all(|sample| (sample - 0.5).abs() < 0.001));
+	}
+
+	#[test]
+	fn test_effect_resampler_streaming() {
+		let sine:Vec<f32> = (0..4410).map(|index| (index as f32 * 0.1).sin()).collect();
+		let whole:AudioBuffer = AudioBuffer::new(vec![sine.clone()], 44100).resampled(48000, 2);
+
+		// Taking the buffer in chunks should produce the same samples as resampling it at once.
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine], 44100);
+		buffer.resample(48000, 2);
+		let mut chunked:Vec<Vec<f32>> = vec![Vec::new(); 2];
+		while !buffer.is_depleted() {
+			for (channel, new_data) in chunked.iter_mut().zip(buffer.take(64)) {
+				channel.extend(new_data);
+			}
+		}
+		assert_eq!(whole.channel_count(), 2);
+		for (whole_channel, chunked_channel) in whole.raw_data().iter().zip(&chunked) {
+			assert_eq!(whole_channel.len(), 4800);
+			for (left, right) in whole_channel.iter().zip(chunked_channel) {
+				assert!((left - right).abs() < 0.00001);
+			}
+		}
+	}
+
+
+
+	/* SETTINGS */
+
+	#[test]
+	fn test_settings() {
+		Resampler::new(48000, 2).settings_test();
+	}
+}
\ No newline at end of file
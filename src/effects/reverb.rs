@@ -0,0 +1,280 @@
+use crate::{ audio_effect::create_effect_id, AudioEffect };
+use std::{ any::Any, collections::VecDeque };
+
+
+
+// Delay lengths of the Freeverb filters at 44100Hz, scaled to other sample rates.
+const TUNING_SAMPLE_RATE:f32 = 44100.0;
+const COMB_LENGTHS:[usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALL_PASS_LENGTHS:[usize; 4] = [556, 441, 341, 225];
+const STEREO_SPREAD:usize = 23;
+const ALL_PASS_FEEDBACK:f32 = 0.5;
+const INPUT_GAIN:f32 = 0.03;
+const ROOM_SCALE:f32 = 0.28;
+const ROOM_OFFSET:f32 = 0.7;
+const DAMPING_SCALE:f32 = 0.4;
+const TAIL_DECAY:f32 = 0.001;
+
+
+
+#[derive(PartialEq)]
+struct CombFilter {
+	buffer:Vec<f32>,
+	index:usize,
+	filter_store:f32
+}
+impl CombFilter {
+
+	/// Create a new comb filter with a delay of the given length.
+	fn new(len:usize) -> CombFilter {
+		CombFilter {
+			buffer: vec![0.0; len.max(1)],
+			index: 0,
+			filter_store: 0.0
+		}
+	}
+
+	/// Process a sample, low-pass filtering the feedback with the given damping.
+	fn process(&mut self, input:f32, feedback:f32, damping:f32) -> f32 {
+		let output:f32 = self.buffer[self.index];
+		self.filter_store = output * (1.0 - damping) + self.filter_store * damping;
+		self.buffer[self.index] = input + self.filter_store * feedback;
+		self.index = (self.index + 1) % self.buffer.len();
+		output
+	}
+}
+
+
+
+#[derive(PartialEq)]
+struct AllPassFilter {
+	buffer:Vec<f32>,
+	index:usize
+}
+impl AllPassFilter {
+
+	/// Create a new all-pass filter with a delay of the given length.
+	fn new(len:usize) -> AllPassFilter {
+		AllPassFilter {
+			buffer: vec![0.0; len.max(1)],
+			index: 0
+		}
+	}
+
+	/// Process a sample.
+	fn process(&mut self, input:f32) -> f32 {
+		let delayed:f32 = self.buffer[self.index];
+		self.buffer[self.index] = input + delayed * ALL_PASS_FEEDBACK;
+		self.index = (self.index + 1) % self.buffer.len();
+		delayed - input
+	}
+}
+
+
+
+#[derive(PartialEq)]
+pub struct Reverb {
+	id:usize,
+	room_size:f32,
+	damping:f32,
+	pre_delay:f32,
+	width:f32,
+	mix:f32,
+
+	configuration:Option<(u32, usize)>,
+	pre_delay_line:VecDeque<f32>,
+	comb_filters:Vec<Vec<CombFilter>>,
+	all_pass_filters:Vec<Vec<AllPassFilter>>
+}
+impl Reverb {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new Freeverb-style reverb with the given room size from 0 to 1 and the given mix from 0 (dry) to 1 (wet). Uses a damping of 0.5, no pre-delay and full stereo width.
+	pub fn new(room_size:f32, mix:f32) -> Reverb {
+		Reverb {
+			id: create_effect_id(),
+			room_size,
+			damping: 0.5,
+			pre_delay: 0.0,
+			width: 1.0,
+			mix,
+
+			configuration: None,
+			pre_delay_line: VecDeque::new(),
+			comb_filters: Vec::new(),
+			all_pass_filters: Vec::new()
+		}
+	}
+
+
+
+	/* BUILDER METHODS */
+
+	/// Return self with the given damping from 0 to 1. Higher damping makes high frequencies die out faster, like in a room with soft walls.
+	pub fn with_damping(mut self, damping:f32) -> Self {
+		self.damping = damping;
+		self
+	}
+
+	/// Return self with the given pre-delay in milliseconds. This is the time between the dry sound and the start of the reverb.
+	pub fn with_pre_delay(mut self, pre_delay:f32) -> Self {
+		self.pre_delay = pre_delay;
+		self
+	}
+
+	/// Return self with the given stereo width from 0 to 1. At 0, all channels get the same reverb.
+	pub fn with_width(mut self, width:f32) -> Self {
+		self.width = width;
+		self
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the amount the comb filters feed back, which sets how long the reverb lasts.
+	fn feedback(&self) -> f32 {
+		self.room_size.clamp(0.0, 1.0) * ROOM_SCALE + ROOM_OFFSET
+	}
+
+	/// Get the length of a filter tuned at the given length for the given sample rate.
+	fn filter_len(tuned_len:usize, sample_rate:u32) -> usize {
+		((tuned_len as f32 * sample_rate as f32 / TUNING_SAMPLE_RATE).round() as usize).max(1)
+	}
+
+	/// Get the amount of samples of pre-delay for the given sample rate.
+	fn pre_delay_len(&self, sample_rate:u32) -> usize {
+		(self.pre_delay.max(0.0) * 0.001 * sample_rate as f32).round() as usize
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Reset the state for the given sample rate and channel count. Each channel gets slightly longer filters, so the channels sound different.
+	fn initialize(&mut self, sample_rate:u32, channel_count:usize) {
+		self.configuration = Some((sample_rate, channel_count));
+		self.pre_delay_line = VecDeque::new();
+		self.comb_filters = (0..channel_count).map(|channel_index| COMB_LENGTHS.iter().map(|len| CombFilter::new(Reverb::filter_len(len + channel_index * STEREO_SPREAD, sample_rate))).collect()).collect();
+		self.all_pass_filters = (0..channel_count).map(|channel_index| ALL_PASS_LENGTHS.iter().map(|len| AllPassFilter::new(Reverb::filter_len(len + channel_index * STEREO_SPREAD, sample_rate))).collect()).collect();
+	}
+}
+impl AudioEffect for Reverb {
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the ID of the effect.
+	fn id(&self) -> usize {
+		self.id
+	}
+
+	/// Get the name of the effect.
+	fn name(&self) -> &str {
+		"reverb"
+	}
+
+	/// Get the amount of samples the reverb continues after the input ends, until it decayed by 60 decibels.
+	fn tail(&self, sample_rate:u32, channel_count:usize) -> usize {
+		let longest_comb_len:usize = Reverb::filter_len(COMB_LENGTHS[COMB_LENGTHS.len() - 1] + channel_count.saturating_sub(1) * STEREO_SPREAD, sample_rate);
+		let all_pass_len:usize = ALL_PASS_LENGTHS.iter().map(|len| Reverb::filter_len(len + channel_count.saturating_sub(1) * STEREO_SPREAD, sample_rate)).sum();
+		let decay_cycles:usize = (TAIL_DECAY.ln() / self.feedback().ln()).ceil() as usize;
+		self.pre_delay_len(sample_rate) + longest_comb_len * decay_cycles + all_pass_len
+	}
+
+	/// Clone the effect into a box.
+	fn boxed(&self) -> Box<dyn AudioEffect> {
+		Box::new(
+			Reverb::new(self.room_size, self.mix)
+				.with_damping(self.damping)
+				.with_pre_delay(self.pre_delay)
+				.with_width(self.width)
+		)
+	}
+
+	/// Allow downcasting.
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Apply the effect to the given buffer.
+	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, _channel_count:&mut usize) {
+		if data.is_empty() {
+			return;
+		}
+		if self.configuration != Some((*sample_rate, data.len())) {
+			self.initialize(*sample_rate, data.len());
+		}
+
+		// Resize the pre-delay when it changed, keeping the state of the filters.
+		let pre_delay_len:usize = self.pre_delay_len(*sample_rate);
+		while self.pre_delay_line.len() < pre_delay_len {
+			self.pre_delay_line.push_front(0.0);
+		}
+		while self.pre_delay_line.len() > pre_delay_len {
+			self.pre_delay_line.pop_front();
+		}
+		let feedback:f32 = self.feedback();
+		let damping:f32 = self.damping.clamp(0.0, 1.0) * DAMPING_SCALE;
+		let width:f32 = self.width.clamp(0.0, 1.0);
+		let mix:f32 = self.mix.clamp(0.0, 1.0);
+		let own_gain:f32 = mix * (width * 0.5 + 0.5);
+		let other_gain:f32 = mix * (1.0 - width) * 0.5;
+		let channel_count:usize = data.len();
+
+		// Loop through data.
+		let mut wet:Vec<f32> = vec![0.0; channel_count];
+		for sample_index in 0..data[0].len() {
+
+			// Feed the mono sum through the pre-delay into the filters of each channel.
+			self.pre_delay_line.push_back(data.iter().map(|channel| channel[sample_index]).sum::<f32>() / channel_count as f32 * INPUT_GAIN);
+			let input:f32 = self.pre_delay_line.pop_front().unwrap_or(0.0);
+			for ((wet_sample, comb_filters), all_pass_filters) in wet.iter_mut().zip(&mut self.comb_filters).zip(&mut self.all_pass_filters) {
+				let combined:f32 = comb_filters.iter_mut().map(|filter| filter.process(input, feedback, damping)).sum();
+				*wet_sample = all_pass_filters.iter_mut().fold(combined, |sample, filter| filter.process(sample));
+			}
+
+			// Mix the reverb of each channel with that of the other channels by the width.
+			let wet_sum:f32 = wet.iter().sum();
+			for (channel, wet_sample) in data.iter_mut().zip(&wet) {
+				let other_wet:f32 = if channel_count > 1 { (wet_sum - wet_sample) / (channel_count - 1) as f32 } else { *wet_sample };
+				channel[sample_index] = channel[sample_index] * (1.0 - mix) + wet_sample * own_gain + other_wet * other_gain;
+			}
+		}
+	}
+
+	/// Forget the audio processed so far, so the next output does not depend on it.
+	fn reset(&mut self) {
+		self.configuration = None;
+	}
+
+
+
+	/* SETTING METHODS */
+
+	/// Get a list of settings with their names.
+	fn settings(&self) -> Vec<(&str, &f32)> {
+		vec![
+			("room_size", &self.room_size),
+			("damping", &self.damping),
+			("pre_delay", &self.pre_delay),
+			("width", &self.width),
+			("mix", &self.mix)
+		]
+	}
+
+	/// Get a mutable list of settings with their names.
+	fn settings_mut(&mut self) -> Vec<(&str, &mut f32)> {
+		vec![
+			("room_size", &mut self.room_size),
+			("damping", &mut self.damping),
+			("pre_delay", &mut self.pre_delay),
+			("width", &mut self.width),
+			("mix", &mut self.mix)
+		]
+	}
+}
\ No newline at end of file
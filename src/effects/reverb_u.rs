@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioEffect, AudioGenerator, Reverb };
+
+
+
+	/// Create a single impulse followed by silence.
+	fn impulse(sample_count:usize) -> Vec<f32> {
+		let mut samples:Vec<f32> = vec![0.0; sample_count];
+		samples[0] = 1.0;
+		samples
+	}
+
+	/// Get the energy of the given data.
+	fn energy(data:&[f32]) -> f32 {
+		data.iter().map(|sample| sample * sample).sum()
+	}
+
+
+
+	/* REVERB TESTS */
+
+	#[test]
+	fn test_effect_reverb_dry() {
+		let samples:Vec<f32> = (0..4410).map(|index| (index as f32 * 0.05).sin()).collect();
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone(), samples.clone()], 44100);
+		buffer.add_effect(Reverb::new(0.5, 0.0));
+		let data:&Vec<Vec<f32>> = buffer.processed_data();
+		for channel in data {
+			assert_eq!(channel[..4410], samples[..]);
+			assert!(channel[4410..].iter().all(|sample| *sample == 0.0));
+		}
+	}
+
+	#[test]
+	fn test_effect_reverb_pre_delay() {
+
+		// The reverb starts once the impulse passed the pre-delay and the shortest comb filter.
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![impulse(4410)], 44100);
+		buffer.add_effect(Reverb::new(0.5, 1.0).with_pre_delay(10.0));
+		let data:&[f32] = &buffer.processed_data()[0];
+		assert_eq!(data.iter().position(|sample| *sample != 0.0), Some(441 + 1116));
+	}
+
+	#[test]
+	fn test_effect_reverb_room_size() {
+		let mut small_buffer:AudioBuffer = AudioBuffer::new(vec![impulse(4410)], 44100);
+		small_buffer.add_effect(Reverb::new(0.2, 1.0));
+		let mut large_buffer:AudioBuffer = AudioBuffer::new(vec![impulse(4410)], 44100);
+		large_buffer.add_effect(Reverb::new(0.9, 1.0));
+		let small:&[f32] = &small_buffer.processed_data()[0];
+		let large:&[f32] = &large_buffer.processed_data()[0];
+		assert!(large.len() > small.len());
+		assert!(energy(&large[22050..44100]) > energy(&small[22050..small.len().min(44100)]) * 10.0);
+	}
+
+	#[test]
+	fn test_effect_reverb_width() {
+		let mut mono_buffer:AudioBuffer = AudioBuffer::new(vec![impulse(4410), impulse(4410)], 44100);
+		mono_buffer.add_effect(Reverb::new(0.5, 1.0).with_width(0.0));
+		let mono:&Vec<Vec<f32>> = mono_buffer.processed_data();
+		assert!(mono[0].iter().zip(&mono[1]).all(|(left, right)| (left - right).abs() < 0.000001));
+		let mut stereo_buffer:AudioBuffer = AudioBuffer::new(vec![impulse(4410), impulse(4410)], 44100);
+		stereo_buffer.add_effect(Reverb::new(0.5, 1.0).with_width(1.0));
+		let stereo:&Vec<Vec<f32>> = stereo_buffer.processed_data();
+		assert!(stereo[0].iter().zip(&stereo[1]).any(|(left, right)| (left - right).abs() > 0.001));
+	}
+
+
+
+	/* TAIL TESTS */
+
+	#[test]
+	fn test_effect_reverb_tail() {
+		let reverb:Reverb = Reverb::new(0.5, 0.5);
+		let tail_len:usize = reverb.tail(44100, 1);
+		assert!(tail_len > 44100);
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![impulse(4410)], 44100);
+		buffer.add_effect(reverb);
+		let data:&[f32] = &buffer.processed_data()[0];
+
+		// The reverb sounds past the end of the input and has died out at the end of the tail.
+		assert_eq!(data.len(), 4410 + tail_len);
+		assert!(energy(&data[4410..8820]) > 0.0001);
+		assert!(data[data.len() - 4410..].iter().all(|sample| sample.abs() < 0.0001));
+	}
+
+	#[test]
+	fn test_effect_reverb_duration() {
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![impulse(8000)], 8000);
+		buffer.add_effect(Reverb::new(0.8, 0.3));
+		let duration:f32 = buffer.duration().as_secs_f32();
+		assert!(duration > 2.0);
+		assert!((duration - buffer.processed_data()[0].len() as f32 / 8000.0).abs() < 0.001);
+	}
+
+	#[test]
+	fn test_effect_reverb_streaming() {
+		let samples:Vec<f32> = (0..8000).map(|index| (index as f32 * 0.05).sin() * (index as f32 * 0.001).sin()).collect();
+		let mut whole_buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone(), samples.clone()], 8000);
+		whole_buffer.add_effect(Reverb::new(0.7, 0.4).with_pre_delay(20.0).with_damping(0.3));
+		let whole:Vec<f32> = whole_buffer.processed_data()[1].clone();
+
+		// Taking the buffer in chunks should produce the same samples as processing it at once, including the tail.
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone(), samples], 8000);
+		buffer.add_effect(Reverb::new(0.7, 0.4).with_pre_delay(20.0).with_damping(0.3));
+		let mut chunked:Vec<f32> = Vec::new();
+		while !buffer.is_depleted() {
+			chunked.extend(buffer.take(700).remove(1));
+		}
+		assert!(whole.len() > 8000);
+		assert_eq!(whole[..], chunked[..whole.len()]);
+		assert!(chunked[whole.len()..].iter().all(|sample| *sample == 0.0));
+	}
+
+	#[test]
+	fn test_effect_reverb_live_pre_delay() {
+		let samples:Vec<f32> = (0..8000).map(|index| (index as f32 * 0.05).sin()).collect();
+		let take_halves = |new_pre_delay:f32| {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 8000);
+			buffer.add_effect(Reverb::new(0.7, 0.5));
+			buffer.take(4000);
+			buffer.set_effect_setting("reverb", "pre_delay", new_pre_delay);
+			buffer.take(4000).remove(0)
+		};
+
+		// Changing the pre-delay keeps the reverb that is already sounding.
+		let unchanged:Vec<f32> = take_halves(0.0);
+		let changed:Vec<f32> = take_halves(20.0);
+		assert_eq!(changed[..100], unchanged[..100]);
+	}
+
+	#[test]
+	fn test_effect_reverb_settings() {
+		Reverb::new(0.5, 0.3).settings_test();
+	}
+}
\ No newline at end of file
@@ -0,0 +1,217 @@
+use std::f64::consts::PI;
+
+
+
+const KERNEL_PHASES:usize = 256;
+const MIN_CUTOFF:f32 = 1.0 / 16.0;
+
+
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ResampleQuality { Linear, Low, #[default] Medium, High }
+impl ResampleQuality {
+
+	/// Get the amount of input samples on each side of the output position that are used to calculate an output sample, when not filtering below the input rate.
+	fn half_width(&self) -> usize {
+		match self {
+			ResampleQuality::Linear => 1,
+			ResampleQuality::Low => 8,
+			ResampleQuality::Medium => 16,
+			ResampleQuality::High => 32
+		}
+	}
+
+	/// Get the beta of the kaiser window applied to the sinc kernel. Higher values increase stopband attenuation at the cost of a wider transition band.
+	fn window_beta(&self) -> f64 {
+		match self {
+			ResampleQuality::Linear => 0.0,
+			ResampleQuality::Low => 6.0,
+			ResampleQuality::Medium => 8.0,
+			ResampleQuality::High => 10.0
+		}
+	}
+
+	/// Get the cutoff frequency of the kernel relative to the lowest of the input and output nyquist frequency.
+	fn rolloff(&self) -> f32 {
+		match self {
+			ResampleQuality::Linear => 1.0,
+			ResampleQuality::Low => 0.85,
+			ResampleQuality::Medium => 0.92,
+			ResampleQuality::High => 0.95
+		}
+	}
+}
+
+
+
+#[derive(Clone, PartialEq)]
+pub(crate) struct SincResampler {
+	quality:ResampleQuality,
+	kernel:Vec<f32>,
+	kernel_sums:Vec<f32>,
+	kernel_multiplier:f32,
+	kernel_half_width:usize,
+	history:Vec<Vec<f32>>,
+	position:f64
+}
+impl SincResampler {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new resampler with the given quality.
+	pub fn new(quality:ResampleQuality) -> SincResampler {
+		SincResampler {
+			quality,
+			kernel: Vec::new(),
+			kernel_sums: Vec::new(),
+			kernel_multiplier: 0.0,
+			kernel_half_width: 0,
+			history: Vec::new(),
+			position: 0.0
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the quality of the resampler.
+	pub fn quality(&self) -> ResampleQuality {
+		self.quality
+	}
+
+	/// Get the amount of input samples on each side of the output position used for the given duration multiplier. When the output rate is lower than the input rate, the kernel widens to filter out frequencies the output cannot represent.
+	pub fn half_width(&self, multiplier:f32) -> usize {
+		if self.quality == ResampleQuality::Linear {
+			1
+		} else {
+			(self.quality.half_width() as f32 / multiplier.clamp(MIN_CUTOFF, 1.0)).ceil() as usize
+		}
+	}
+
+	/// Get the amount of input samples the output lags behind the input. After the last input sample, this many samples of silence need to be processed for all output to be produced.
+	pub fn latency(&self, multiplier:f32) -> usize {
+		self.half_width(multiplier) + 1
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Resample the given data by the given duration multiplier. Input that is needed to calculate future output samples is kept, so data can be processed in chunks without seams. The output lags behind the input by the latency of the resampler.
+	pub fn process(&mut self, data:&[Vec<f32>], multiplier:f32) -> Vec<Vec<f32>> {
+		self.prepare_kernel(multiplier);
+		let half_width:usize = self.kernel_half_width;
+
+		// Start with silence before the first sample so the first output sample lines up with the first input sample.
+		if self.history.len() != data.len() {
+			self.history = vec![vec![0.0; half_width]; data.len()];
+			self.position = half_width as f64;
+		}
+		for (history_channel, channel) in self.history.iter_mut().zip(data) {
+			history_channel.extend_from_slice(channel);
+		}
+		let history_len:usize = self.history.first().map(|channel| channel.len()).unwrap_or(0);
+
+		// Calculate output samples for as long as there is enough input ahead of the position.
+		let position_increment:f64 = 1.0 / multiplier as f64;
+		let mut output:Vec<Vec<f32>> = vec![Vec::with_capacity((history_len as f64 * multiplier as f64) as usize); data.len()];
+		let tap_count:usize = half_width * 2;
+		while (self.position.floor() as usize) + half_width < history_len {
+			let first_tap:usize = self.position.floor() as usize + 1 - half_width;
+
+			// Interpolate between the two nearest phases of the kernel.
+			let phase_position:f64 = (self.position - self.position.floor()) * KERNEL_PHASES as f64;
+			let phase:usize = (phase_position as usize).min(KERNEL_PHASES - 1);
+			let phase_fraction:f32 = (phase_position - phase as f64) as f32;
+			let left_weights:&[f32] = &self.kernel[phase * tap_count..(phase + 1) * tap_count];
+			let right_weights:&[f32] = &self.kernel[(phase + 1) * tap_count..(phase + 2) * tap_count];
+			let weight_sum:f32 = self.kernel_sums[phase] + (self.kernel_sums[phase + 1] - self.kernel_sums[phase]) * phase_fraction;
+			let weight_scale:f32 = if self.quality == ResampleQuality::Linear || weight_sum == 0.0 { 1.0 } else { 1.0 / weight_sum };
+			for (output_channel, history_channel) in output.iter_mut().zip(&self.history) {
+				let taps:&[f32] = &history_channel[first_tap..first_tap + tap_count];
+				let mut left_sample:f32 = 0.0;
+				let mut right_sample:f32 = 0.0;
+				for tap_index in 0..tap_count {
+					left_sample += taps[tap_index] * left_weights[tap_index];
+					right_sample += taps[tap_index] * right_weights[tap_index];
+				}
+				output_channel.push((left_sample + (right_sample - left_sample) * phase_fraction) * weight_scale);
+			}
+			self.position += position_increment;
+		}
+
+		// Remove input that is no longer needed.
+		let consumed:usize = (self.position.floor() as usize + 1).saturating_sub(half_width).min(history_len);
+		for history_channel in &mut self.history {
+			history_channel.drain(..consumed);
+		}
+		self.position -= consumed as f64;
+
+		// Return output.
+		output
+	}
+
+	/// Forget the input that was kept for future output, so the next data is processed as if it is the start of the input.
+	pub fn reset(&mut self) {
+		self.history = Vec::new();
+		self.position = 0.0;
+	}
+
+	/// Build the polyphase kernel table for the given duration multiplier, unless it was already built. The table holds the weights of all taps for each phase, plus one extra phase to interpolate towards.
+	fn prepare_kernel(&mut self, multiplier:f32) {
+		if self.kernel_multiplier == multiplier && !self.kernel.is_empty() {
+			return;
+		}
+		let half_width:usize = self.half_width(multiplier);
+		if half_width != self.kernel_half_width {
+			self.history = Vec::new();
+		}
+		self.kernel_multiplier = multiplier;
+		self.kernel_half_width = half_width;
+
+		// Sample a kaiser-windowed sinc function with its cutoff at the lowest nyquist frequency. Linear interpolation uses a triangle function instead.
+		let cutoff:f64 = (multiplier.clamp(MIN_CUTOFF, 1.0) * self.quality.rolloff()) as f64;
+		let beta:f64 = self.quality.window_beta();
+		let window_scale:f64 = 1.0 / SincResampler::bessel_i0(beta);
+		let kernel_function = |distance:f64| -> f32 {
+			let distance:f64 = distance.abs();
+			if distance >= half_width as f64 {
+				0.0
+			} else if self.quality == ResampleQuality::Linear {
+				(1.0 - distance) as f32
+			} else {
+				let window_position:f64 = distance / half_width as f64;
+				let window:f64 = SincResampler::bessel_i0(beta * (1.0 - window_position * window_position).sqrt()) * window_scale;
+				let sinc:f64 = if distance == 0.0 { 1.0 } else { (PI * cutoff * distance).sin() / (PI * cutoff * distance) };
+				(cutoff * sinc * window) as f32
+			}
+		};
+
+		// Create table.
+		let tap_count:usize = half_width * 2;
+		self.kernel = Vec::with_capacity((KERNEL_PHASES + 1) * tap_count);
+		self.kernel_sums = Vec::with_capacity(KERNEL_PHASES + 1);
+		for phase in 0..=KERNEL_PHASES {
+			let fraction:f64 = phase as f64 / KERNEL_PHASES as f64;
+			let weights:Vec<f32> = (0..tap_count).map(|tap_index| kernel_function(fraction + (half_width - 1) as f64 - tap_index as f64)).collect();
+			self.kernel_sums.push(weights.iter().sum());
+			self.kernel.extend(weights);
+		}
+	}
+
+	/// Calculate the zeroth order modified bessel function of the first kind, used by the kaiser window.
+	fn bessel_i0(value:f64) -> f64 {
+		let mut sum:f64 = 1.0;
+		let mut term:f64 = 1.0;
+		let half_value:f64 = value / 2.0;
+		for index in 1..64 {
+			term *= half_value / index as f64;
+			sum += term * term;
+			if term * term < sum * 1e-12 {
+				break;
+			}
+		}
+		sum
+	}
+}
\ No newline at end of file
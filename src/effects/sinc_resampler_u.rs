@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+	use super::super::sinc_resampler::SincResampler;
+	use crate::ResampleQuality;
+
+
+
+	const QUALITIES:[ResampleQuality; 4] = [ResampleQuality::Linear, ResampleQuality::Low, ResampleQuality::Medium, ResampleQuality::High];
+
+
+
+	#[test]
+	fn test_constant_signal() {
+		for quality in QUALITIES {
+			for multiplier in [0.5, 0.9, 1.0884354, 3.0] {
+				let mut resampler:SincResampler = SincResampler::new(quality);
+				let output:Vec<Vec<f32>> = resampler.process(&[vec![0.5; 1000]], multiplier);
+				let half_width:usize = resampler.half_width(multiplier);
+				for sample in &output[0][(half_width as f32 * multiplier) as usize + 1..] {
+					assert!((sample - 0.5).abs() < 0.001, "{quality:?} at {multiplier} produced {sample}");
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_latency() {
+		for quality in QUALITIES {
+			let mut resampler:SincResampler = SincResampler::new(quality);
+			let mut output_len:usize = resampler.process(&[vec![0.5; 100]], 2.0)[0].len();
+			output_len += resampler.process(&[vec![0.0; resampler.latency(2.0)]], 2.0)[0].len();
+			assert!(output_len >= 200);
+		}
+	}
+
+	#[test]
+	fn test_chunked_processing() {
+		let sine:Vec<f32> = (0..2000).map(|index| (index as f32 * 0.05).sin()).collect();
+		for quality in QUALITIES {
+			let whole:Vec<f32> = SincResampler::new(quality).process(std::slice::from_ref(&sine), 1.0884354).remove(0);
+			let mut resampler:SincResampler = SincResampler::new(quality);
+			let chunked:Vec<f32> = sine.chunks(37).flat_map(|chunk| resampler.process(&[chunk.to_vec()], 1.0884354).remove(0)).collect();
+			assert_eq!(whole.len(), chunked.len());
+			for (left, right) in whole.iter().zip(&chunked) {
+				assert!((left - right).abs() < 0.00001);
+			}
+		}
+	}
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+use std::f64::consts::TAU;
+
+
+
+/// Create a sine wave of the given frequency and amplitude.
+pub(crate) fn sine(frequency:f32, amplitude:f32, sample_rate:u32, sample_count:usize) -> Vec<f32> {
+	(0..sample_count).map(|index| (index as f64 / sample_rate as f64 * frequency as f64 * TAU).sin() as f32 * amplitude).collect()
+}
+
+/// Estimate the frequency of the given data by counting zero crossings, ignoring the edges.
+pub(crate) fn frequency(data:&[f32], sample_rate:u32) -> f32 {
+	let data:&[f32] = &data[data.len() / 10..data.len() * 9 / 10];
+	let crossings:usize = data.windows(2).filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0)).count();
+	crossings as f32 / 2.0 / (data.len() as f32 / sample_rate as f32)
+}
\ No newline at end of file
@@ -0,0 +1,237 @@
+use crate::{ audio_effect::create_effect_id, AudioEffect };
+use std::{ any::Any, f32::consts::TAU };
+
+
+
+const HOP_DURATION:f32 = 0.02;
+const SEARCH_DURATION:f32 = 0.01;
+const COARSE_SEARCH_STEP:usize = 4;
+const FINE_SEARCH_RANGE:usize = COARSE_SEARCH_STEP - 1;
+
+
+
+#[derive(PartialEq)]
+pub struct TimeStretcher {
+	id:usize,
+	duration_multiplier:f32,
+
+	sample_rate:u32,
+	hop_len:usize,
+	search_len:usize,
+	window:Vec<f32>,
+	history:Vec<Vec<f32>>,
+	mono_history:Vec<f32>,
+	analysis_position:f64,
+	continuation_position:Option<usize>,
+	overlap:Vec<Vec<f32>>,
+	skip_len:usize
+}
+impl TimeStretcher {
+
+	/// Create a new time stretcher. Multiplies the duration by the given multiplier without changing the pitch.
+	pub fn new(multiplier:f32) -> TimeStretcher {
+		TimeStretcher {
+			id: create_effect_id(),
+			duration_multiplier: multiplier,
+
+			sample_rate: 0,
+			hop_len: 0,
+			search_len: 0,
+			window: Vec::new(),
+			history: Vec::new(),
+			mono_history: Vec::new(),
+			analysis_position: 0.0,
+			continuation_position: None,
+			overlap: Vec::new(),
+			skip_len: 0
+		}
+	}
+
+	/// Get the amount of samples between frames in the output and the amount of samples a frame can be moved to line up with the previous frame.
+	fn frame_lengths(sample_rate:u32) -> (usize, usize) {
+		(((sample_rate as f32 * HOP_DURATION) as usize).max(2), ((sample_rate as f32 * SEARCH_DURATION) as usize).max(1))
+	}
+
+	/// Reset the state for the given sample rate and channel count.
+	fn initialize(&mut self, sample_rate:u32, channel_count:usize) {
+		let (hop_len, search_len) = TimeStretcher::frame_lengths(sample_rate);
+		self.sample_rate = sample_rate;
+		self.hop_len = hop_len;
+		self.search_len = search_len;
+		self.window = (0..hop_len * 2).map(|index| 0.5 - 0.5 * (index as f32 / (hop_len * 2) as f32 * TAU).cos()).collect();
+
+		// Start the first frame a hop before the first sample, so the first output is not faded in. The output of that hop is skipped.
+		self.history = vec![vec![0.0; search_len + hop_len]; channel_count];
+		self.mono_history = vec![0.0; search_len + hop_len];
+		self.analysis_position = search_len as f64;
+		self.continuation_position = None;
+		self.overlap = vec![vec![0.0; hop_len]; channel_count];
+		self.skip_len = hop_len;
+	}
+
+	/// Find the start of the frame near the nominal position that best continues the waveform of the previous frame. The continuation position is where the second half of the previous frame started.
+	fn best_position(&self, continuation_position:usize, nominal_position:usize) -> usize {
+		let template:&[f32] = &self.mono_history[continuation_position..continuation_position + self.hop_len];
+		let score = |candidate:usize, stride:usize| -> f32 {
+			let candidate_data:&[f32] = &self.mono_history[candidate..candidate + self.hop_len];
+			let mut correlation:f32 = 0.0;
+			let mut energy:f32 = 0.0;
+			for index in (0..self.hop_len).step_by(stride) {
+				correlation += template[index] * candidate_data[index];
+				energy += candidate_data[index] * candidate_data[index];
+			}
+			correlation / (energy + 1e-9).sqrt()
+		};
+
+		// Search coarsely through the full range, then refine around the best candidate.
+		let search_start:usize = nominal_position - self.search_len;
+		let search_end:usize = nominal_position + self.search_len;
+		let mut best_position:usize = nominal_position;
+		let mut best_score:f32 = score(nominal_position, COARSE_SEARCH_STEP);
+		for candidate in (search_start..=search_end).step_by(COARSE_SEARCH_STEP) {
+			let candidate_score:f32 = score(candidate, COARSE_SEARCH_STEP);
+			if candidate_score > best_score {
+				best_position = candidate;
+				best_score = candidate_score;
+			}
+		}
+		let coarse_position:usize = best_position;
+		best_score = score(coarse_position, 1);
+		for candidate in coarse_position.saturating_sub(FINE_SEARCH_RANGE).max(search_start)..=(coarse_position + FINE_SEARCH_RANGE).min(search_end) {
+			let candidate_score:f32 = score(candidate, 1);
+			if candidate_score > best_score {
+				best_position = candidate;
+				best_score = candidate_score;
+			}
+		}
+		best_position
+	}
+}
+impl AudioEffect for TimeStretcher {
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the ID of the effect.
+	fn id(&self) -> usize {
+		self.id
+	}
+
+	/// Get the name of the effect.
+	fn name(&self) -> &str {
+		"time_stretcher"
+	}
+
+	/// Return the time multiplier of this effect.
+	fn sample_multiplier(&self, _sample_rate:u32, _channel_count:usize) -> f32 {
+		self.duration_multiplier.abs()
+	}
+
+	/// Get the amount of samples the output lags behind the input.
+	fn latency(&self, sample_rate:u32, _channel_count:usize) -> usize {
+		if self.duration_multiplier == 1.0 && self.history.is_empty() {
+			0
+		} else {
+			let (hop_len, search_len) = TimeStretcher::frame_lengths(sample_rate);
+			hop_len * 4 + search_len
+		}
+	}
+
+	/// Clone the effect into a box.
+	fn boxed(&self) -> Box<dyn AudioEffect> {
+		Box::new(TimeStretcher::new(self.duration_multiplier))
+	}
+
+	/// Allow downcasting.
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Apply the effect to the given buffer.
+	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, _channel_count:&mut usize) {
+		if (self.duration_multiplier == 1.0 && self.history.is_empty()) || data.is_empty() {
+			return;
+		}
+		if self.sample_rate != *sample_rate || self.history.len() != data.len() {
+			self.initialize(*sample_rate, data.len());
+		}
+
+		// Add data to history.
+		for (history_channel, channel) in self.history.iter_mut().zip(data.iter()) {
+			history_channel.extend_from_slice(channel);
+		}
+		for sample_index in 0..data[0].len() {
+			self.mono_history.push(data.iter().map(|channel| channel[sample_index]).sum());
+		}
+		let history_len:usize = self.mono_history.len();
+
+		// Overlap-add windowed frames for as long as there is enough input ahead of the analysis position.
+		let hop_len:usize = self.hop_len;
+		let mut output:Vec<Vec<f32>> = vec![Vec::with_capacity((data[0].len() as f32 * self.duration_multiplier.abs()) as usize + hop_len); data.len()];
+		loop {
+			let nominal_position:usize = self.analysis_position.floor() as usize;
+			if nominal_position + self.search_len + hop_len * 2 > history_len {
+				break;
+			}
+			let position:usize = match self.continuation_position {
+				Some(continuation_position) => self.best_position(continuation_position, nominal_position),
+				None => nominal_position
+			};
+			for ((output_channel, overlap_channel), history_channel) in output.iter_mut().zip(&mut self.overlap).zip(&self.history) {
+				for index in 0..hop_len {
+					output_channel.push(overlap_channel[index] + history_channel[position + index] * self.window[index]);
+					overlap_channel[index] = history_channel[position + hop_len + index] * self.window[hop_len + index];
+				}
+			}
+			self.continuation_position = Some(position + hop_len);
+			self.analysis_position += hop_len as f64 / self.duration_multiplier.abs().max(f32::EPSILON) as f64;
+		}
+
+		// Skip the output of the hop before the first sample.
+		if self.skip_len > 0 {
+			let skip_len:usize = self.skip_len.min(output[0].len());
+			output.iter_mut().for_each(|channel| { channel.drain(..skip_len); });
+			self.skip_len -= skip_len;
+		}
+
+		// Remove input that is no longer needed.
+		let mut consumed:usize = (self.analysis_position.floor() as usize).saturating_sub(self.search_len);
+		if let Some(continuation_position) = self.continuation_position {
+			consumed = consumed.min(continuation_position);
+		}
+		consumed = consumed.min(history_len);
+		self.history.iter_mut().for_each(|channel| { channel.drain(..consumed); });
+		self.mono_history.drain(..consumed);
+		self.analysis_position -= consumed as f64;
+		self.continuation_position = self.continuation_position.map(|continuation_position| continuation_position - consumed);
+
+		// Set new data.
+		*data = output;
+	}
+
+	/// Forget the audio processed so far, so the next output does not depend on it.
+	fn reset(&mut self) {
+		self.history = Vec::new();
+	}
+
+
+
+	/* SETTING METHODS */
+
+	/// Get a list of settings with their names.
+	fn settings(&self) -> Vec<(&str, &f32)> {
+		vec![
+			("duration_multiplier", &self.duration_multiplier)
+		]
+	}
+
+	/// Get a mutable list of settings with their names.
+	fn settings_mut(&mut self) -> Vec<(&str, &mut f32)> {
+		vec![
+			("duration_multiplier", &mut self.duration_multiplier)
+		]
+	}
+}
\ No newline at end of file
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ AudioBuffer, AudioEffect, AudioGenerator, DurationModifier, TimeStretcher };
+	use super::super::test_signals::{ frequency, sine };
+	use std::{ f32::consts::TAU, time::Duration };
+
+
+
+	/* DURATION TESTS */
+
+	#[test]
+	fn test_effect_time_stretch_duration() {
+		for multiplier in [0.5, 0.75, 1.5, 2.0] {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 8000)], 8000);
+			buffer.add_effect(TimeStretcher::new(multiplier));
+			assert_eq!(buffer.duration(), Duration::from_secs_f32(multiplier));
+			assert_eq!(buffer.processed_data()[0].len(), (8000.0 * multiplier) as usize);
+		}
+	}
+
+	#[test]
+	fn test_effect_time_stretch_keeps_pitch() {
+		for multiplier in [0.5, 0.75, 1.5, 2.0] {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 8000)], 8000);
+			buffer.add_effect(TimeStretcher::new(multiplier));
+			let stretched_frequency:f32 = frequency(buffer.processed_data().first().unwrap(), 8000);
+			println!("{multiplier}: {stretched_frequency}Hz");
+			assert!((stretched_frequency - 440.0).abs() < 440.0 * 0.02);
+		}
+
+		// A duration modifier changes the pitch instead.
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 8000)], 8000);
+		buffer.add_effect(DurationModifier::new(2.0));
+		assert!((frequency(buffer.processed_data().first().unwrap(), 8000) - 220.0).abs() < 220.0 * 0.02);
+	}
+
+	#[test]
+	fn test_effect_time_stretch_smooth() {
+
+		// A stretched sine should not have jumps larger than the steepest slope of the sine.
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 8000)], 8000);
+		buffer.add_effect(TimeStretcher::new(1.5));
+		let max_slope:f32 = 0.5 * TAU * 440.0 / 8000.0;
+		let data:&[f32] = &buffer.processed_data()[0];
+		let max_step:f32 = data[1000..data.len() - 1000].windows(2).map(|pair| (pair[1] - pair[0]).abs()).fold(0.0, f32::max);
+		assert!(max_step < max_slope * 1.2, "step of {max_step} exceeds slope of {max_slope}");
+	}
+
+	#[test]
+	fn test_effect_time_stretch_unchanged() {
+		let samples:Vec<f32> = sine(440.0, 0.5, 8000, 8000);
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone()], 8000);
+		buffer.add_effect(TimeStretcher::new(1.0));
+		assert_eq!(buffer.processed_data()[0], samples);
+	}
+
+	#[test]
+	fn test_effect_time_stretch_streaming() {
+		let mut whole_buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 8000), sine(660.0, 0.5, 8000, 8000)], 8000);
+		whole_buffer.add_effect(TimeStretcher::new(1.3));
+		let whole:Vec<Vec<f32>> = whole_buffer.processed_data().clone();
+
+		// Taking the buffer in chunks should produce the same samples as processing it at once.
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![sine(440.0, 0.5, 8000, 8000), sine(660.0, 0.5, 8000, 8000)], 8000);
+		buffer.add_effect(TimeStretcher::new(1.3));
+		let mut chunked:Vec<Vec<f32>> = vec![Vec::new(); 2];
+		while !buffer.is_depleted() {
+			for (channel, new_data) in chunked.iter_mut().zip(buffer.take(50)) {
+				channel.extend(new_data);
+			}
+		}
+		for (whole_channel, chunked_channel) in whole.iter().zip(&chunked) {
+			assert_eq!(whole_channel.len(), 10400);
+			assert_eq!(whole_channel[..], chunked_channel[..whole_channel.len()]);
+		}
+	}
+
+
+
+	/* SETTINGS */
+
+	#[test]
+	fn test_settings() {
+		TimeStretcher::new(1.5).settings_test();
+	}
+}
\ No newline at end of file
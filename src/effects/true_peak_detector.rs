@@ -0,0 +1,62 @@
+use std::{ collections::VecDeque, f32::consts::PI };
+
+
+
+const OVERSAMPLING:usize = 4;
+const HALF_WIDTH:usize = 8;
+
+
+
+#[derive(Clone, PartialEq)]
+pub(crate) struct TruePeakDetector {
+	kernels:Vec<Vec<f32>>,
+	history:Vec<VecDeque<f32>>
+}
+impl TruePeakDetector {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new detector that finds peaks between samples by oversampling the audio 4 times.
+	pub fn new(channel_count:usize) -> TruePeakDetector {
+		TruePeakDetector {
+
+			// Create a hann-windowed sinc kernel for each position between two samples.
+			kernels: (1..OVERSAMPLING).map(|phase| {
+				let fraction:f32 = phase as f32 / OVERSAMPLING as f32;
+				(0..HALF_WIDTH * 2).map(|tap_index| {
+					let distance:f32 = fraction + (HALF_WIDTH - 1) as f32 - tap_index as f32;
+					let window:f32 = 0.5 + 0.5 * (distance / HALF_WIDTH as f32 * PI).cos();
+					(PI * distance).sin() / (PI * distance) * window
+				}).collect()
+			}).collect(),
+			history: vec![vec![0.0; HALF_WIDTH * 2].into(); channel_count]
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the amount of samples the detected peaks lag behind the pushed samples.
+	pub fn latency() -> usize {
+		HALF_WIDTH
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Add a sample for each channel and return the highest absolute value of all channels at and just after the sample the detector lags behind.
+	pub fn push<T>(&mut self, samples:T) -> f32 where T:Iterator<Item = f32> {
+		let mut peak:f32 = 0.0;
+		for (history, sample) in self.history.iter_mut().zip(samples) {
+			history.pop_front();
+			history.push_back(sample);
+			peak = peak.max(history[HALF_WIDTH - 1].abs());
+			for kernel in &self.kernels {
+				peak = peak.max(history.iter().zip(kernel).map(|(sample, weight)| sample * weight).sum::<f32>().abs());
+			}
+		}
+		peak
+	}
+}
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::{ audio_effect::create_effect_id, AudioEffect };
+use crate::{ audio_effect::create_effect_id, AudioEffect, LoudnessMeter };
 use std::any::Any;
 
 
@@ -7,7 +7,10 @@ use std::any::Any;
 pub struct VolumeAmplifier {
 	id:usize,
 	maximize_target_volume:Option<f32>,
-	multiplier:f32
+	loudness_target:Option<f32>,
+	multiplier:f32,
+
+	measured_level:Option<f32>
 }
 impl VolumeAmplifier {
 
@@ -16,7 +19,10 @@ impl VolumeAmplifier {
 		VolumeAmplifier {
 			id: create_effect_id(),
 			maximize_target_volume: None,
-			multiplier
+			loudness_target: None,
+			multiplier,
+
+			measured_level: None
 		}
 	}
 
@@ -30,7 +36,55 @@ impl VolumeAmplifier {
 		VolumeAmplifier {
 			id: create_effect_id(),
 			maximize_target_volume: Some(peak_volume),
-			multiplier: 1.0
+			loudness_target: None,
+			multiplier: 1.0,
+
+			measured_level: None
+		}
+	}
+
+	/// Create a volume amplifier that normalizes the volume so the integrated loudness matches the given loudness in LUFS, like -23 for EBU R128 broadcast or -16 for podcasts.
+	pub fn new_loudness_normalizer(target_lufs:f32) -> VolumeAmplifier {
+		VolumeAmplifier {
+			id: create_effect_id(),
+			maximize_target_volume: None,
+			loudness_target: Some(target_lufs),
+			multiplier: 1.0,
+
+			measured_level: None
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the level of the given data the target applies to, the highest peak for a maximizer or the integrated loudness for a loudness normalizer. Returns None without a target.
+	fn measure_level(&self, data:&[Vec<f32>], sample_rate:u32) -> Option<f32> {
+		if self.loudness_target.is_some() {
+			let mut meter:LoudnessMeter = LoudnessMeter::new(sample_rate, data.len());
+			meter.add_samples(data);
+			Some(meter.integrated())
+		} else if self.maximize_target_volume.is_some() {
+			let mut max:f32 = 0.0;
+			for sample in data.iter().flatten() {
+				let sample_abs:f32 = sample.abs();
+				if sample_abs > max {
+					max = sample_abs;
+				}
+			}
+			Some(max)
+		} else {
+			None
+		}
+	}
+
+	/// Get the multiplier that makes data of the given level reach the peak or loudness target. Returns 1.0 without a target.
+	fn target_multiplier(&self, level:Option<f32>) -> f32 {
+		match (level, self.loudness_target, self.maximize_target_volume) {
+			(Some(loudness), Some(loudness_target), _) if loudness.is_finite() => 10.0_f32.powf((loudness_target - loudness) / 20.0),
+			(Some(max), None, Some(target_volume)) => 1.0 / max * target_volume,
+			_ => 1.0
 		}
 	}
 }
@@ -53,7 +107,10 @@ impl AudioEffect for VolumeAmplifier {
 		Box::new(VolumeAmplifier {
 			id: create_effect_id(),
 			maximize_target_volume: self.maximize_target_volume,
-			multiplier: self.multiplier
+			loudness_target: self.loudness_target,
+			multiplier: self.multiplier,
+
+			measured_level: self.measured_level
 		})
 	}
 
@@ -66,21 +123,19 @@ impl AudioEffect for VolumeAmplifier {
 
 	/* USAGE METHODS */
 
-	/// Apply the effect to the given buffer.
-	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, _sample_rate:&mut u32, _channel_count:&mut usize) {
-		let mut multiplier:f32 = 1.0;
+	/// Check if the effect needs to measure all data it will be applied to before it is applied to parts of it, like a normalizer does.
+	fn needs_analysis(&self) -> bool {
+		(self.maximize_target_volume.is_some() || self.loudness_target.is_some()) && self.measured_level.is_none()
+	}
 
-		if let Some(target_volume) = self.maximize_target_volume {
-			let mut max:f32 = 0.0;
-			for sample in data.iter().flatten() {
-				let sample_abs:f32 = sample.abs();
-				if sample_abs > max {
-					max = sample_abs;
-				}
-			}
-			multiplier = 1.0 / max * target_volume;
-		}
-		multiplier *= self.multiplier;
+	/// Measure all data the effect will be applied to, so every part of the data gets the same multiplier. The level is stored instead of the multiplier, so changing the target afterwards still applies.
+	fn analyze(&mut self, data:&[Vec<f32>], sample_rate:u32, _channel_count:usize) {
+		self.measured_level = self.measure_level(data, sample_rate);
+	}
+
+	/// Apply the effect to the given buffer.
+	fn apply_to(&mut self, data:&mut Vec<Vec<f32>>, sample_rate:&mut u32, _channel_count:&mut usize) {
+		let multiplier:f32 = self.target_multiplier(self.measured_level.or_else(|| self.measure_level(data, *sample_rate))) * self.multiplier;
 		if multiplier != 1.0 {
 			data.iter_mut().for_each(|channel| channel.iter_mut().for_each(|sample| *sample *= multiplier));
 		}
@@ -90,17 +145,23 @@ impl AudioEffect for VolumeAmplifier {
 	fn combine(&self, other:&dyn AudioEffect) -> Option<Box<dyn AudioEffect>> {
 		if let Some(other) = other.as_any().downcast_ref::<VolumeAmplifier>() {
 			Some(Box::new(
-				if let Some(other_maximize_target) = other.maximize_target_volume {
+				if other.maximize_target_volume.is_some() || other.loudness_target.is_some() {
 					VolumeAmplifier {
 						id: create_effect_id(),
-						maximize_target_volume: Some(other_maximize_target),
-						multiplier: other.multiplier
+						maximize_target_volume: other.maximize_target_volume,
+						loudness_target: other.loudness_target,
+						multiplier: other.multiplier,
+
+						measured_level: None
 					}
 				} else {
 					VolumeAmplifier {
 						id: create_effect_id(),
 						maximize_target_volume: self.maximize_target_volume,
-						multiplier: self.multiplier * other.multiplier
+						loudness_target: self.loudness_target,
+						multiplier: self.multiplier * other.multiplier,
+
+						measured_level: self.measured_level
 					}
 				}
 			))
@@ -115,29 +176,27 @@ impl AudioEffect for VolumeAmplifier {
 
 	/// Get a list of settings with their names.
 	fn settings(&self) -> Vec<(&str, &f32)> {
+		let mut settings:Vec<(&str, &f32)> = Vec::new();
 		if let Some(maximize_target_volume) = &self.maximize_target_volume {
-			vec![
-				("maximize_target_volume", maximize_target_volume),
-				("multiplier", &self.multiplier)
-			]	
-		} else {
-			vec![
-				("multiplier", &self.multiplier)
-			]
+			settings.push(("maximize_target_volume", maximize_target_volume));
 		}
+		if let Some(loudness_target) = &self.loudness_target {
+			settings.push(("loudness_target", loudness_target));
+		}
+		settings.push(("multiplier", &self.multiplier));
+		settings
 	}
 
 	/// Get a mutable list of settings with their names.
 	fn settings_mut(&mut self) -> Vec<(&str, &mut f32)> {
+		let mut settings:Vec<(&str, &mut f32)> = Vec::new();
 		if let Some(maximize_target_volume) = &mut self.maximize_target_volume {
-			vec![
-				("maximize_target_volume", maximize_target_volume),
-				("multiplier", &mut self.multiplier)
-			]	
-		} else {
-			vec![
-				("multiplier", &mut self.multiplier)
-			]
+			settings.push(("maximize_target_volume", maximize_target_volume));
+		}
+		if let Some(loudness_target) = &mut self.loudness_target {
+			settings.push(("loudness_target", loudness_target));
 		}
+		settings.push(("multiplier", &mut self.multiplier));
+		settings
 	}
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
-	use crate::{ AudioBuffer, AudioEffect, VolumeAmplifier };
+	use crate::{ AudioBuffer, AudioEffect, AudioGenerator, VolumeAmplifier };
+	use std::f32::consts::TAU;
 
 
 
@@ -50,9 +51,61 @@ mod tests {
 		assert_eq!(&buffer.processed_data()[0], &saw_shape);
 	}
 
+	#[test]
+	fn test_effect_volume_loudness_normalizer() {
+		let samples:Vec<f32> = (0..16000 * 2).map(|index| (index as f32 / 16000.0 * 1000.0 * TAU).sin() * 0.5).collect();
+		for target in [-16.0, -23.0] {
+			let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone(), samples.clone()], 16000);
+			buffer.add_effect(VolumeAmplifier::new_loudness_normalizer(target));
+			assert!((buffer.loudness().integrated() - target).abs() < 0.1);
+		}
+
+		// Silence is left as is.
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![vec![0.0; 48000]], 48000);
+		buffer.add_effect(VolumeAmplifier::new_loudness_normalizer(-23.0));
+		assert_eq!(buffer.processed_data()[0], vec![0.0; 48000]);
+	}
+
+	#[test]
+	fn test_effect_volume_loudness_normalizer_streaming() {
+
+		// A quiet part followed by a loud part keeps its dynamics and reaches the target when taken in chunks shorter than the loudness blocks.
+		let mut samples:Vec<f32> = (0..16000 * 2).map(|index| (index as f32 / 16000.0 * 1000.0 * TAU).sin() * 0.01).collect();
+		samples.extend((0..16000 * 3).map(|index| (index as f32 / 16000.0 * 1000.0 * TAU).sin() * 0.1));
+		let mut whole_buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone(), samples.clone()], 16000);
+		whole_buffer.add_effect(VolumeAmplifier::new_loudness_normalizer(-16.0));
+		let whole:Vec<Vec<f32>> = whole_buffer.processed_data().clone();
+
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone(), samples], 16000);
+		buffer.add_effect(VolumeAmplifier::new_loudness_normalizer(-16.0));
+		let mut chunked:Vec<Vec<f32>> = vec![Vec::new(); 2];
+		while !buffer.is_depleted() {
+			for (channel, new_channel) in chunked.iter_mut().zip(buffer.take(1024)) {
+				channel.extend(new_channel);
+			}
+		}
+		assert!(whole[0].iter().zip(&chunked[0]).all(|(whole_sample, chunked_sample)| (whole_sample - chunked_sample).abs() < 0.00001));
+		assert!((AudioBuffer::new(chunked, 16000).loudness().integrated() + 16.0).abs() < 0.1);
+	}
+
+	#[test]
+	fn test_effect_volume_loudness_normalizer_target_change() {
+		let samples:Vec<f32> = (0..16000 * 4).map(|index| (index as f32 / 16000.0 * 1000.0 * TAU).sin() * 0.5).collect();
+		let mut buffer:AudioBuffer = AudioBuffer::new(vec![samples.clone(), samples], 16000);
+		buffer.add_effect(VolumeAmplifier::new_loudness_normalizer(-16.0));
+		let first:Vec<Vec<f32>> = buffer.take(16000 * 2);
+
+		// Changing the target after the first take applies to the next take.
+		buffer.set_effect_setting("volume_amplifier", "loudness_target", -23.0);
+		let second:Vec<Vec<f32>> = buffer.take(16000 * 2);
+		assert!((AudioBuffer::new(first, 16000).loudness().integrated() + 16.0).abs() < 0.1);
+		assert!((AudioBuffer::new(second, 16000).loudness().integrated() + 23.0).abs() < 0.1);
+	}
+
 	#[test]
 	fn test_settings() {
 		VolumeAmplifier::new(1.0).settings_test();
 		VolumeAmplifier::new_maximizer().settings_test();
+		VolumeAmplifier::new_loudness_normalizer(-23.0).settings_test();
 	}
 }
\ No newline at end of file
@@ -5,6 +5,10 @@ mod audio_buffer_u;
 mod audio_effect;
 mod audio_effect_u;
 mod audio_generator;
+mod audio_welder_error;
+mod loudness_meter;
+mod loudness_meter_u;
+mod wav_format;
 
 mod device;
 mod effects;
@@ -14,6 +18,9 @@ pub use arg_traits::*;
 pub use audio_buffer::AudioBuffer;
 pub use audio_effect::AudioEffect;
 pub use audio_generator::AudioGenerator;
+pub use audio_welder_error::AudioWelderError;
+pub use loudness_meter::LoudnessMeter;
 pub use audio_generators::*;
 pub use device::*;
-pub use effects::*;
\ No newline at end of file
+pub use effects::*;
+pub use wav_format::WavFormat;
\ No newline at end of file
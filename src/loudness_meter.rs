@@ -0,0 +1,177 @@
+use crate::{ effects::TruePeakDetector, AudioEffect, BiquadFilter };
+use std::f32::consts::PI;
+
+
+
+const SUB_BLOCK_DURATION:f32 = 0.1;
+const MOMENTARY_SUB_BLOCKS:usize = 4;
+const SHORT_TERM_SUB_BLOCKS:usize = 30;
+const LOUDNESS_OFFSET:f64 = -0.691;
+const ABSOLUTE_GATE:f64 = -70.0;
+const INTEGRATED_RELATIVE_GATE:f64 = -10.0;
+const RANGE_RELATIVE_GATE:f64 = -20.0;
+const RANGE_PERCENTILES:(f64, f64) = (0.1, 0.95);
+
+// Parameters of the K-weighting filter from ITU-R BS.1770, for use at any sample rate.
+const K_SHELF_FREQUENCY:f32 = 1681.9745;
+const K_SHELF_Q:f32 = 0.70717525;
+const K_SHELF_GAIN:f32 = 3.9998438;
+const K_HIGH_PASS_FREQUENCY:f32 = 38.13547;
+const K_HIGH_PASS_Q:f32 = 0.50032705;
+
+
+
+pub struct LoudnessMeter {
+	sample_rate:u32,
+	channel_weights:Vec<f64>,
+	filters:[BiquadFilter; 2],
+	sub_block_len:usize,
+	sub_block_energy:f64,
+	sub_block_progress:usize,
+	sub_block_energies:Vec<f64>,
+	true_peak_detector:TruePeakDetector,
+	true_peak:f32
+}
+impl LoudnessMeter {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Create a new loudness meter following ITU-R BS.1770 and EBU R128 for audio of the given sample rate and channel count. Channels are ordered like in wav files. With 6 or more channels, the LFE channel is ignored and surround channels weigh more.
+	pub fn new(sample_rate:u32, channel_count:usize) -> LoudnessMeter {
+
+		// The poles of the K-weighting shelf lie at its frequency, while those of the biquad shelf lie above its frequency by the square root of its gain.
+		let shelf_frequency:f32 = ((PI * K_SHELF_FREQUENCY / sample_rate as f32).tan() / 10.0_f32.powf(K_SHELF_GAIN / 80.0)).atan() * sample_rate as f32 / PI;
+		LoudnessMeter {
+			sample_rate,
+			channel_weights: (0..channel_count).map(|channel_index| LoudnessMeter::channel_weight(channel_index, channel_count)).collect(),
+			filters: [
+				BiquadFilter::new_high_shelf(shelf_frequency, K_SHELF_Q, K_SHELF_GAIN),
+				BiquadFilter::new_high_pass(K_HIGH_PASS_FREQUENCY, K_HIGH_PASS_Q)
+			],
+			sub_block_len: ((sample_rate as f32 * SUB_BLOCK_DURATION).round() as usize).max(1),
+			sub_block_energy: 0.0,
+			sub_block_progress: 0,
+			sub_block_energies: Vec::new(),
+			true_peak_detector: TruePeakDetector::new(channel_count),
+			true_peak: 0.0
+		}
+	}
+
+	/// Get the weight of the channel at the given index in the total loudness.
+	fn channel_weight(channel_index:usize, channel_count:usize) -> f64 {
+		match (channel_count >= 6, channel_index) {
+			(true, 3) => 0.0,
+			(true, 4..) => 1.41,
+			_ => 1.0
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the loudness in LUFS of the last 400ms. Returns negative infinity when less than 400ms was measured.
+	pub fn momentary(&self) -> f32 {
+		self.recent_loudness(MOMENTARY_SUB_BLOCKS)
+	}
+
+	/// Get the loudness in LUFS of the last 3 seconds. Returns negative infinity when less than 3 seconds was measured.
+	pub fn short_term(&self) -> f32 {
+		self.recent_loudness(SHORT_TERM_SUB_BLOCKS)
+	}
+
+	/// Get the loudness in LUFS of everything measured. Silence and quiet parts well below the average loudness are left out, as described in ITU-R BS.1770. Returns negative infinity when nothing loud enough was measured.
+	pub fn integrated(&self) -> f32 {
+		let energies:Vec<f64> = LoudnessMeter::gated_energies(&self.block_energies(MOMENTARY_SUB_BLOCKS), INTEGRATED_RELATIVE_GATE);
+		if energies.is_empty() {
+			f32::NEG_INFINITY
+		} else {
+			LoudnessMeter::loudness(energies.iter().sum::<f64>() / energies.len() as f64)
+		}
+	}
+
+	/// Get the loudness range in LU of everything measured, as described in EBU Tech 3342. This is the difference between the 10th and 95th percentile of the short-term loudness, leaving out silence and quiet parts.
+	pub fn loudness_range(&self) -> f32 {
+		let mut loudnesses:Vec<f32> = LoudnessMeter::gated_energies(&self.block_energies(SHORT_TERM_SUB_BLOCKS), RANGE_RELATIVE_GATE).into_iter().map(LoudnessMeter::loudness).collect();
+		if loudnesses.is_empty() {
+			return 0.0;
+		}
+		loudnesses.sort_by(|left, right| left.total_cmp(right));
+		let percentile = |percentile:f64| loudnesses[((loudnesses.len() - 1) as f64 * percentile).round() as usize];
+		percentile(RANGE_PERCENTILES.1) - percentile(RANGE_PERCENTILES.0)
+	}
+
+	/// Get the highest peak in dBTP of everything measured, including peaks between samples.
+	pub fn true_peak(&self) -> f32 {
+
+		// Push silence through a copy of the detector to include the last samples.
+		let mut detector:TruePeakDetector = self.true_peak_detector.clone();
+		let channel_count:usize = self.channel_weights.len();
+		let peak:f32 = (0..TruePeakDetector::latency()).fold(self.true_peak, |peak, _| peak.max(detector.push(std::iter::repeat_n(0.0, channel_count))));
+		20.0 * peak.log10()
+	}
+
+	/// Get the loudness of the mean of the last given amount of sub-block energies.
+	fn recent_loudness(&self, sub_block_count:usize) -> f32 {
+		if self.sub_block_energies.len() < sub_block_count {
+			f32::NEG_INFINITY
+		} else {
+			LoudnessMeter::loudness(self.sub_block_energies[self.sub_block_energies.len() - sub_block_count..].iter().sum::<f64>() / sub_block_count as f64)
+		}
+	}
+
+	/// Get the energy of each window of the given amount of sub-blocks, moving a sub-block at a time.
+	fn block_energies(&self, sub_block_count:usize) -> Vec<f64> {
+		self.sub_block_energies.windows(sub_block_count).map(|window| window.iter().sum::<f64>() / sub_block_count as f64).collect()
+	}
+
+	/// Keep the energies above the absolute gate and above the given gate relative to the loudness of the mean of those energies.
+	fn gated_energies(energies:&[f64], relative_gate:f64) -> Vec<f64> {
+		let absolute_threshold:f64 = LoudnessMeter::energy(ABSOLUTE_GATE);
+		let energies:Vec<f64> = energies.iter().filter(|energy| **energy > absolute_threshold).cloned().collect();
+		if energies.is_empty() {
+			return energies;
+		}
+		let relative_threshold:f64 = energies.iter().sum::<f64>() / energies.len() as f64 * 10.0_f64.powf(relative_gate / 10.0);
+		energies.into_iter().filter(|energy| *energy > relative_threshold).collect()
+	}
+
+	/// Convert a mean square energy to a loudness in LUFS.
+	fn loudness(energy:f64) -> f32 {
+		(LOUDNESS_OFFSET + 10.0 * energy.log10()) as f32
+	}
+
+	/// Convert a loudness in LUFS to a mean square energy.
+	fn energy(loudness:f64) -> f64 {
+		10.0_f64.powf((loudness - LOUDNESS_OFFSET) / 10.0)
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Measure the given samples, continuing from the previously measured samples.
+	pub fn add_samples(&mut self, data:&[Vec<f32>]) {
+		for sample_index in 0..data.first().map(|channel| channel.len()).unwrap_or(0) {
+			self.true_peak = self.true_peak.max(self.true_peak_detector.push(data.iter().map(|channel| channel[sample_index])));
+		}
+
+		// Apply the K-weighting filter.
+		let mut data:Vec<Vec<f32>> = data.to_vec();
+		let channel_count:usize = data.len();
+		for filter in &mut self.filters {
+			filter.apply_to(&mut data, &mut self.sample_rate.clone(), &mut channel_count.clone());
+		}
+
+		// Add the weighted energy of all channels to the sub-blocks.
+		for sample_index in 0..data.first().map(|channel| channel.len()).unwrap_or(0) {
+			self.sub_block_energy += data.iter().zip(&self.channel_weights).map(|(channel, weight)| channel[sample_index] as f64 * channel[sample_index] as f64 * weight).sum::<f64>();
+			self.sub_block_progress += 1;
+			if self.sub_block_progress == self.sub_block_len {
+				self.sub_block_energies.push(self.sub_block_energy / self.sub_block_len as f64);
+				self.sub_block_energy = 0.0;
+				self.sub_block_progress = 0;
+			}
+		}
+	}
+}
\ No newline at end of file
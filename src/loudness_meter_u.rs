@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+	use crate::{ effects::test_signals::sine, AudioBuffer, LoudnessMeter };
+	use std::f32::consts::{ FRAC_PI_4, TAU };
+
+
+
+	/// Get the amplitude of a sine wave with the given loudness in dBFS.
+	fn amplitude(level:f32) -> f32 {
+		10.0_f32.powf(level / 20.0)
+	}
+
+
+
+	/* LOUDNESS TESTS */
+
+	#[test]
+	fn test_loudness_meter_reference_tone() {
+
+		// A stereo 1kHz sine at -23 dBFS measures -23 LUFS, as described in EBU Tech 3341.
+		let samples:Vec<f32> = sine(1000.0, amplitude(-23.0), 48000, 48000 * 5);
+		let meter:LoudnessMeter = AudioBuffer::new(vec![samples.clone(), samples], 48000).loudness();
+		assert!((meter.integrated() + 23.0).abs() < 0.1);
+		assert!((meter.momentary() + 23.0).abs() < 0.1);
+		assert!((meter.short_term() + 23.0).abs() < 0.1);
+		assert!(meter.loudness_range() < 0.1);
+		assert!((meter.true_peak() + 23.0).abs() < 0.1);
+	}
+
+	#[test]
+	fn test_loudness_meter_not_enough_data() {
+		let meter:LoudnessMeter = AudioBuffer::new(vec![sine(1000.0, amplitude(-23.0), 48000, 4800)], 48000).loudness();
+		assert_eq!(meter.momentary(), f32::NEG_INFINITY);
+		assert_eq!(meter.short_term(), f32::NEG_INFINITY);
+		assert_eq!(meter.integrated(), f32::NEG_INFINITY);
+		assert_eq!(meter.loudness_range(), 0.0);
+	}
+
+	#[test]
+	fn test_loudness_meter_absolute_gate() {
+
+		// Silence does not lower the integrated loudness.
+		let mut samples:Vec<f32> = sine(1000.0, amplitude(-20.0), 16000, 16000 * 10);
+		samples.extend(vec![0.0; 16000 * 10]);
+		let meter:LoudnessMeter = AudioBuffer::new(vec![samples.clone(), samples], 16000).loudness();
+		assert!((meter.integrated() + 20.0).abs() < 0.2);
+		assert!(meter.momentary() < -70.0);
+	}
+
+	#[test]
+	fn test_loudness_meter_relative_gate() {
+
+		// Parts more than 10 LU below the loudness of the rest are left out, as in the EBU Tech 3341 test case.
+		let mut samples:Vec<f32> = sine(1000.0, amplitude(-36.0), 16000, 16000 * 5);
+		samples.extend(sine(1000.0, amplitude(-23.0), 16000, 16000 * 30));
+		samples.extend(sine(1000.0, amplitude(-36.0), 16000, 16000 * 5));
+		let meter:LoudnessMeter = AudioBuffer::new(vec![samples.clone(), samples], 16000).loudness();
+		assert!((meter.integrated() + 23.0).abs() < 0.2);
+	}
+
+	#[test]
+	fn test_loudness_meter_loudness_range() {
+
+		// Equal parts at -20 and -30 LUFS have a loudness range of 10 LU, as in the EBU Tech 3342 test case.
+		let mut samples:Vec<f32> = sine(1000.0, amplitude(-20.0), 16000, 16000 * 20);
+		samples.extend(sine(1000.0, amplitude(-30.0), 16000, 16000 * 20));
+		let meter:LoudnessMeter = AudioBuffer::new(vec![samples.clone(), samples], 16000).loudness();
+		assert!((meter.loudness_range() - 10.0).abs() < 0.2);
+	}
+
+	#[test]
+	fn test_loudness_meter_true_peak() {
+
+		// A sine at a quarter of the sample rate, sampled at 45 degrees, peaks between the samples.
+		let samples:Vec<f32> = (0..4800).map(|index| (index as f32 * TAU / 4.0 + FRAC_PI_4).sin()).collect();
+		let meter:LoudnessMeter = AudioBuffer::new(vec![samples], 48000).loudness();
+		assert!(meter.true_peak().abs() < 0.2);
+		assert!(meter.true_peak() > 20.0 * FRAC_PI_4.sin().log10() + 1.0);
+	}
+
+	#[test]
+	fn test_loudness_meter_channel_weights() {
+
+		// Surround channels weigh 1.5 dB more and the LFE channel is ignored.
+		let samples:Vec<f32> = sine(1000.0, amplitude(-30.0), 16000, 16000 * 2);
+		let silence:Vec<f32> = vec![0.0; samples.len()];
+		let front:LoudnessMeter = AudioBuffer::new(vec![samples.clone(), silence.clone(), silence.clone(), silence.clone(), silence.clone(), silence.clone()], 16000).loudness();
+		let surround:LoudnessMeter = AudioBuffer::new(vec![silence.clone(), silence.clone(), silence.clone(), silence.clone(), samples.clone(), silence.clone()], 16000).loudness();
+		let lfe:LoudnessMeter = AudioBuffer::new(vec![silence.clone(), silence.clone(), silence.clone(), samples, silence.clone(), silence], 16000).loudness();
+		assert!((surround.integrated() - front.integrated() - 1.5).abs() < 0.05);
+		assert_eq!(lfe.integrated(), f32::NEG_INFINITY);
+	}
+
+
+
+	/* STREAMING TESTS */
+
+	#[test]
+	fn test_loudness_meter_chunked() {
+		let samples:Vec<f32> = (0..16000 * 5).map(|index| (index as f32 * 0.05).sin() * (index as f32 * 0.0001).sin() * 0.5).collect();
+		let mut whole:LoudnessMeter = LoudnessMeter::new(16000, 1);
+		whole.add_samples(std::slice::from_ref(&samples));
+		let mut chunked:LoudnessMeter = LoudnessMeter::new(16000, 1);
+		for chunk in samples.chunks(1234) {
+			chunked.add_samples(&[chunk.to_vec()]);
+		}
+		assert!((whole.integrated() - chunked.integrated()).abs() < 0.0001);
+		assert!((whole.short_term() - chunked.short_term()).abs() < 0.0001);
+		assert!((whole.loudness_range() - chunked.loudness_range()).abs() < 0.0001);
+		assert_eq!(whole.true_peak(), chunked.true_peak());
+	}
+}
\ No newline at end of file
@@ -0,0 +1,75 @@
+use hound::{ SampleFormat, WavSpec };
+
+
+
+/// The sample format of a wav file, as integer PCM of the given bit depth or 32-bit float.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WavFormat { Int8, Int16, Int24, Int32, Float32 }
+impl WavFormat {
+
+	/* CONSTRUCTOR METHODS */
+
+	/// Get the format matching the given wav spec. Returns None if the format is not supported.
+	pub(crate) fn from_spec(spec:&WavSpec) -> Option<WavFormat> {
+		match (spec.sample_format, spec.bits_per_sample) {
+			(SampleFormat::Int, 8) => Some(WavFormat::Int8),
+			(SampleFormat::Int, 16) => Some(WavFormat::Int16),
+			(SampleFormat::Int, 24) => Some(WavFormat::Int24),
+			(SampleFormat::Int, 32) => Some(WavFormat::Int32),
+			(SampleFormat::Float, 32) => Some(WavFormat::Float32),
+			_ => None
+		}
+	}
+
+
+
+	/* PROPERTY GETTER METHODS */
+
+	/// Get the amount of bits used to store a single sample.
+	pub fn bits_per_sample(&self) -> u16 {
+		match self {
+			WavFormat::Int8 => 8,
+			WavFormat::Int16 => 16,
+			WavFormat::Int24 => 24,
+			WavFormat::Int32 | WavFormat::Float32 => 32
+		}
+	}
+
+	/// Get the hound sample format this format is stored as.
+	pub(crate) fn sample_format(&self) -> SampleFormat {
+		match self {
+			WavFormat::Float32 => SampleFormat::Float,
+			_ => SampleFormat::Int
+		}
+	}
+
+	/// Get the value a full-scale sample is stored as. Returns None for float formats.
+	pub(crate) fn int_scale(&self) -> Option<f64> {
+		match self {
+			WavFormat::Float32 => None,
+			_ => Some(((1u64 << (self.bits_per_sample() - 1)) - 1) as f64)
+		}
+	}
+
+	/// Create a wav spec for this format.
+	pub(crate) fn spec(&self, channel_count:usize, sample_rate:u32) -> WavSpec {
+		WavSpec {
+			channels: channel_count as u16,
+			sample_rate,
+			bits_per_sample: self.bits_per_sample(),
+			sample_format: self.sample_format()
+		}
+	}
+
+
+
+	/* USAGE METHODS */
+
+	/// Convert a sample to the integer value stored in a file of this format.
+	pub(crate) fn quantize(&self, sample:f32) -> i32 {
+		match self.int_scale() {
+			Some(scale) => (sample.clamp(-1.0, 1.0) as f64 * scale).round() as i32,
+			None => 0
+		}
+	}
+}
\ No newline at end of file